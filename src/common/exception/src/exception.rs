@@ -14,6 +14,7 @@
 
 #![allow(non_snake_case)]
 
+use std::any::Any;
 use std::backtrace::Backtrace;
 use std::backtrace::BacktraceStatus;
 use std::fmt::Debug;
@@ -83,6 +84,9 @@ pub struct ErrorCode {
     // cause is only used to contain an `anyhow::Error`.
     // TODO: remove `cause` when we completely get rid of `anyhow::Error`.
     cause: Option<Box<dyn std::error::Error + Sync + Send>>,
+    // structured info of the error for the callers in the same process, e.g. the location of a
+    // malformed value, not part of the message and not serialized to other nodes
+    detail: Option<Arc<dyn Any + Send + Sync>>,
     backtrace: Option<ErrorCodeBacktrace>,
 }
 
@@ -104,6 +108,7 @@ impl ErrorCode {
             code: self.code(),
             display_text: format!("{}\n{}", msg.as_ref(), self.display_text),
             cause: self.cause,
+            detail: self.detail,
             backtrace: self.backtrace,
         }
     }
@@ -114,10 +119,22 @@ impl ErrorCode {
             code: self.code(),
             display_text: format!("{}{}", self.display_text, msg.as_ref()),
             cause: self.cause,
+            detail: self.detail,
             backtrace: self.backtrace,
         }
     }
 
+    /// Attach a structured detail, which can be fetched back with `ErrorCode::detail`.
+    #[must_use]
+    pub fn with_detail<T: Any + Send + Sync>(mut self, detail: T) -> Self {
+        self.detail = Some(Arc::new(detail));
+        self
+    }
+
+    pub fn detail<T: Any>(&self) -> Option<&T> {
+        self.detail.as_ref().and_then(|d| d.downcast_ref::<T>())
+    }
+
     /// Set backtrace info for this error.
     ///
     /// Useful when trying to keep original backtrace
@@ -189,6 +206,7 @@ impl ErrorCode {
             code: 1002,
             display_text: error.to_string(),
             cause: None,
+            detail: None,
             backtrace: Some(ErrorCodeBacktrace::Origin(Arc::new(Backtrace::capture()))),
         }
     }
@@ -203,6 +221,7 @@ impl ErrorCode {
             code,
             display_text,
             cause,
+            detail: None,
             backtrace,
         }
     }
@@ -255,6 +274,8 @@ where E: Display + Send + Sync + 'static
 
 impl Clone for ErrorCode {
     fn clone(&self) -> Self {
-        ErrorCode::create(self.code(), self.message(), None, self.backtrace())
+        let mut error = ErrorCode::create(self.code(), self.message(), None, self.backtrace());
+        error.detail = self.detail.clone();
+        error
     }
 }
@@ -50,6 +50,27 @@ fn test_error_code() {
     assert_eq!(err.code(), ErrorCode::UnknownExceptionCode(),);
 }
 
+#[test]
+fn test_error_code_detail() {
+    use common_exception::exception::*;
+
+    let err = ErrorCode::BadBytes("bad value").with_detail((3usize, "col".to_string()));
+    assert_eq!(err.message(), "bad value");
+    assert_eq!(
+        err.detail::<(usize, String)>(),
+        Some(&(3, "col".to_string()))
+    );
+    assert_eq!(err.detail::<String>(), None);
+
+    // kept through the messages added and the clones
+    let err = err.add_message("while loading").clone();
+    assert_eq!(
+        err.detail::<(usize, String)>(),
+        Some(&(3, "col".to_string()))
+    );
+    assert_eq!(ErrorCode::BadBytes("bad value").detail::<String>(), None);
+}
+
 #[test]
 fn test_derive_from_std_error() {
     use common_exception::exception::ErrorCode;
@@ -17,6 +17,7 @@ use chrono::Datelike;
 use chrono::Duration;
 use chrono::FixedOffset;
 use chrono::NaiveDate;
+use chrono::NaiveDateTime;
 use chrono::Offset;
 use chrono::TimeZone;
 use chrono_tz::Tz;
@@ -30,6 +31,10 @@ use super::BufferReadExt;
 pub trait BufferReadDateTimeExt: BufferRead {
     fn read_date_text(&mut self, tz: &Tz) -> Result<NaiveDate>;
     fn read_timestamp_text(&mut self, tz: &Tz) -> Result<DateTime<Tz>>;
+    /// Read the rest of the field as a date of the strftime-style `format`.
+    fn read_date_text_with_format(&mut self, format: &str) -> Result<NaiveDate>;
+    /// Read the rest of the field as a timestamp of the strftime-style `format`, in `tz`.
+    fn read_timestamp_text_with_format(&mut self, format: &str, tz: &Tz) -> Result<DateTime<Tz>>;
     fn parse_time_offset(
         &mut self,
         tz: &Tz,
@@ -50,6 +55,29 @@ where R: BufferRead
         self.read_timestamp_text(tz).map(|dt| dt.naive_utc().date())
     }
 
+    fn read_date_text_with_format(&mut self, format: &str) -> Result<NaiveDate> {
+        let text = read_field_text(self)?;
+        NaiveDate::parse_from_str(&text, format).map_err_to_code(ErrorCode::BadBytes, || {
+            format!("cannot parse date '{}' with format '{}'", text, format)
+        })
+    }
+
+    fn read_timestamp_text_with_format(&mut self, format: &str, tz: &Tz) -> Result<DateTime<Tz>> {
+        let text = read_field_text(self)?;
+        let dt = NaiveDateTime::parse_from_str(&text, format)
+            .map_err_to_code(ErrorCode::BadBytes, || {
+                format!("cannot parse timestamp '{}' with format '{}'", text, format)
+            })?;
+        // the earlier one of an ambiguous local time
+        tz.from_local_datetime(&dt).earliest().ok_or_else(|| {
+            ErrorCode::BadBytes(format!(
+                "timestamp '{}' does not exist in {}",
+                text,
+                tz.name()
+            ))
+        })
+    }
+
     fn read_timestamp_text(&mut self, tz: &Tz) -> Result<DateTime<Tz>> {
         // Date Part YYYY-MM-DD
         let mut buf = vec![0; DATE_LEN];
@@ -242,3 +270,12 @@ where R: BufferRead
         }
     }
 }
+
+// a field ends at a tab or a newline, the trailing spaces are not part of the value
+fn read_field_text<R: BufferRead>(reader: &mut R) -> Result<String> {
+    let mut buf = vec![];
+    reader.keep_read(&mut buf, |b| b != b'\t' && b != b'\n')?;
+    let len = buf.len() - buf.iter().rev().take_while(|b| **b == b' ').count();
+    buf.truncate(len);
+    String::from_utf8(buf).map_err_to_code(ErrorCode::BadBytes, || "cannot convert value to utf8")
+}
@@ -22,6 +22,13 @@ use super::BufferReadExt;
 
 pub trait BufferReadNumberExt: BufferRead {
     fn read_int_text<T: FromLexical>(&mut self) -> Result<T>;
+    /// Saturate to `bounds`, i.e. `(min, max)` of the type, instead of an error on overflow.
+    /// A leading `+` and `-0` (0 for unsigned types too) are errors unless `lenient_sign`.
+    fn read_int_text_saturating<T: FromLexical>(
+        &mut self,
+        bounds: Option<(T, T)>,
+        lenient_sign: bool,
+    ) -> Result<T>;
     fn read_float_text<T: FromLexical>(&mut self) -> Result<T>;
 }
 
@@ -29,6 +36,14 @@ impl<R> BufferReadNumberExt for R
 where R: BufferRead
 {
     fn read_int_text<T: FromLexical>(&mut self) -> Result<T> {
+        self.read_int_text_saturating(None, true)
+    }
+
+    fn read_int_text_saturating<T: FromLexical>(
+        &mut self,
+        bounds: Option<(T, T)>,
+        lenient_sign: bool,
+    ) -> Result<T> {
         // TODO: reuse the buf
         let mut buf = Vec::with_capacity(8);
         let mut has_point = false;
@@ -77,10 +92,32 @@ where R: BufferRead
             let _ = self.ignores(|f| (b'0'..=b'9').contains(&f))?;
         }
 
+        let negative_zero = buf.len() > 1 && buf[0] == b'-' && buf[1..].iter().all(|b| *b == b'0');
+        if !lenient_sign && (negative_zero || buf.first() == Some(&b'+')) {
+            return Err(ErrorCode::BadBytes(format!(
+                "sign of value {} is not allowed",
+                String::from_utf8_lossy(&buf)
+            )));
+        }
+        if negative_zero {
+            // the unsigned types reject the sign
+            buf.remove(0);
+        }
+
         match buf.is_empty() {
             true => Ok(T::default()),
             false => match FromLexical::from_lexical(buf.as_slice()) {
                 Ok(value) => Ok(value),
+                Err(cause @ lexical_core::Error::Overflow(_))
+                | Err(cause @ lexical_core::Error::Underflow(_)) => match bounds {
+                    Some((_, max)) if matches!(cause, lexical_core::Error::Overflow(_)) => Ok(max),
+                    Some((min, _)) => Ok(min),
+                    None => Err(ErrorCode::Overflow(format!(
+                        "value {} out of range for type {}",
+                        String::from_utf8_lossy(&buf),
+                        std::any::type_name::<T>()
+                    ))),
+                },
                 Err(cause) => Err(ErrorCode::BadBytes(format!(
                     "Cannot parse value:{:?} to number type, cause: {:?}",
                     String::from_utf8(buf),
@@ -12,20 +12,179 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use chrono_tz::Tz;
 
+/// What an unquoted empty field is read as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyFieldAs {
+    // the default value of the column, which is NULL for nullable columns
+    Default,
+    // NULL, an error for non-nullable columns
+    Null,
+    // an empty string for string columns, the default value for others
+    EmptyString,
+}
+
+/// How the values of string columns are transformed during load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseTransform {
+    None,
+    Upper,
+    Lower,
+}
+
+/// How the values of string columns are encoded in text files, binary values are often not
+/// stored as is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryEncoding {
+    Raw,
+    Hex,
+    Base64,
+}
+
+/// How the values of timestamp columns are written in text files, as datetime strings, or as
+/// integers of the time since the Unix epoch in a unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampUnit {
+    Datetime,
+    EpochSeconds,
+    EpochMillis,
+    EpochMicros,
+}
+
+impl TimestampUnit {
+    /// The microseconds of an epoch integer in the unit, None for `Datetime`. An epoch is an
+    /// instant, the timezone is not applied.
+    pub fn epoch_to_micros(&self, data: &[u8]) -> Option<Result<i64, String>> {
+        let (unit, micros_per_unit) = match self {
+            TimestampUnit::Datetime => return None,
+            TimestampUnit::EpochSeconds => ("seconds", 1_000_000),
+            TimestampUnit::EpochMillis => ("milliseconds", 1_000),
+            TimestampUnit::EpochMicros => ("microseconds", 1),
+        };
+        let value = std::str::from_utf8(data)
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .ok_or_else(|| format!("expect an integer of {} since the Unix epoch", unit))
+            .and_then(|v| {
+                v.checked_mul(micros_per_unit)
+                    .ok_or_else(|| format!("epoch {} out of range", unit))
+            });
+        Some(value)
+    }
+}
+
+/// The byte range of a column in the records of fixed-width files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedWidthColumn {
+    // 0-based
+    pub start: usize,
+    pub len: usize,
+    // the padding trimmed from the field, b' ' or b'0'
+    pub pad: u8,
+}
+
+/// Which side of a field its padding is stripped from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadSide {
+    Left,
+    Right,
+    Both,
+}
+
+/// The padding stripped from the fields of a column, e.g. the leading zeros of `00042`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnPadding {
+    pub pad: u8,
+    pub side: PadSide,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FormatSettings {
     pub record_delimiter: Vec<u8>,
     pub field_delimiter: Vec<u8>,
     pub empty_as_default: bool,
+    pub empty_field_as: EmptyFieldAs,
     pub timezone: Tz,
-    pub true_bytes: Vec<u8>,
-    pub false_bytes: Vec<u8>,
+    // strftime-style formats of date and timestamp input, None for the built-in parser
+    pub date_format: Option<String>,
+    pub timestamp_format: Option<String>,
+    // the first literal is written for output, any of them is accepted for input
+    pub true_bytes: Vec<Vec<u8>>,
+    pub false_bytes: Vec<Vec<u8>>,
+    pub bool_case_insensitive: bool,
+    // extra literals of NaN and infinity for float columns on input, e.g. `1.#INF`
+    pub nan_literals: Vec<Vec<u8>>,
+    pub inf_literals: Vec<Vec<u8>>,
     pub null_bytes: Vec<u8>,
+    // column index -> extra null values of the column, checked before null_bytes
+    pub column_null_bytes: HashMap<usize, Vec<Vec<u8>>>,
+    // extra null values of all nullable columns, only for unquoted fields, e.g. `null`
+    pub null_values: Vec<Vec<u8>>,
+    pub null_values_case_sensitive: bool,
+    // column index -> the literal read for empty fields, instead of the default value of the type
+    pub column_defaults: HashMap<usize, Vec<u8>>,
+    // column index -> the padding stripped from the fields of the column
+    pub column_padding: HashMap<usize, ColumnPadding>,
     pub nan_bytes: Vec<u8>,
     pub inf_bytes: Vec<u8>,
-    pub quote_char: u8,
+    // None means fields are never quoted
+    pub quote_char: Option<u8>,
+    // also quote fields besides quote_char, a quoted field is closed by the char opening it
+    pub extra_quote_chars: Vec<u8>,
+    pub escape_char: Option<u8>,
+    // a doubled quote char in a quoted field means a quote char
+    pub double_quote: bool,
+    // a quote in a quoted field not followed by a delimiter is data, e.g. `"3" pipe"`,
+    // instead of closing the field
+    pub lenient_quotes: bool,
+    // a run of field delimiters separates two fields, e.g. the spaces of whitespace aligned
+    // columns, and the ones at the start or end of a record are ignored
+    pub merge_consecutive_delimiters: bool,
+    // whitespace trimmed around unquoted string fields, other types always tolerate it
+    pub trim_leading_spaces: bool,
+    pub trim_trailing_spaces: bool,
+    // lines starting with it are skipped, e.g. `#` or `--`, empty means none
+    pub comment_token: Vec<u8>,
+    // the label of the encoding of the input, transcoded to UTF-8 before split into records
+    pub encoding: String,
+    // the comment token must be the very first bytes of the line, not after blanks
+    pub comment_requires_line_start: bool,
+    // stripped from numeric fields, e.g. `,` of `1,234,567.89`
+    pub numeric_group_separator: Option<u8>,
+    // replaced by `.` in float fields, e.g. `,` of `1.234,56`, None means `.`
+    pub decimal_separator: Option<u8>,
+    pub allow_extra_columns: bool,
+    pub allow_missing_columns: bool,
+    // ignore the data of the field after a trailing field delimiter, e.g. `1,2,x` of 2 columns
+    pub ignore_trailing_field: bool,
+    // drop the records of only whitespace, empty lines are always dropped
+    pub skip_blank_lines: bool,
+    // drop the records of only empty unquoted fields, e.g. `,,,`, instead of reading defaults
+    pub skip_empty_records: bool,
+    // saturate the integers out of the range of the type, instead of an error
+    pub saturate_integer_overflow: bool,
+    // accept integers like `+5` and `-0`
+    pub lenient_sign: bool,
+    // schema inference keeps the numbers with leading zeros as strings, e.g. zip codes
+    pub preserve_leading_zeros: bool,
+    // names of the columns of numeric codes, e.g. account numbers, which are never inferred
+    // as numbers and never have their padding stripped
+    pub preserve_string_numerics: Vec<String>,
+    // 0 means unlimited
+    pub max_field_bytes: usize,
+    // a field containing any of them is an error, e.g. NUL of binary junk
+    pub rejected_control_chars: Vec<u8>,
+    // applied to the non-null values of string columns
+    pub case_transform: CaseTransform,
+    pub binary_encoding: BinaryEncoding,
+    pub timestamp_unit: TimestampUnit,
+    // for fixed-width files only
+    pub fixed_width_columns: Vec<FixedWidthColumn>,
+    // read the fields beyond the end of a short record as empty
+    pub allow_short_records: bool,
 
     pub csv_null_bytes: Vec<u8>,
     pub tsv_null_bytes: Vec<u8>,
@@ -41,10 +200,21 @@ impl Default for FormatSettings {
             record_delimiter: vec![b'\n'],
             field_delimiter: vec![b','],
             empty_as_default: true,
+            empty_field_as: EmptyFieldAs::Default,
             timezone: "UTC".parse::<Tz>().unwrap(),
-            true_bytes: vec![b'1'],
-            false_bytes: vec![b'0'],
+            date_format: None,
+            timestamp_format: None,
+            true_bytes: vec![vec![b'1']],
+            false_bytes: vec![vec![b'0']],
+            bool_case_insensitive: false,
+            nan_literals: vec![],
+            inf_literals: vec![],
             null_bytes: vec![b'N', b'U', b'L', b'L'],
+            column_null_bytes: HashMap::new(),
+            null_values: vec![],
+            null_values_case_sensitive: false,
+            column_defaults: HashMap::new(),
+            column_padding: HashMap::new(),
             nan_bytes: vec![b'N', b'a', b'N'],
             inf_bytes: vec![b'i', b'n', b'f'],
             csv_null_bytes: vec![b'\\', b'N'],
@@ -52,7 +222,35 @@ impl Default for FormatSettings {
             json_quote_denormals: false,
             json_escape_forward_slashes: true,
             ident_case_sensitive: false,
-            quote_char: b'\'',
+            quote_char: Some(b'\''),
+            extra_quote_chars: vec![],
+            escape_char: None,
+            comment_token: vec![],
+            encoding: "utf-8".to_string(),
+            comment_requires_line_start: false,
+            numeric_group_separator: None,
+            decimal_separator: None,
+            allow_extra_columns: false,
+            allow_missing_columns: false,
+            ignore_trailing_field: false,
+            skip_blank_lines: false,
+            skip_empty_records: false,
+            saturate_integer_overflow: false,
+            lenient_sign: true,
+            preserve_leading_zeros: false,
+            preserve_string_numerics: vec![],
+            max_field_bytes: 0,
+            rejected_control_chars: vec![],
+            case_transform: CaseTransform::None,
+            binary_encoding: BinaryEncoding::Raw,
+            timestamp_unit: TimestampUnit::Datetime,
+            fixed_width_columns: vec![],
+            allow_short_records: false,
+            double_quote: true,
+            lenient_quotes: false,
+            merge_consecutive_delimiters: false,
+            trim_leading_spaces: true,
+            trim_trailing_spaces: true,
         }
     }
 }
@@ -29,7 +29,14 @@ pub use crate::buffer::CheckpointRead;
 pub use crate::buffer::MemoryReader;
 pub use crate::buffer::NestedCheckpointReader;
 pub use crate::file_split::*;
+pub use crate::format_settings::BinaryEncoding;
+pub use crate::format_settings::CaseTransform;
+pub use crate::format_settings::ColumnPadding;
+pub use crate::format_settings::EmptyFieldAs;
+pub use crate::format_settings::FixedWidthColumn;
 pub use crate::format_settings::FormatSettings;
+pub use crate::format_settings::PadSide;
+pub use crate::format_settings::TimestampUnit;
 pub use crate::options_deserializer::OptionsDeserializer;
 pub use crate::options_deserializer::OptionsDeserializerError;
 pub use crate::position::*;
@@ -89,3 +89,20 @@ fn test_read_datetime_ext() -> Result<()> {
     assert_eq!(res, expected);
     Ok(())
 }
+
+#[test]
+fn test_read_datetime_with_format() -> Result<()> {
+    let tz = Tz::UTC;
+    let mut reader = BufferReader::new("03/04/2022 13:04:05  \t".as_bytes());
+    let time = reader.read_timestamp_text_with_format("%m/%d/%Y %H:%M:%S", &tz)?;
+    assert_eq!(format!("{:?}", time), "2022-03-04T13:04:05UTC");
+    assert!(reader.ignore_byte(b'\t')?);
+
+    let mut reader = BufferReader::new("03/04/2022".as_bytes());
+    let date = reader.read_date_text_with_format("%m/%d/%Y")?;
+    assert_eq!(format!("{:?}", date), "2022-03-04");
+
+    let mut reader = BufferReader::new("2022-03-04".as_bytes());
+    assert!(reader.read_date_text_with_format("%m/%d/%Y").is_err());
+    Ok(())
+}
@@ -52,3 +52,59 @@ fn test_read_number_ext() -> Result<()> {
     assert_eq!(res, expected);
     Ok(())
 }
+
+#[test]
+fn test_read_int_text_overflow() -> Result<()> {
+    let mut reader = BufferReader::new("99999999999".as_bytes());
+    let err = reader.read_int_text::<i32>().unwrap_err();
+    assert!(err.message().contains("out of range for type"));
+
+    let bounds = Some((i32::MIN, i32::MAX));
+    let mut reader = BufferReader::new("99999999999,-99999999999,7".as_bytes());
+    let mut res = vec![];
+    for _ in 0..3 {
+        res.push(reader.read_int_text_saturating::<i32>(bounds, true)?);
+        let _ = reader.ignore_byte(b',')?;
+    }
+    assert_eq!(res, vec![i32::MAX, i32::MIN, 7]);
+    Ok(())
+}
+
+#[test]
+fn test_read_int_text_sign() -> Result<()> {
+    let mut reader =
+        BufferReader::new("+5,-0,+0,-00,2147483647,-2147483648,+2147483647".as_bytes());
+    let mut res = vec![];
+    for _ in 0..7 {
+        res.push(reader.read_int_text_saturating::<i32>(None, true)?);
+        let _ = reader.ignore_byte(b',')?;
+    }
+    assert_eq!(res, vec![5, 0, 0, 0, i32::MAX, i32::MIN, i32::MAX]);
+
+    // `-0` is 0 for the unsigned types too
+    let mut reader = BufferReader::new("+5,-0,4294967295".as_bytes());
+    let mut res = vec![];
+    for _ in 0..3 {
+        res.push(reader.read_int_text_saturating::<u32>(None, true)?);
+        let _ = reader.ignore_byte(b',')?;
+    }
+    assert_eq!(res, vec![5, 0, u32::MAX]);
+    let mut reader = BufferReader::new("-1".as_bytes());
+    assert!(reader.read_int_text_saturating::<u32>(None, true).is_err());
+
+    for v in ["+5", "-0", "+0"] {
+        let mut reader = BufferReader::new(v.as_bytes());
+        let err = reader
+            .read_int_text_saturating::<i64>(None, false)
+            .unwrap_err();
+        assert!(err.message().contains("is not allowed"), "{}", v);
+    }
+    let mut reader = BufferReader::new("-5".as_bytes());
+    assert_eq!(reader.read_int_text_saturating::<i64>(None, false)?, -5);
+    let mut reader = BufferReader::new("-9223372036854775808".as_bytes());
+    assert_eq!(
+        reader.read_int_text_saturating::<i64>(None, false)?,
+        i64::MIN
+    );
+    Ok(())
+}
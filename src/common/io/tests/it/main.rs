@@ -16,4 +16,5 @@ mod binary_read;
 mod binary_write;
 mod buffer;
 mod options_deserializer;
+mod timestamp_unit;
 mod utils;
@@ -0,0 +1,72 @@
+// Copyright 2021 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_io::prelude::TimestampUnit;
+
+#[test]
+fn test_epoch_to_micros() {
+    let micros = |unit: TimestampUnit, data: &str| unit.epoch_to_micros(data.as_bytes());
+
+    assert_eq!(micros(TimestampUnit::Datetime, "1"), None);
+
+    assert_eq!(
+        micros(TimestampUnit::EpochSeconds, "1"),
+        Some(Ok(1_000_000))
+    );
+    assert_eq!(micros(TimestampUnit::EpochMillis, "1"), Some(Ok(1_000)));
+    assert_eq!(micros(TimestampUnit::EpochMicros, "1"), Some(Ok(1)));
+
+    // before the epoch
+    assert_eq!(
+        micros(TimestampUnit::EpochSeconds, "-1"),
+        Some(Ok(-1_000_000))
+    );
+    assert_eq!(micros(TimestampUnit::EpochMillis, "-1"), Some(Ok(-1_000)));
+    assert_eq!(micros(TimestampUnit::EpochMicros, "-1"), Some(Ok(-1)));
+
+    // overflow when converted to microseconds, or as an i64
+    let max = i64::MAX.to_string();
+    assert_eq!(
+        micros(TimestampUnit::EpochSeconds, &max),
+        Some(Err("epoch seconds out of range".to_string()))
+    );
+    assert_eq!(
+        micros(TimestampUnit::EpochMillis, "-9223372036854776"),
+        Some(Err("epoch milliseconds out of range".to_string()))
+    );
+    assert_eq!(micros(TimestampUnit::EpochMicros, &max), Some(Ok(i64::MAX)));
+    assert_eq!(
+        micros(TimestampUnit::EpochMicros, "9223372036854775808"),
+        Some(Err(
+            "expect an integer of microseconds since the Unix epoch".to_string()
+        ))
+    );
+
+    // not an integer
+    for (unit, name) in [
+        (TimestampUnit::EpochSeconds, "seconds"),
+        (TimestampUnit::EpochMillis, "milliseconds"),
+        (TimestampUnit::EpochMicros, "microseconds"),
+    ] {
+        for data in ["1.5", "1e3", "2023-01-01", ""] {
+            assert_eq!(
+                micros(unit, data),
+                Some(Err(format!(
+                    "expect an integer of {} since the Unix epoch",
+                    name
+                )))
+            );
+        }
+    }
+}
@@ -314,7 +314,17 @@ impl FromToProto for mt::StageFileFormatType {
     where Self: Sized {
         match p {
             pb::user_stage_info::StageFileFormatType::Csv => Ok(mt::StageFileFormatType::Csv),
+            pb::user_stage_info::StageFileFormatType::CsvWithTypes => {
+                Ok(mt::StageFileFormatType::CsvWithTypes)
+            }
             pb::user_stage_info::StageFileFormatType::Tsv => Ok(mt::StageFileFormatType::Tsv),
+            pb::user_stage_info::StageFileFormatType::TsvWithNames => {
+                Ok(mt::StageFileFormatType::TsvWithNames)
+            }
+            pb::user_stage_info::StageFileFormatType::Psv => Ok(mt::StageFileFormatType::Psv),
+            pb::user_stage_info::StageFileFormatType::FixedWidth => {
+                Ok(mt::StageFileFormatType::FixedWidth)
+            }
             pb::user_stage_info::StageFileFormatType::Json => Ok(mt::StageFileFormatType::Json),
             pb::user_stage_info::StageFileFormatType::NdJson => Ok(mt::StageFileFormatType::NdJson),
             pb::user_stage_info::StageFileFormatType::Avro => Ok(mt::StageFileFormatType::Avro),
@@ -329,7 +339,17 @@ impl FromToProto for mt::StageFileFormatType {
     fn to_pb(&self) -> Result<pb::user_stage_info::StageFileFormatType, Incompatible> {
         match *self {
             mt::StageFileFormatType::Csv => Ok(pb::user_stage_info::StageFileFormatType::Csv),
+            mt::StageFileFormatType::CsvWithTypes => {
+                Ok(pb::user_stage_info::StageFileFormatType::CsvWithTypes)
+            }
             mt::StageFileFormatType::Tsv => Ok(pb::user_stage_info::StageFileFormatType::Tsv),
+            mt::StageFileFormatType::TsvWithNames => {
+                Ok(pb::user_stage_info::StageFileFormatType::TsvWithNames)
+            }
+            mt::StageFileFormatType::Psv => Ok(pb::user_stage_info::StageFileFormatType::Psv),
+            mt::StageFileFormatType::FixedWidth => {
+                Ok(pb::user_stage_info::StageFileFormatType::FixedWidth)
+            }
             mt::StageFileFormatType::Json => Ok(pb::user_stage_info::StageFileFormatType::Json),
             mt::StageFileFormatType::NdJson => Ok(pb::user_stage_info::StageFileFormatType::NdJson),
             mt::StageFileFormatType::Avro => Ok(pb::user_stage_info::StageFileFormatType::Avro),
@@ -366,6 +386,7 @@ impl FromToProto for mt::StageFileCompression {
             }
             pb::user_stage_info::StageFileCompression::None => Ok(mt::StageFileCompression::None),
             pb::user_stage_info::StageFileCompression::Xz => Ok(mt::StageFileCompression::Xz),
+            pb::user_stage_info::StageFileCompression::Lz4 => Ok(mt::StageFileCompression::Lz4),
         }
     }
 
@@ -390,6 +411,7 @@ impl FromToProto for mt::StageFileCompression {
             }
             mt::StageFileCompression::None => Ok(pb::user_stage_info::StageFileCompression::None),
             mt::StageFileCompression::Xz => Ok(pb::user_stage_info::StageFileCompression::Xz),
+            mt::StageFileCompression::Lz4 => Ok(pb::user_stage_info::StageFileCompression::Lz4),
         }
     }
 }
@@ -63,6 +63,20 @@ const META_CHANGE_LOG: &[(u64, &str)] = &[
     ),
     (15, "2022-10-12: Remove: precision in TimestampType"),
     (16, "2022-09-29: Add: CopyOptions::split_size"),
+    (17, "2022-10-14: Add: user.proto/StageFileCompression::Lz4"),
+    (18, "2022-10-14: Add: user.proto/StageFileFormatType::Psv"),
+    (
+        19,
+        "2022-10-14: Add: user.proto/StageFileFormatType::FixedWidth",
+    ),
+    (
+        20,
+        "2022-10-14: Add: user.proto/StageFileFormatType::TsvWithNames",
+    ),
+    (
+        21,
+        "2022-10-14: Add: user.proto/StageFileFormatType::CsvWithTypes",
+    ),
 ];
 
 pub const VER: u64 = META_CHANGE_LOG.last().unwrap().0;
@@ -82,6 +82,7 @@ pub enum StageFileCompression {
     Lzo,
     Snappy,
     Xz,
+    Lz4,
     None,
 }
 
@@ -105,8 +106,9 @@ impl FromStr for StageFileCompression {
             "lzo" => Ok(StageFileCompression::Lzo),
             "snappy" => Ok(StageFileCompression::Snappy),
             "xz" => Ok(StageFileCompression::Xz),
+            "lz4" => Ok(StageFileCompression::Lz4),
             "none" => Ok(StageFileCompression::None),
-            _ => Err("Unknown file compression type, must one of { auto | gzip | bz2 | brotli | zstd | deflate | raw_deflate | lzo | snappy | xz | none }"
+            _ => Err("Unknown file compression type, must one of { auto | gzip | bz2 | brotli | zstd | deflate | raw_deflate | lzo | snappy | xz | lz4 | none }"
                          .to_string()),
         }
     }
@@ -115,7 +117,11 @@ impl FromStr for StageFileCompression {
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
 pub enum StageFileFormatType {
     Csv,
+    CsvWithTypes,
     Tsv,
+    TsvWithNames,
+    Psv,
+    FixedWidth,
     Json,
     NdJson,
     Avro,
@@ -135,14 +141,19 @@ impl FromStr for StageFileFormatType {
     fn from_str(s: &str) -> std::result::Result<Self, String> {
         match s.to_uppercase().as_str() {
             "CSV" => Ok(StageFileFormatType::Csv),
+            "CSVWITHTYPES" | "CSVWITHNAMESANDTYPES" => Ok(StageFileFormatType::CsvWithTypes),
             "TSV" | "TABSEPARATED" => Ok(StageFileFormatType::Tsv),
+            "TSVWITHNAMES" | "TABSEPARATEDWITHNAMES" => Ok(StageFileFormatType::TsvWithNames),
+            "PSV" | "PIPESEPARATED" => Ok(StageFileFormatType::Psv),
+            "FIXEDWIDTH" => Ok(StageFileFormatType::FixedWidth),
             "NDJSON" | "JSONEACHROW" => Ok(StageFileFormatType::NdJson),
+            "JSONARRAY" => Ok(StageFileFormatType::Json),
             "PARQUET" => Ok(StageFileFormatType::Parquet),
             "XML" | "ORC" | "AVRO" | "JSON" => Err(format!(
-                "File format type '{s}' not implemented yet', must be one of ( CSV | TSV | NDJSON | PARQUET)"
+                "File format type '{s}' not implemented yet', must be one of ( CSV | CSVWITHTYPES | TSV | TSVWITHNAMES | PSV | FIXEDWIDTH | NDJSON | JSONARRAY | PARQUET)"
             )),
             _ => Err(format!(
-                "Unknown file format type '{s}', must be one of ( CSV | TSV | NDJSON | PARQUET)"
+                "Unknown file format type '{s}', must be one of ( CSV | CSVWITHTYPES | TSV | TSVWITHNAMES | PSV | FIXEDWIDTH | NDJSON | JSONARRAY | PARQUET)"
             )),
         }
     }
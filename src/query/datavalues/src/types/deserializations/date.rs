@@ -81,7 +81,10 @@ where
 
     fn de_whole_text(&mut self, reader: &[u8], format: &FormatSettings) -> Result<()> {
         let mut reader = BufferReader::new(reader);
-        let date = reader.read_date_text(&format.timezone)?;
+        let date = match &format.date_format {
+            Some(date_format) => reader.read_date_text_with_format(date_format)?,
+            None => reader.read_date_text(&format.timezone)?,
+        };
         let days = uniform(date);
         check_date(days.as_i32())?;
         reader.must_eof()?;
@@ -112,7 +115,10 @@ where
         reader: &mut NestedCheckpointReader<R>,
         format: &FormatSettings,
     ) -> Result<()> {
-        let date = reader.read_date_text(&format.timezone)?;
+        let date = match &format.date_format {
+            Some(date_format) => reader.read_date_text_with_format(date_format)?,
+            None => reader.read_date_text(&format.timezone)?,
+        };
         let days = uniform(date);
         check_date(days.as_i32())?;
         self.builder.append_value(days);
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_exception::ErrorCode;
 use common_exception::Result;
 use common_io::prelude::*;
 use enum_dispatch::enum_dispatch;
@@ -71,6 +72,19 @@ pub trait TypeDeserializer: Send + Sync {
         format: &FormatSettings,
     ) -> Result<()>;
 
+    /// Deserialize the text of a whole field borrowed from the batch data, e.g. a CSV field,
+    /// nothing is appended if it fails or the value is not read to its end.
+    /// The types may append the value without the intermediate buffer of `de_text`.
+    fn de_text_borrowed(&mut self, value: &[u8], format: &FormatSettings) -> Result<()> {
+        let mut reader = NestedCheckpointReader::new(value);
+        self.de_text(&mut reader, format)?;
+        if reader.must_eof().is_err() {
+            self.pop_data_value()?;
+            return Err(ErrorCode::BadBytes("bad field end"));
+        }
+        Ok(())
+    }
+
     fn de_text_csv<R: BufferRead>(
         &mut self,
         reader: &mut NestedCheckpointReader<R>,
@@ -166,6 +166,17 @@ impl TypeDeserializer for NullableDeserializer {
         Ok(())
     }
 
+    fn de_text_borrowed(&mut self, value: &[u8], format: &FormatSettings) -> Result<()> {
+        if value.eq_ignore_ascii_case(&format.null_bytes) {
+            self.de_default(format);
+            return Ok(());
+        }
+
+        self.inner.de_text_borrowed(value, format)?;
+        self.bitmap.push(true);
+        Ok(())
+    }
+
     fn de_whole_text(&mut self, reader: &[u8], format: &FormatSettings) -> Result<()> {
         if reader.eq_ignore_ascii_case(&format.null_bytes) {
             self.de_default(format);
@@ -17,6 +17,7 @@ use common_exception::Result;
 use common_io::prelude::*;
 use lexical_core::FromLexical;
 use micromarshal::Unmarshal;
+use num::Bounded;
 
 use crate::prelude::*;
 
@@ -27,7 +28,7 @@ pub struct NumberDeserializer<T: PrimitiveType> {
 impl<T> TypeDeserializer for NumberDeserializer<T>
 where
     T: PrimitiveType,
-    T: Unmarshal<T> + StatBuffer + FromLexical,
+    T: Unmarshal<T> + StatBuffer + FromLexical + Bounded,
 {
     fn memory_size(&self) -> usize {
         self.builder.memory_size()
@@ -80,10 +81,10 @@ where
         false
     }
 
-    fn de_whole_text(&mut self, reader: &[u8], _format: &FormatSettings) -> Result<()> {
+    fn de_whole_text(&mut self, reader: &[u8], format: &FormatSettings) -> Result<()> {
         let mut reader = BufferReader::new(reader);
         let v: T = if !T::FLOATING {
-            reader.read_int_text()
+            reader.read_int_text_saturating(saturating_bounds(format), format.lenient_sign)
         } else {
             reader.read_float_text()
         }?;
@@ -96,10 +97,10 @@ where
     fn de_text<R: BufferRead>(
         &mut self,
         reader: &mut NestedCheckpointReader<R>,
-        _format: &FormatSettings,
+        format: &FormatSettings,
     ) -> Result<()> {
         let v: T = if !T::FLOATING {
-            reader.read_int_text()
+            reader.read_int_text_saturating(saturating_bounds(format), format.lenient_sign)
         } else {
             reader.read_float_text()
         }?;
@@ -140,3 +141,9 @@ where
         self.builder.to_column()
     }
 }
+
+fn saturating_bounds<T: Bounded>(format: &FormatSettings) -> Option<(T, T)> {
+    format
+        .saturate_integer_overflow
+        .then(|| (T::min_value(), T::max_value()))
+}
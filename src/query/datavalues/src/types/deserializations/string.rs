@@ -99,6 +99,22 @@ impl TypeDeserializer for StringDeserializer {
         Ok(())
     }
 
+    fn de_text_borrowed(&mut self, value: &[u8], _format: &FormatSettings) -> Result<()> {
+        // the bytes read_escaped_string_text may change or stop at
+        if !value.iter().any(|b| matches!(b, b'\t' | b'\n' | b'\\')) {
+            self.builder.append_value(value);
+            return Ok(());
+        }
+        self.buffer.clear();
+        let mut reader = NestedCheckpointReader::new(value);
+        reader.read_escaped_string_text(&mut self.buffer)?;
+        if reader.must_eof().is_err() {
+            return Err(ErrorCode::BadBytes("bad field end"));
+        }
+        self.builder.append_value(self.buffer.as_slice());
+        Ok(())
+    }
+
     fn de_text_quoted<R: BufferRead>(
         &mut self,
         reader: &mut NestedCheckpointReader<R>,
@@ -90,7 +90,12 @@ impl TypeDeserializer for TimestampDeserializer {
 
     fn de_whole_text(&mut self, reader: &[u8], format: &FormatSettings) -> Result<()> {
         let mut reader = BufferReader::new(reader);
-        let ts = reader.read_timestamp_text(&format.timezone)?;
+        let ts = match &format.timestamp_format {
+            Some(timestamp_format) => {
+                reader.read_timestamp_text_with_format(timestamp_format, &format.timezone)?
+            }
+            None => reader.read_timestamp_text(&format.timezone)?,
+        };
         let micros = ts.timestamp_micros();
         check_timestamp(micros)?;
         reader.must_eof()?;
@@ -103,7 +108,12 @@ impl TypeDeserializer for TimestampDeserializer {
         reader: &mut NestedCheckpointReader<R>,
         format: &FormatSettings,
     ) -> Result<()> {
-        let ts = reader.read_timestamp_text(&format.timezone)?;
+        let ts = match &format.timestamp_format {
+            Some(timestamp_format) => {
+                reader.read_timestamp_text_with_format(timestamp_format, &format.timezone)?
+            }
+            None => reader.read_timestamp_text(&format.timezone)?,
+        };
         let micros = ts.timestamp_micros();
         check_timestamp(micros)?;
         self.builder.append_value(micros.as_());
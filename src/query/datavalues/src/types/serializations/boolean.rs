@@ -38,12 +38,14 @@ impl<'a> TypeSerializer<'a> for BooleanSerializer {
     }
 
     fn write_field(&self, row_index: usize, buf: &mut Vec<u8>, format: &FormatSettings) {
-        let v = if self.values.get_bit(row_index) {
+        let literals = if self.values.get_bit(row_index) {
             &format.true_bytes
         } else {
             &format.false_bytes
         };
-        buf.extend_from_slice(v);
+        if let Some(v) = literals.first() {
+            buf.extend_from_slice(v);
+        }
     }
 
     fn serialize_json_values(&self, _format: &FormatSettings) -> Result<Vec<Value>> {
@@ -48,3 +48,33 @@ fn test_nullable_deserializer_pop() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn test_deserializer_text_borrowed() -> Result<()> {
+    let format = FormatSettings::default();
+
+    let data_type = NullableType::new_impl(StringType::new_impl());
+    let mut deserializer = data_type.create_deserializer(4);
+    deserializer.de_text_borrowed(b"abc", &format)?;
+    deserializer.de_text_borrowed(b"a\\nb", &format)?;
+    deserializer.de_text_borrowed(b"NULL", &format)?;
+    // nothing is appended if the value is not read to its end
+    assert!(deserializer.de_text_borrowed(b"a\tb", &format).is_err());
+    assert_eq!(deserializer.pop_data_value()?, DataValue::Null);
+    assert_eq!(
+        deserializer.pop_data_value()?,
+        DataValue::String(b"a\nb".to_vec())
+    );
+    assert_eq!(
+        deserializer.pop_data_value()?,
+        DataValue::String(b"abc".to_vec())
+    );
+    assert!(deserializer.pop_data_value().is_err());
+
+    let data_type = Int32Type::new_impl();
+    let mut deserializer = data_type.create_deserializer(2);
+    deserializer.de_text_borrowed(b"12", &format)?;
+    assert!(deserializer.de_text_borrowed(b"12x", &format).is_err());
+    assert_eq!(deserializer.finish_to_column().len(), 1);
+    Ok(())
+}
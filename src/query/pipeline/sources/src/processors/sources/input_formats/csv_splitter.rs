@@ -0,0 +1,827 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::mem;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+use csv_core::ReadRecordResult;
+
+use crate::processors::sources::input_formats::delimiter::RecordDelimiter;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    StartRecord,
+    StartField,
+    InField,
+    InQuotedField,
+    InQuoteInQuotedField,
+    InEscapedQuote,
+    InComment,
+    End,
+}
+
+enum Matched {
+    FieldDelimiter,
+    RecordDelimiter,
+    Prefix,
+    None,
+}
+
+struct Sink<'a> {
+    output: &'a mut [u8],
+    n_out: usize,
+    ends: &'a mut [usize],
+    quoted: &'a mut [bool],
+    n_end: usize,
+}
+
+/// A byte-scanning replacement of `csv_core::Reader`, which also supports the dialects csv-core
+/// can not handle, e.g. field delimiters longer than one byte, or comment lines starting with a
+/// comment token like `--` (after blanks unless `comment_requires_line_start`), and tells whether
+/// each field is quoted.
+///
+/// It follows the contract of `csv_core::Reader::read_record`:
+/// 1. the field ends are relative to the start of the record, even if the record spans many calls.
+/// 2. feeding an empty input means EOF, the pending record (if any) is emitted first.
+/// 3. `quoted` is filled in parallel with `ends`.
+///
+/// Bytes which may be the beginning of a delimiter (or the leading blanks and comment token of a
+/// possible comment line) are held back until confirmed or denied, so the output of one call
+/// may be longer than its input by at most `max_pending()` bytes.
+pub struct CsvSplitter {
+    field_delimiter: Vec<u8>,
+    record_delimiter: RecordDelimiter,
+    quote: Option<u8>,
+    extra_quotes: Vec<u8>,
+    escape: Option<u8>,
+    double_quote: bool,
+    lenient_quotes: bool,
+    merge_delimiters: bool,
+    comment: Vec<u8>,
+    comment_requires_line_start: bool,
+
+    state: State,
+    // the quote opening the current quoted field, which closes it
+    open_quote: u8,
+    pending: Vec<u8>,
+    blanks: Vec<u8>,
+    // the bytes of the comment token matched at the start of the record
+    comment_matched: usize,
+    output_pos: usize,
+    field_quoted: bool,
+}
+
+impl CsvSplitter {
+    pub fn try_create(
+        format_settings: &FormatSettings,
+        record_delimiter: &RecordDelimiter,
+    ) -> Result<Self> {
+        let field_delimiter = &format_settings.field_delimiter[..];
+        let quote = format_settings.quote_char;
+        let escape = format_settings.escape_char;
+        let comment = &format_settings.comment_token;
+        if field_delimiter.is_empty() {
+            return Err(ErrorCode::InvalidArgument(
+                "field_delimiter should not be empty",
+            ));
+        }
+        let record_delimiter_bytes: &[u8] = match record_delimiter {
+            RecordDelimiter::Crlf => b"\r\n",
+            RecordDelimiter::Any(b) => std::slice::from_ref(b),
+            RecordDelimiter::Multi(v) => v,
+            RecordDelimiter::Auto => {
+                return Err(ErrorCode::InvalidArgument(
+                    "record_delimiter 'auto' should be detected before splitting",
+                ));
+            }
+        };
+        for b in quote
+            .iter()
+            .chain(format_settings.extra_quote_chars.iter())
+            .chain(escape.iter())
+            .chain(comment)
+            .chain(record_delimiter_bytes)
+        {
+            if field_delimiter.contains(b) {
+                return Err(ErrorCode::InvalidArgument(format!(
+                    "field_delimiter {:?} should not contain quote, escape, comment or record delimiter",
+                    String::from_utf8_lossy(field_delimiter)
+                )));
+            }
+        }
+        Ok(Self {
+            field_delimiter: field_delimiter.to_vec(),
+            record_delimiter: record_delimiter.clone(),
+            quote,
+            extra_quotes: format_settings.extra_quote_chars.clone(),
+            escape,
+            double_quote: format_settings.double_quote,
+            lenient_quotes: format_settings.lenient_quotes,
+            merge_delimiters: format_settings.merge_consecutive_delimiters,
+            comment: comment.clone(),
+            comment_requires_line_start: format_settings.comment_requires_line_start,
+            state: State::StartRecord,
+            open_quote: quote.unwrap_or_default(),
+            pending: Vec::with_capacity(field_delimiter.len()),
+            blanks: vec![],
+            comment_matched: 0,
+            output_pos: 0,
+            field_quoted: false,
+        })
+    }
+
+    /// A splitter of the same record delimiter, for the field delimiter `auto` once detected.
+    pub fn with_field_delimiter(
+        &self,
+        format_settings: &FormatSettings,
+        field_delimiter: u8,
+    ) -> Result<Self> {
+        let format_settings = FormatSettings {
+            field_delimiter: vec![field_delimiter],
+            ..format_settings.clone()
+        };
+        Self::try_create(&format_settings, &self.record_delimiter)
+    }
+
+    /// Drop the incomplete record, the next input is read from the start of a record.
+    pub fn reset(&mut self) {
+        self.state = State::StartRecord;
+        self.open_quote = self.quote.unwrap_or_default();
+        self.pending.clear();
+        self.blanks.clear();
+        self.comment_matched = 0;
+        self.output_pos = 0;
+        self.field_quoted = false;
+    }
+
+    /// Whether the input so far ends inside a quoted field, e.g. at the end of the file.
+    pub fn in_quoted_field(&self) -> bool {
+        matches!(self.state, State::InQuotedField | State::InEscapedQuote)
+    }
+
+    pub fn max_pending(&self) -> usize {
+        let record_delimiter_len = match &self.record_delimiter {
+            RecordDelimiter::Multi(v) => v.len(),
+            _ => 1,
+        };
+        self.field_delimiter.len().max(record_delimiter_len)
+            + self.blanks.len()
+            + self.comment.len()
+    }
+
+    /// Find the start of the first record after `offset` in `data`, where a worker reading the
+    /// file from `offset` starts parsing, i.e. the end of the first record delimiter at or after
+    /// `offset` which is not in a quoted field. The offset 0 is always a record start.
+    ///
+    /// Whether `offset` is in a quoted field is unknown without parsing from the start of the
+    /// file, so it is guessed by the first quote which can only be an opening or a closing one:
+    /// a quote right after a delimiter (and not right before one) opens a field, a quote right
+    /// before a delimiter (and not right after one) closes a field. Doubled and escaped quotes
+    /// tell nothing. If no quote tells, `offset` is assumed to be out of quoted fields.
+    /// So the guess may be wrong for quoted fields with delimiters next to quotes inside,
+    /// e.g. `"a,""b"`, or for long quoted fields without a telling quote in `data`,
+    /// the caller should fall back to parsing from the start of the file in doubt.
+    /// With `lenient_quotes`, the quotes neither after nor before a delimiter are data.
+    pub fn find_record_start(&self, data: &[u8], offset: usize) -> Option<usize> {
+        if offset == 0 {
+            return Some(0);
+        }
+        let in_quotes = match self.quote {
+            Some(quote) => self.guess_in_quotes(data, offset, quote),
+            None => false,
+        };
+        let mut in_quotes = in_quotes;
+        let mut i = offset;
+        while i < data.len() {
+            let b = data[i];
+            if self.escape.is_some() && self.escape != self.quote && self.escape == Some(b) {
+                i += 2;
+                continue;
+            }
+            if in_quotes {
+                if self.quote == Some(b) {
+                    if self.double_quote && data.get(i + 1) == Some(&b) {
+                        i += 1;
+                    } else if !self.lenient_quotes || self.delimiter_or_end_at(data, i + 1) {
+                        in_quotes = false;
+                    }
+                }
+            } else if self.quote == Some(b)
+                && (!self.lenient_quotes || self.delimiter_ends_at(data, i))
+            {
+                in_quotes = true;
+            } else if let Some(n) = self.record_delimiter_at(data, i) {
+                return Some(i + n);
+            }
+            i += 1;
+        }
+        None
+    }
+
+    fn guess_in_quotes(&self, data: &[u8], offset: usize, quote: u8) -> bool {
+        // the quotes seen from offset
+        let mut num_quotes = 0;
+        let mut i = offset;
+        while i < data.len() {
+            let b = data[i];
+            if self.escape.is_some() && self.escape != self.quote && self.escape == Some(b) {
+                i += 2;
+                continue;
+            }
+            if b == quote {
+                if data.get(i + 1) == Some(&quote) {
+                    // a doubled quote, or an empty quoted field
+                    i += 2;
+                    continue;
+                }
+                let after_delimiter = self.delimiter_ends_at(data, i);
+                let before_delimiter = self.field_delimiter_at(data, i + 1)
+                    || self.record_delimiter_at(data, i + 1).is_some();
+                if after_delimiter && !before_delimiter {
+                    // an opening quote, out of quotes before it
+                    return num_quotes % 2 == 1;
+                } else if before_delimiter && !after_delimiter {
+                    // a closing quote, in quotes before it
+                    return num_quotes % 2 == 0;
+                } else if after_delimiter || !self.lenient_quotes {
+                    num_quotes += 1;
+                }
+            }
+            i += 1;
+        }
+        false
+    }
+
+    fn field_delimiter_at(&self, data: &[u8], i: usize) -> bool {
+        data[i.min(data.len())..].starts_with(&self.field_delimiter)
+    }
+
+    /// whether a field or record delimiter starts at `i`, or `i` is the end of data.
+    fn delimiter_or_end_at(&self, data: &[u8], i: usize) -> bool {
+        i >= data.len()
+            || self.field_delimiter_at(data, i)
+            || self.record_delimiter_at(data, i).is_some()
+    }
+
+    /// the length of the record delimiter at `i`, if any.
+    fn record_delimiter_at(&self, data: &[u8], i: usize) -> Option<usize> {
+        let data = &data[i.min(data.len())..];
+        match &self.record_delimiter {
+            RecordDelimiter::Crlf if data.starts_with(b"\r\n") => Some(2),
+            RecordDelimiter::Multi(v) if data.starts_with(v) => Some(v.len()),
+            RecordDelimiter::Multi(_) => None,
+            _ => match data.first() {
+                Some(b) if self.is_record_delimiter_byte(*b) => Some(1),
+                _ => None,
+            },
+        }
+    }
+
+    /// whether a field or record delimiter ends right before `i`, or `i` is the start of data.
+    fn delimiter_ends_at(&self, data: &[u8], i: usize) -> bool {
+        let head = &data[..i];
+        if head.is_empty() || head.ends_with(&self.field_delimiter) {
+            return true;
+        }
+        match &self.record_delimiter {
+            RecordDelimiter::Multi(v) => head.ends_with(v),
+            _ => self.is_record_delimiter_byte(head[head.len() - 1]),
+        }
+    }
+
+    pub fn read_record(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+        ends: &mut [usize],
+        quoted: &mut [bool],
+    ) -> (ReadRecordResult, usize, usize, usize) {
+        let mut sink = Sink {
+            output,
+            n_out: 0,
+            ends,
+            quoted,
+            n_end: 0,
+        };
+        if input.is_empty() {
+            let res = self.finish(&mut sink);
+            return (res, 0, sink.n_out, sink.n_end);
+        }
+        if self.state == State::End {
+            return (ReadRecordResult::End, 0, 0, 0);
+        }
+        let mut n_in = 0;
+        while n_in < input.len() {
+            if sink.output.len() - sink.n_out <= self.num_held() {
+                return (ReadRecordResult::OutputFull, n_in, sink.n_out, sink.n_end);
+            }
+            if sink.n_end == sink.ends.len() {
+                return (
+                    ReadRecordResult::OutputEndsFull,
+                    n_in,
+                    sink.n_out,
+                    sink.n_end,
+                );
+            }
+            let b = input[n_in];
+            n_in += 1;
+            if self.feed(b, &mut sink) {
+                return (ReadRecordResult::Record, n_in, sink.n_out, sink.n_end);
+            }
+        }
+        (ReadRecordResult::InputEmpty, n_in, sink.n_out, sink.n_end)
+    }
+
+    fn finish(&mut self, sink: &mut Sink) -> ReadRecordResult {
+        if self.state == State::End {
+            return ReadRecordResult::End;
+        }
+        if sink.output.len() - sink.n_out < self.num_held() {
+            return ReadRecordResult::OutputFull;
+        }
+        if sink.n_end == sink.ends.len() {
+            return ReadRecordResult::OutputEndsFull;
+        }
+        let comment_head = self.comment[..mem::take(&mut self.comment_matched)].to_vec();
+        let held = [
+            mem::take(&mut self.blanks),
+            comment_head,
+            mem::take(&mut self.pending),
+        ]
+        .concat();
+        for b in held {
+            self.literal(b, sink);
+        }
+        if self.state == State::StartRecord || self.state == State::InComment {
+            self.state = State::End;
+            ReadRecordResult::End
+        } else if self.merge_delimiters && self.state == State::StartField {
+            // the trailing delimiters are ignored
+            self.end_record();
+            ReadRecordResult::Record
+        } else {
+            self.end_field(sink);
+            self.end_record();
+            ReadRecordResult::Record
+        }
+    }
+
+    /// return true if a record is finished by this byte.
+    fn feed(&mut self, b: u8, sink: &mut Sink) -> bool {
+        if self.state == State::StartRecord && !self.comment.is_empty() && self.pending.is_empty() {
+            if self.comment[self.comment_matched] == b {
+                self.comment_matched += 1;
+                if self.comment_matched == self.comment.len() {
+                    self.comment_matched = 0;
+                    self.blanks.clear();
+                    self.state = State::InComment;
+                }
+                return false;
+            } else if self.comment_matched == 0
+                && !self.comment_requires_line_start
+                && (b == b' ' || b == b'\t')
+                && !self.is_delimiter_start(b)
+            {
+                self.blanks.push(b);
+                return false;
+            }
+            // not a comment line, the bytes held are data
+            let blanks = mem::take(&mut self.blanks);
+            for c in blanks {
+                self.literal(c, sink);
+            }
+            let matched = mem::take(&mut self.comment_matched);
+            if matched > 0 {
+                // the state is not StartRecord after the first byte, so no comment is matched
+                self.literal(self.comment[0], sink);
+                for i in 1..matched {
+                    self.feed(self.comment[i], sink);
+                }
+                return self.feed(b, sink);
+            }
+        }
+        match self.state {
+            State::InComment => {
+                if self.is_comment_end(b) {
+                    self.state = State::StartRecord;
+                }
+                false
+            }
+            State::InQuotedField => {
+                if self.open_quote == b {
+                    self.state = State::InQuoteInQuotedField;
+                } else if self.escape == Some(b) {
+                    self.state = State::InEscapedQuote;
+                } else {
+                    self.push(b, sink);
+                }
+                false
+            }
+            State::InEscapedQuote => {
+                self.push(b, sink);
+                self.state = State::InQuotedField;
+                false
+            }
+            State::InQuoteInQuotedField if self.double_quote && self.open_quote == b => {
+                // RFC4180 doubled quote
+                self.push(b, sink);
+                self.state = State::InQuotedField;
+                false
+            }
+            State::InQuoteInQuotedField if self.lenient_quotes && !self.is_delimiter_start(b) => {
+                // the quote is data, the field is still quoted
+                self.push(self.open_quote, sink);
+                self.state = State::InQuotedField;
+                self.feed(b, sink)
+            }
+            State::InQuoteInQuotedField => {
+                // like csv-core, bytes after the closing quote are taken as data
+                self.state = State::InField;
+                self.feed_unquoted(b, sink)
+            }
+            _ => self.feed_unquoted(b, sink),
+        }
+    }
+
+    fn feed_unquoted(&mut self, b: u8, sink: &mut Sink) -> bool {
+        if self.pending.is_empty() && !self.is_delimiter_start(b) {
+            self.literal(b, sink);
+            return false;
+        }
+        self.pending.push(b);
+        match self.match_pending() {
+            Matched::FieldDelimiter => {
+                self.pending.clear();
+                if self.merge_delimiters
+                    && matches!(self.state, State::StartRecord | State::StartField)
+                {
+                    // the leading delimiters, or one following another, are ignored
+                    return false;
+                }
+                self.end_field(sink);
+                self.state = State::StartField;
+                false
+            }
+            Matched::RecordDelimiter => {
+                self.pending.clear();
+                if self.state == State::StartRecord {
+                    // skip empty lines, the same as csv-core
+                    false
+                } else if self.merge_delimiters && self.state == State::StartField {
+                    // the trailing delimiters are ignored
+                    self.end_record();
+                    true
+                } else {
+                    self.end_field(sink);
+                    self.end_record();
+                    true
+                }
+            }
+            Matched::Prefix => false,
+            Matched::None => {
+                let pending = mem::take(&mut self.pending);
+                self.literal(pending[0], sink);
+                let mut is_record_end = false;
+                for b in &pending[1..] {
+                    is_record_end = self.feed(*b, sink);
+                }
+                is_record_end
+            }
+        }
+    }
+
+    fn match_pending(&self) -> Matched {
+        let pending = &self.pending[..];
+        let (is_record_delimiter, is_record_delimiter_prefix) = match &self.record_delimiter {
+            RecordDelimiter::Multi(v) => (pending == &v[..], v.starts_with(pending)),
+            _ => (
+                pending.len() == 1 && self.is_record_delimiter_byte(pending[0]),
+                false,
+            ),
+        };
+        if pending == self.field_delimiter {
+            Matched::FieldDelimiter
+        } else if is_record_delimiter {
+            Matched::RecordDelimiter
+        } else if self.field_delimiter.starts_with(pending) || is_record_delimiter_prefix {
+            Matched::Prefix
+        } else {
+            Matched::None
+        }
+    }
+
+    /// Only for the single byte record delimiters, i.e. not `RecordDelimiter::Multi`.
+    fn is_record_delimiter_byte(&self, b: u8) -> bool {
+        match &self.record_delimiter {
+            RecordDelimiter::Crlf => b == b'\r' || b == b'\n',
+            RecordDelimiter::Any(v) => b == *v,
+            RecordDelimiter::Multi(_) | RecordDelimiter::Auto => false,
+        }
+    }
+
+    fn is_delimiter_start(&self, b: u8) -> bool {
+        match &self.record_delimiter {
+            RecordDelimiter::Multi(v) => self.field_delimiter[0] == b || v[0] == b,
+            _ => self.field_delimiter[0] == b || self.is_record_delimiter_byte(b),
+        }
+    }
+
+    /// A comment line ends with the record delimiter, the matched bytes of
+    /// `RecordDelimiter::Multi` are kept in `pending`.
+    fn is_comment_end(&mut self, b: u8) -> bool {
+        let delimiter = match &self.record_delimiter {
+            RecordDelimiter::Multi(v) => v,
+            _ => return self.is_record_delimiter_byte(b),
+        };
+        self.pending.push(b);
+        while !delimiter.starts_with(&self.pending) {
+            self.pending.remove(0);
+        }
+        if self.pending.len() == delimiter.len() {
+            self.pending.clear();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn literal(&mut self, b: u8, sink: &mut Sink) {
+        match self.state {
+            State::StartRecord | State::StartField
+                if self.quote == Some(b) || self.extra_quotes.contains(&b) =>
+            {
+                self.open_quote = b;
+                self.state = State::InQuotedField;
+                self.field_quoted = true;
+            }
+            _ => {
+                self.push(b, sink);
+                self.state = State::InField;
+            }
+        }
+    }
+
+    fn push(&mut self, b: u8, sink: &mut Sink) {
+        sink.output[sink.n_out] = b;
+        sink.n_out += 1;
+        self.output_pos += 1;
+    }
+
+    fn end_field(&mut self, sink: &mut Sink) {
+        sink.ends[sink.n_end] = self.output_pos;
+        sink.quoted[sink.n_end] = self.field_quoted;
+        sink.n_end += 1;
+        self.field_quoted = false;
+    }
+
+    fn num_held(&self) -> usize {
+        self.pending.len() + self.blanks.len() + self.comment_matched
+    }
+
+    fn end_record(&mut self) {
+        self.output_pos = 0;
+        self.state = State::StartRecord;
+    }
+}
+
+/// See `CsvSplitter::find_record_start`.
+pub fn find_csv_record_start(
+    data: &[u8],
+    offset: usize,
+    format_settings: &FormatSettings,
+    record_delimiter: &RecordDelimiter,
+) -> Result<Option<usize>> {
+    let splitter = CsvSplitter::try_create(format_settings, record_delimiter)?;
+    Ok(splitter.find_record_start(data, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use common_exception::Result;
+    use common_io::prelude::FormatSettings;
+    use csv_core::ReadRecordResult;
+
+    use super::find_csv_record_start;
+    use super::CsvSplitter;
+    use crate::processors::sources::input_formats::delimiter::RecordDelimiter;
+
+    /// Split the chunks into records of fields with the splitter alone, keeping the incomplete record
+    /// across chunks. The quoted fields are prefixed by `Q:`.
+    fn split_chunks(
+        chunks: &[&[u8]],
+        format_settings: &FormatSettings,
+        record_delimiter: &RecordDelimiter,
+    ) -> Result<Vec<Vec<String>>> {
+        let mut splitter = CsvSplitter::try_create(format_settings, record_delimiter)?;
+        let mut out_tmp = vec![0u8; 1024];
+        let mut field_ends = vec![0usize; 16];
+        let mut field_quoted = vec![false; 16];
+        let mut out = vec![];
+        let mut n_end = 0;
+        let mut records = vec![];
+        // the empty input at last means EOF
+        for chunk in chunks.iter().filter(|c| !c.is_empty()).chain([&&b""[..]]) {
+            let mut buf: &[u8] = chunk;
+            loop {
+                let (result, n_in, n_out, n) = splitter.read_record(
+                    buf,
+                    &mut out_tmp,
+                    &mut field_ends[n_end..],
+                    &mut field_quoted[n_end..],
+                );
+                buf = &buf[n_in..];
+                out.extend_from_slice(&out_tmp[..n_out]);
+                n_end += n;
+                match result {
+                    ReadRecordResult::Record => {
+                        let mut start = 0;
+                        let mut fields = vec![];
+                        for (end, quoted) in field_ends[..n_end].iter().zip(&field_quoted[..n_end])
+                        {
+                            let prefix = if *quoted { "Q:" } else { "" };
+                            fields.push(
+                                prefix.to_string() + &String::from_utf8_lossy(&out[start..*end]),
+                            );
+                            start = *end;
+                        }
+                        records.push(fields);
+                        out.clear();
+                        n_end = 0;
+                        if buf.is_empty() && !chunk.is_empty() {
+                            break;
+                        }
+                    }
+                    ReadRecordResult::InputEmpty if !chunk.is_empty() => break,
+                    ReadRecordResult::InputEmpty => {}
+                    ReadRecordResult::End => return Ok(records),
+                    r => panic!("unexpected {:?}", r),
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    #[test]
+    fn test_split_lenient_quotes() -> Result<()> {
+        let format_settings = FormatSettings {
+            field_delimiter: vec![b','],
+            quote_char: Some(b'"'),
+            lenient_quotes: true,
+            ..Default::default()
+        };
+        let record_delimiter = RecordDelimiter::Crlf;
+        let cases: Vec<(&str, Vec<Vec<&str>>)> = vec![
+            // a quote in an unquoted field
+            ("1,3\" pipe,x\n", vec![vec!["1", "3\" pipe", "x"]]),
+            // a quote in a quoted field not followed by a delimiter
+            ("\"3\" pipe\",x\n", vec![vec!["Q:3\" pipe", "x"]]),
+            ("\"a\"\"b\",\"c\"\n\"d\"\n", vec![
+                vec!["Q:a\"b", "Q:c"],
+                vec!["Q:d"],
+            ]),
+        ];
+        for (data, expected) in cases {
+            let expected: Vec<Vec<String>> = expected
+                .iter()
+                .map(|r| r.iter().map(|f| f.to_string()).collect())
+                .collect();
+            assert_eq!(
+                split_chunks(&[data.as_bytes()], &format_settings, &record_delimiter)?,
+                expected
+            );
+        }
+
+        // the quote in the middle of a field does not open a quoted field
+        let data = "1,3\" a\nb,c\n";
+        assert_eq!(
+            find_csv_record_start(data.as_bytes(), 1, &format_settings, &record_delimiter)?,
+            Some(7)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_merge_consecutive_delimiters() -> Result<()> {
+        let format_settings = FormatSettings {
+            field_delimiter: vec![b' '],
+            quote_char: Some(b'"'),
+            merge_consecutive_delimiters: true,
+            ..Default::default()
+        };
+        let record_delimiter = RecordDelimiter::Crlf;
+        let cases: Vec<(&str, Vec<Vec<&str>>)> = vec![
+            ("1   a  b\n22  \"c d\"  e\n", vec![
+                vec!["1", "a", "b"],
+                vec!["22", "Q:c d", "e"],
+            ]),
+            // the leading and trailing delimiters are ignored
+            ("  1 a  \r\n 2 b ", vec![vec!["1", "a"], vec!["2", "b"]]),
+            // a quoted empty field is still a field
+            ("1  \"\"  b\n", vec![vec!["1", "Q:", "b"]]),
+            // a line of only delimiters is an empty line
+            ("1 a\n   \n2 b\n", vec![vec!["1", "a"], vec!["2", "b"]]),
+        ];
+        for (data, expected) in cases {
+            let expected: Vec<Vec<String>> = expected
+                .iter()
+                .map(|r| r.iter().map(|f| f.to_string()).collect())
+                .collect();
+            assert_eq!(
+                split_chunks(&[data.as_bytes()], &format_settings, &record_delimiter)?,
+                expected
+            );
+            // the same fed byte by byte
+            let chunks: Vec<&[u8]> = data.as_bytes().chunks(1).collect();
+            assert_eq!(
+                split_chunks(&chunks, &format_settings, &record_delimiter)?,
+                expected
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_after_reset() -> Result<()> {
+        let format_settings = FormatSettings {
+            field_delimiter: vec![b','],
+            quote_char: Some(b'"'),
+            ..Default::default()
+        };
+        let mut splitter = CsvSplitter::try_create(&format_settings, &RecordDelimiter::Crlf)?;
+        let mut out = vec![0u8; 64];
+        let mut field_ends = vec![0usize; 8];
+        let mut field_quoted = vec![false; 8];
+
+        // the first file ends in a quoted field
+        let (result, _, _, n_end) =
+            splitter.read_record(b"1,\"ab", &mut out, &mut field_ends, &mut field_quoted);
+        assert_eq!(result, ReadRecordResult::InputEmpty);
+        assert_eq!(n_end, 1);
+        assert!(splitter.in_quoted_field());
+
+        // the next file starts with a new record, not in the quotes of the last one
+        splitter.reset();
+        assert!(!splitter.in_quoted_field());
+        let (result, n_in, n_out, n_end) =
+            splitter.read_record(b"c,d\n", &mut out, &mut field_ends, &mut field_quoted);
+        assert_eq!(result, ReadRecordResult::Record);
+        assert_eq!(n_in, 4);
+        assert_eq!(&out[..n_out], b"cd");
+        assert_eq!(&field_ends[..n_end], &[1, 2]);
+        assert_eq!(&field_quoted[..n_end], &[false, false]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_comment_token() -> Result<()> {
+        let record_delimiter = RecordDelimiter::Crlf;
+        let mut format_settings = FormatSettings {
+            field_delimiter: vec![b','],
+            quote_char: Some(b'"'),
+            comment_token: b"--".to_vec(),
+            ..Default::default()
+        };
+        let cases: Vec<(bool, &str, Vec<Vec<&str>>)> = vec![
+            (false, "--c\n1,2\n", vec![vec!["1", "2"]]),
+            (false, " \t--c,d\n1\n", vec![vec!["1"]]),
+            // the token elsewhere, or a part of it, is data
+            (false, "-1,--2\n-\n", vec![vec!["-1", "--2"], vec!["-"]]),
+            (false, "1\n-", vec![vec!["1"], vec!["-"]]),
+            (true, "--c\n  --d\n", vec![vec!["  --d"]]),
+        ];
+        for (requires_line_start, data, expected) in cases {
+            format_settings.comment_requires_line_start = requires_line_start;
+            let data = data.as_bytes();
+            let expected: Vec<Vec<String>> = expected
+                .iter()
+                .map(|r| r.iter().map(|f| f.to_string()).collect())
+                .collect();
+            // the token may be split across chunks
+            for i in 0..=data.len() {
+                for j in i..=data.len() {
+                    let chunks = [&data[..i], &data[i..j], &data[j..]];
+                    assert_eq!(
+                        split_chunks(&chunks, &format_settings, &record_delimiter)?,
+                        expected,
+                        "chunks {:?}",
+                        chunks
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
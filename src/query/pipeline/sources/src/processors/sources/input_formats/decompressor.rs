@@ -0,0 +1,271 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::collections::VecDeque;
+use std::io::Read;
+use std::io::Write;
+use std::mem;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use flate2::write::MultiGzDecoder;
+use opendal::io_util::CompressAlgorithm;
+use opendal::io_util::DecompressDecoder;
+use opendal::io_util::DecompressState;
+
+use crate::processors::sources::input_formats::input_context::InputContext;
+
+/// Streaming decoder of a compressed file.
+///
+/// The output of one input buffer is handed out in chunks of at most `chunk_size` bytes,
+/// so a single huge frame (e.g. of zstd) is never decompressed into one buffer.
+pub enum Decompressor {
+    Opendal(DecompressDecoder),
+    Gzip(GzipDecoder),
+    Lz4(Lz4FrameDecoder),
+}
+
+impl Decompressor {
+    pub fn try_create(ctx: &InputContext, path: &str) -> Result<Option<Self>> {
+        if ctx.is_lz4_compressed(path) {
+            return Ok(Some(Self::new_lz4()?));
+        }
+        Ok(ctx.get_compression_alg(path)?.map(Self::new_opendal))
+    }
+
+    pub fn new_opendal(alg: CompressAlgorithm) -> Self {
+        match alg {
+            CompressAlgorithm::Gzip => Decompressor::Gzip(GzipDecoder::new()),
+            _ => Decompressor::Opendal(DecompressDecoder::new(alg)),
+        }
+    }
+
+    pub fn new_lz4() -> Result<Self> {
+        Ok(Decompressor::Lz4(Lz4FrameDecoder::try_create()?))
+    }
+
+    pub fn is_done(&mut self) -> bool {
+        match self {
+            Decompressor::Opendal(decoder) => decoder.state() == DecompressState::Done,
+            Decompressor::Gzip(decoder) => decoder.decoder.try_finish().is_ok(),
+            Decompressor::Lz4(decoder) => decoder.is_done(),
+        }
+    }
+
+    pub fn decompress<F>(&mut self, compressed: &[u8], chunk_size: usize, mut f: F) -> Result<()>
+    where F: FnMut(&[u8]) -> Result<()> {
+        let mut buf = vec![0u8; chunk_size];
+        let mut len = 0;
+        match self {
+            Decompressor::Opendal(decoder) => {
+                let mut amt = 0;
+                loop {
+                    match decoder.state() {
+                        DecompressState::Reading => {
+                            if amt == compressed.len() {
+                                break;
+                            }
+                            amt += decoder.fill(&compressed[amt..]);
+                        }
+                        DecompressState::Decoding => {
+                            len += decoder
+                                .decode(&mut buf[len..])
+                                .map_err(invalid_compression_data)?;
+                        }
+                        DecompressState::Flushing => {
+                            len += decoder
+                                .finish(&mut buf[len..])
+                                .map_err(invalid_compression_data)?;
+                        }
+                        DecompressState::Done => break,
+                    }
+                    if len == chunk_size {
+                        f(&buf)?;
+                        len = 0;
+                    }
+                }
+            }
+            Decompressor::Gzip(decoder) => {
+                let mut amt = 0;
+                while amt < compressed.len() {
+                    let n = decoder
+                        .decoder
+                        .write(&compressed[amt..])
+                        .map_err(invalid_compression_data)?;
+                    if n == 0 {
+                        return Err(ErrorCode::InvalidCompressionData(
+                            "compression data invalid: gzip decoder takes no input",
+                        ));
+                    }
+                    amt += n;
+                    decoder.drain(&mut buf, &mut len, &mut f)?;
+                }
+                // the output of the last input is kept by the decoder until flushed
+                decoder.decoder.flush().map_err(invalid_compression_data)?;
+                decoder.drain(&mut buf, &mut len, &mut f)?;
+            }
+            Decompressor::Lz4(decoder) => {
+                decoder.input.push(compressed);
+                loop {
+                    let n = decoder.decode(&mut buf[len..])?;
+                    if n == 0 {
+                        break;
+                    }
+                    len += n;
+                    if len == chunk_size {
+                        f(&buf)?;
+                        len = 0;
+                    }
+                }
+            }
+        }
+        if len > 0 {
+            f(&buf[..len])?;
+        }
+        Ok(())
+    }
+}
+
+/// Decoder of gzip, which goes on with the next member at the end of one, like `gzip -d`,
+/// e.g. for files of concatenated `.gz` files.
+pub struct GzipDecoder {
+    // writes the decompressed data to the inner buffer, a little at a time
+    decoder: MultiGzDecoder<Vec<u8>>,
+}
+
+impl GzipDecoder {
+    fn new() -> Self {
+        Self {
+            decoder: MultiGzDecoder::new(vec![]),
+        }
+    }
+
+    /// Hand out the decompressed data in chunks of `buf.len()` bytes,
+    /// `len` bytes of `buf` are filled already.
+    fn drain<F>(&mut self, buf: &mut [u8], len: &mut usize, f: &mut F) -> Result<()>
+    where F: FnMut(&[u8]) -> Result<()> {
+        let mut out = mem::take(self.decoder.get_mut());
+        let mut pos = 0;
+        while pos < out.len() {
+            let n = (buf.len() - *len).min(out.len() - pos);
+            buf[*len..*len + n].copy_from_slice(&out[pos..pos + n]);
+            *len += n;
+            pos += n;
+            if *len == buf.len() {
+                f(buf)?;
+                *len = 0;
+            }
+        }
+        // reuse the allocation
+        out.clear();
+        *self.decoder.get_mut() = out;
+        Ok(())
+    }
+}
+
+/// Decoder of the lz4 frame format, which is what the `lz4` command line tool writes.
+/// The frames of concatenated `.lz4` files are decoded one after another, like `lz4 -d`.
+pub struct Lz4FrameDecoder {
+    // `lz4::Decoder` pulls the compressed data from `input`, which the data is pushed to.
+    // The Mutex is only to make it Sync, it is accessed through `&mut self` without locking.
+    // None if failed to start the next frame.
+    decoder: Mutex<Option<lz4::Decoder<Lz4Input>>>,
+    input: Lz4Input,
+}
+
+impl Lz4FrameDecoder {
+    pub fn try_create() -> Result<Self> {
+        let input = Lz4Input::default();
+        let decoder = lz4::Decoder::new(input.clone()).map_err(invalid_compression_data)?;
+        Ok(Self {
+            decoder: Mutex::new(Some(decoder)),
+            input,
+        })
+    }
+
+    /// Decode the data pushed into `output`, return 0 once all of it is decoded.
+    fn decode(&mut self, output: &mut [u8]) -> Result<usize> {
+        let decoder = self.decoder.get_mut().expect("must success");
+        loop {
+            let n = match decoder.as_mut() {
+                Some(d) => d.read(output).map_err(invalid_compression_data)?,
+                None => {
+                    return Err(ErrorCode::InvalidCompressionData(
+                        "compression data invalid: fail to start the next lz4 frame",
+                    ));
+                }
+            };
+            if n > 0 || self.input.is_empty() {
+                return Ok(n);
+            }
+            // the frame is ended, the rest of the data is the next frame
+            let (input, res) = decoder.take().expect("must success").finish();
+            res.map_err(invalid_compression_data)?;
+            *decoder = Some(lz4::Decoder::new(input).map_err(invalid_compression_data)?);
+        }
+    }
+
+    /// Whether the data pushed ends at the end of a frame, e.g. not truncated.
+    fn is_done(&mut self) -> bool {
+        if self.input.pulled() == 0 {
+            return true;
+        }
+        let decoder = self.decoder.get_mut().expect("must success");
+        match decoder.take() {
+            Some(d) => {
+                let (input, res) = d.finish();
+                *decoder = lz4::Decoder::new(input).ok();
+                res.is_ok()
+            }
+            None => false,
+        }
+    }
+}
+
+/// The compressed data pushed and not pulled by `lz4::Decoder` yet.
+#[derive(Clone, Default)]
+struct Lz4Input {
+    // (the data, the number of bytes pulled)
+    buffer: Arc<Mutex<(VecDeque<u8>, usize)>>,
+}
+
+impl Lz4Input {
+    fn push(&self, data: &[u8]) {
+        let mut guard = self.buffer.lock().expect("must success");
+        guard.0.extend(data);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.buffer.lock().expect("must success").0.is_empty()
+    }
+
+    fn pulled(&self) -> usize {
+        self.buffer.lock().expect("must success").1
+    }
+}
+
+impl Read for Lz4Input {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut guard = self.buffer.lock().expect("must success");
+        let n = guard.0.read(buf)?;
+        guard.1 += n;
+        Ok(n)
+    }
+}
+
+fn invalid_compression_data(e: std::io::Error) -> ErrorCode {
+    ErrorCode::InvalidCompressionData(format!("compression data invalid: {e}"))
+}
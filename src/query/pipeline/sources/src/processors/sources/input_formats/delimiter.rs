@@ -15,10 +15,17 @@
 use common_exception::ErrorCode;
 use common_exception::Result;
 
-#[derive(Debug)]
+/// The candidates of the field delimiter `auto`, the earlier one wins a tie.
+const FIELD_DELIMITER_CANDIDATES: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+#[derive(Debug, Clone)]
 pub enum RecordDelimiter {
     Crlf,
     Any(u8),
+    // e.g. `\r\n\r\n`, only supported by CSV for now
+    Multi(Vec<u8>),
+    // one of `\n`, `\r\n` and `\r`, detected from the head of the file, only supported by CSV
+    Auto,
 }
 
 impl RecordDelimiter {
@@ -26,8 +33,92 @@ impl RecordDelimiter {
         match self {
             RecordDelimiter::Crlf => b'\n',
             RecordDelimiter::Any(b) => *b,
+            RecordDelimiter::Multi(v) => v[v.len() - 1],
+            RecordDelimiter::Auto => b'\n',
+        }
+    }
+
+    /// Detect the record delimiter by its first occurrence out of quoted fields in `data`.
+    /// Return None if not found, or if `data` ends with `\r` which may be followed by `\n`,
+    /// unless `is_end`.
+    pub fn detect(
+        data: &[u8],
+        quote: Option<u8>,
+        escape: Option<u8>,
+        is_end: bool,
+    ) -> Option<Self> {
+        let mut in_quotes = false;
+        let mut escaped = false;
+        for (i, b) in data.iter().enumerate() {
+            if escaped {
+                escaped = false;
+            } else if in_quotes && escape != quote && Some(*b) == escape {
+                escaped = true;
+            } else if Some(*b) == quote {
+                // a doubled quote in a quoted field toggles twice
+                in_quotes = !in_quotes;
+            } else if !in_quotes && *b == b'\n' {
+                return Some(RecordDelimiter::Any(b'\n'));
+            } else if !in_quotes && *b == b'\r' {
+                return match data.get(i + 1) {
+                    Some(b'\n') => Some(RecordDelimiter::Crlf),
+                    Some(_) => Some(RecordDelimiter::Any(b'\r')),
+                    None if is_end => Some(RecordDelimiter::Any(b'\r')),
+                    None => None,
+                };
+            }
+        }
+        None
+    }
+}
+
+/// Detect the field delimiter `auto` by counting the candidates out of quoted fields in the
+/// first record of `data`, skipping `exclude`, e.g. the decimal separator. The one splitting the
+/// record into `num_fields` fields is preferred, otherwise the most frequent one.
+/// Return None if the first record may be incomplete, unless `is_end`.
+pub fn detect_field_delimiter(
+    data: &[u8],
+    quote: Option<u8>,
+    escape: Option<u8>,
+    record_delimiter_end: u8,
+    num_fields: Option<usize>,
+    exclude: Option<u8>,
+    is_end: bool,
+) -> Option<u8> {
+    let mut counts = [0usize; FIELD_DELIMITER_CANDIDATES.len()];
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut complete = is_end;
+    for b in data {
+        if escaped {
+            escaped = false;
+        } else if in_quotes && escape != quote && Some(*b) == escape {
+            escaped = true;
+        } else if Some(*b) == quote {
+            in_quotes = !in_quotes;
+        } else if !in_quotes && *b == record_delimiter_end {
+            complete = true;
+            break;
+        } else if !in_quotes {
+            if let Some(i) = FIELD_DELIMITER_CANDIDATES.iter().position(|d| d == b) {
+                counts[i] += 1;
+            }
+        }
+    }
+    if !complete {
+        return None;
+    }
+    let candidates = FIELD_DELIMITER_CANDIDATES
+        .into_iter()
+        .zip(counts)
+        .filter(|(d, _)| Some(*d) != exclude);
+    if let Some(n) = num_fields {
+        if let Some((d, _)) = candidates.clone().find(|(_, c)| c + 1 == n) {
+            return Some(d);
         }
     }
+    // max_by_key returns the last of the equal ones
+    candidates.rev().max_by_key(|(_, c)| *c).map(|(d, _)| d)
 }
 
 impl TryFrom<&str> for RecordDelimiter {
@@ -40,9 +131,13 @@ impl TryFrom<&str> for RecordDelimiter {
 impl TryFrom<&[u8]> for RecordDelimiter {
     type Error = ErrorCode;
     fn try_from(s: &[u8]) -> Result<Self> {
+        if s.eq_ignore_ascii_case(b"auto") {
+            return Ok(RecordDelimiter::Auto);
+        }
         match s.len() {
             1 => Ok(RecordDelimiter::Any(s[0])),
             2 if s.eq(b"\r\n") => Ok(RecordDelimiter::Crlf),
+            n if n > 1 => Ok(RecordDelimiter::Multi(s.to_vec())),
             _ => Err(ErrorCode::InvalidArgument(format!(
                 "bad RecordDelimiter: '{:?}'",
                 s
@@ -12,140 +12,274 @@
 //  See the License for the specific language governing permissions and
 //  limitations under the License.
 
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
 use std::mem;
+use std::ops::Range;
 use std::sync::Arc;
 
+use bstr::ByteSlice;
+use common_datablocks::DataBlock;
+use common_datavalues::remove_nullable;
 use common_datavalues::DataSchemaRef;
+use common_datavalues::DataType;
+use common_datavalues::DataTypeImpl;
+use common_datavalues::DataValue;
 use common_datavalues::TypeDeserializer;
+use common_datavalues::TypeDeserializerImpl;
+use common_datavalues::TypeID;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_formats::verbose_string;
+use common_io::prelude::BinaryEncoding;
 use common_io::prelude::BufferReadExt;
+use common_io::prelude::CaseTransform;
+use common_io::prelude::ColumnPadding;
 use common_io::prelude::FormatSettings;
 use common_io::prelude::NestedCheckpointReader;
+use common_io::prelude::PadSide;
+use common_io::prelude::TimestampUnit;
 use common_meta_types::StageFileFormatType;
 use common_settings::Settings;
 use csv_core::ReadRecordResult;
+use encoding_rs::Decoder;
+use encoding_rs::Encoding;
+use encoding_rs::UTF_8;
 
+use crate::processors::sources::input_formats::csv_splitter::CsvSplitter;
+use crate::processors::sources::input_formats::delimiter::detect_field_delimiter;
 use crate::processors::sources::input_formats::delimiter::RecordDelimiter;
 use crate::processors::sources::input_formats::impls::input_format_tsv::format_column_error;
+use crate::processors::sources::input_formats::input_format_text::de_empty_field;
+use crate::processors::sources::input_formats::input_format_text::get_datetime_formats;
+use crate::processors::sources::input_formats::input_format_text::get_empty_field_as;
 use crate::processors::sources::input_formats::input_format_text::get_time_zone;
+use crate::processors::sources::input_formats::input_format_text::parse_column_defaults;
+use crate::processors::sources::input_formats::input_format_text::rollback_row;
 use crate::processors::sources::input_formats::input_format_text::AligningState;
 use crate::processors::sources::input_formats::input_format_text::BlockBuilder;
 use crate::processors::sources::input_formats::input_format_text::InputFormatTextBase;
 use crate::processors::sources::input_formats::input_format_text::RowBatch;
+use crate::processors::sources::input_formats::input_pipeline::BlockBuilderTrait;
+use crate::processors::sources::input_formats::schema_inference::is_string_numeric;
+use crate::processors::sources::input_formats::HeaderMapper;
 use crate::processors::sources::input_formats::InputContext;
+use crate::processors::sources::input_formats::InputErrorMode;
+use crate::processors::sources::input_formats::RowPosition;
 
 pub struct InputFormatCSV {}
 
 impl InputFormatCSV {
-    fn read_row(
-        buf: &[u8],
-        deserializers: &mut [common_datavalues::TypeDeserializerImpl],
-        schema: &DataSchemaRef,
-        field_ends: &[usize],
-        format_settings: &FormatSettings,
-        path: &str,
-        row_index: usize,
-    ) -> Result<()> {
-        let mut field_start = 0;
-        for (c, deserializer) in deserializers.iter_mut().enumerate() {
-            let field_end = field_ends[c];
-            let col_data = &buf[field_start..field_end];
-            let mut reader = NestedCheckpointReader::new(col_data);
-            reader.ignore_white_spaces().expect("must success");
-            if reader.eof().expect("must success") {
-                deserializer.de_default(format_settings);
-            } else {
-                // todo(youngsofun): do not need escape, already done in csv-core
-                if let Err(e) = deserializer.de_text(&mut reader, format_settings) {
-                    let err_msg = format_column_error(schema, c, col_data, &e.message());
-                    return Err(csv_error(&err_msg, path, row_index));
-                };
-                reader.ignore_white_spaces().expect("must success");
-                if reader.must_eof().is_err() {
-                    let err_msg = format_column_error(schema, c, col_data, "bad field end");
-                    return Err(csv_error(&err_msg, path, row_index));
+    /// Align `buf_in` to records, `is_end` means the end of the input, where the pending record
+    /// without a trailing record delimiter is emitted.
+    pub(crate) fn align_records<T: InputFormatTextBase>(
+        state: &mut AligningState<T>,
+        buf_in: &[u8],
+        is_end: bool,
+    ) -> Result<Vec<RowBatch>> {
+        let reader = state.csv_reader.as_mut().expect("must success");
+
+        let transcoded;
+        let buf_in = match reader.decoder.as_mut() {
+            Some(decoder) => {
+                transcoded = transcode(decoder, buf_in)?;
+                &transcoded[..]
+            }
+            None => buf_in,
+        };
+        let without_bom;
+        let buf_in = if reader.bom_checked {
+            buf_in
+        } else {
+            let head_len = reader.head.len() + buf_in.len();
+            match reader.strip_bom(buf_in) {
+                Some(buf) => {
+                    // the offsets count the BOM
+                    state.offset += head_len - buf.len();
+                    without_bom = buf;
+                    &without_bom[..]
                 }
+                None => return Ok(vec![]),
             }
-            field_start = field_end;
-        }
-        Ok(())
-    }
-}
-
-impl InputFormatTextBase for InputFormatCSV {
-    fn format_type() -> StageFileFormatType {
-        StageFileFormatType::Csv
-    }
+        };
+        let detected;
+        let buf_in = if reader.auto_delimiter_head.is_some() {
+            match Self::detect_record_delimiter(state, buf_in, is_end)? {
+                Some(head) => {
+                    detected = head;
+                    &detected[..]
+                }
+                None => return Ok(vec![]),
+            }
+        } else {
+            buf_in
+        };
+        let detected_field_delimiter;
+        let buf_in = if state
+            .csv_reader
+            .as_ref()
+            .expect("must success")
+            .auto_field_delimiter_head
+            .is_some()
+        {
+            match Self::detect_field_delimiter(state, buf_in, is_end)? {
+                Some(head) => {
+                    detected_field_delimiter = head;
+                    &detected_field_delimiter[..]
+                }
+                None => return Ok(vec![]),
+            }
+        } else {
+            buf_in
+        };
 
-    fn get_format_settings(settings: &Arc<Settings>) -> Result<FormatSettings> {
-        let timezone = get_time_zone(settings)?;
-        let quote_char = settings.get_format_quote_char()?.into_bytes();
-        if quote_char.len() != 1 {
-            return Err(ErrorCode::InvalidArgument(
-                "quote_char can only contain one char",
-            ));
+        if state
+            .csv_reader
+            .as_ref()
+            .expect("must success")
+            .single_column
+        {
+            // each line is a field, quotes and field delimiters are data
+            return Ok(state.align_by_record_delimiter(buf_in));
         }
-        Ok(FormatSettings {
-            record_delimiter: settings.get_format_record_delimiter()?.into_bytes(),
-            field_delimiter: settings.get_format_field_delimiter()?.into_bytes(),
-            empty_as_default: settings.get_format_empty_as_default()? > 0,
-            quote_char: quote_char[0],
-            null_bytes: vec![b'\\', b'N'],
-            timezone,
-            ..Default::default()
-        })
+        Self::split_records(state, buf_in, is_end)
     }
 
-    fn default_field_delimiter() -> u8 {
-        b','
+    /// Keep the head of the file until the record delimiter `auto` is detected, then split the
+    /// file by it. Return the head once detected.
+    fn detect_record_delimiter<T: InputFormatTextBase>(
+        state: &mut AligningState<T>,
+        buf: &[u8],
+        is_end: bool,
+    ) -> Result<Option<Vec<u8>>> {
+        let reader = state.csv_reader.as_mut().expect("must success");
+        let head = reader.auto_delimiter_head.as_mut().expect("must success");
+        head.extend_from_slice(buf);
+        let format_settings = &state.ctx.format_settings;
+        let delimiter = match RecordDelimiter::detect(
+            head,
+            format_settings.quote_char,
+            format_settings.escape_char,
+            is_end,
+        ) {
+            Some(delimiter) => delimiter,
+            // a single record without the trailing record delimiter
+            None if is_end => RecordDelimiter::Any(b'\n'),
+            None => {
+                check_record_delimiter(
+                    reader.max_bytes_without_record_delimiter,
+                    head,
+                    state.record_delimiter_end,
+                    &state.path,
+                    state.rows,
+                )?;
+                check_record_size(&state.ctx, head.len(), &state.path, state.rows)?;
+                return Ok(None);
+            }
+        };
+        tracing::debug!("csv aligner: detected record delimiter {:?}", delimiter);
+        reader.reader =
+            CsvRecordReader::try_create(format_settings, &delimiter, reader.track_quoted)?;
+        reader.strip_trailing_cr &= matches!(delimiter, RecordDelimiter::Any(b'\n'));
+        state.record_delimiter_end = delimiter.end();
+        Ok(reader.auto_delimiter_head.take())
     }
 
-    fn deserialize(builder: &mut BlockBuilder<Self>, batch: RowBatch) -> Result<()> {
-        let columns = &mut builder.mutable_columns;
-        let n_column = columns.len();
-        let mut start = 0usize;
-        let start_row = batch.start_row.expect("must success");
-        let mut field_end_idx = 0;
-        for (i, end) in batch.row_ends.iter().enumerate() {
-            let buf = &batch.data[start..*end];
-            Self::read_row(
-                buf,
-                columns,
-                &builder.ctx.schema,
-                &batch.field_ends[field_end_idx..field_end_idx + n_column],
-                &builder.ctx.format_settings,
-                &batch.path,
-                start_row + i,
-            )?;
-            start = *end;
-            field_end_idx += n_column;
-        }
-        Ok(())
+    /// Keep the head of the file until the field delimiter `auto` is detected from its first
+    /// record, then split the file by it. Return the head once detected.
+    fn detect_field_delimiter<T: InputFormatTextBase>(
+        state: &mut AligningState<T>,
+        buf: &[u8],
+        is_end: bool,
+    ) -> Result<Option<Vec<u8>>> {
+        let reader = state.csv_reader.as_mut().expect("must success");
+        let head = reader
+            .auto_field_delimiter_head
+            .as_mut()
+            .expect("must success");
+        head.extend_from_slice(buf);
+        let format_settings = &state.ctx.format_settings;
+        let delimiter = match detect_field_delimiter(
+            head,
+            format_settings.quote_char,
+            format_settings.escape_char,
+            state.record_delimiter_end,
+            Some(state.num_fields),
+            format_settings.decimal_separator,
+            is_end,
+        ) {
+            Some(delimiter) => delimiter,
+            None => {
+                check_record_delimiter(
+                    reader.max_bytes_without_record_delimiter,
+                    head,
+                    state.record_delimiter_end,
+                    &state.path,
+                    state.rows,
+                )?;
+                check_record_size(&state.ctx, head.len(), &state.path, state.rows)?;
+                return Ok(None);
+            }
+        };
+        tracing::debug!(
+            "csv aligner: detected field delimiter {:?} for {}",
+            delimiter as char,
+            state.path
+        );
+        reader.reader = reader
+            .reader
+            .with_field_delimiter(format_settings, delimiter)?;
+        Ok(reader.auto_field_delimiter_head.take())
     }
 
-    fn align(state: &mut AligningState<Self>, buf_in: &[u8]) -> Result<Vec<RowBatch>> {
-        let num_fields = state.num_fields;
+    /// Split `buf_in`, which is transcoded and without the BOM, into records.
+    fn split_records<T: InputFormatTextBase>(
+        state: &mut AligningState<T>,
+        buf_in: &[u8],
+        is_end: bool,
+    ) -> Result<Vec<RowBatch>> {
         let reader = state.csv_reader.as_mut().expect("must success");
-        let field_ends = &mut reader.field_ends[..];
         let start_row = state.rows;
+        // the offset in the file of buf, counted in the transcoded bytes for other encodings
+        let mut buf_offset = state.offset;
         state.offset += buf_in.len();
 
-        // assume n_out <= n_in for read_record
-        let mut out_tmp = vec![0u8; buf_in.len()];
+        // assume n_out <= n_in + max_pending for read_record
+        let out_len = buf_in.len() + reader.reader.max_pending();
+        if reader.out_tmp.len() < out_len {
+            reader.out_tmp.resize(out_len, 0);
+        }
+        let out_tmp = &mut reader.out_tmp[..out_len];
         let mut endlen = reader.n_end;
         let mut buf = buf_in;
 
-        while state.rows_to_skip > 0 {
-            let (result, n_in, _, n_end) =
-                reader
-                    .reader
-                    .read_record(buf, &mut out_tmp, &mut field_ends[endlen..]);
+        while ((reader.map_by_header || reader.header_check != HeaderCheck::None)
+            && state.header.is_none())
+            || reader.expect_types_row
+        {
+            // the header is kept in reader.out until it is complete
+            let (result, n_in, n_out, n_end) = reader.reader.read_record(
+                buf,
+                out_tmp,
+                &mut reader.field_ends[endlen..],
+                &mut reader.field_quoted[endlen..],
+            );
             buf = &buf[n_in..];
+            buf_offset += n_in;
             endlen += n_end;
+            reader.out.extend_from_slice(&out_tmp[..n_out]);
 
             match result {
                 ReadRecordResult::InputEmpty => {
+                    check_record_delimiter(
+                        reader.max_bytes_without_record_delimiter,
+                        &reader.out,
+                        state.record_delimiter_end,
+                        &state.path,
+                        state.rows,
+                    )?;
+                    check_record_size(&state.ctx, reader.out.len(), &state.path, state.rows)?;
                     reader.n_end = endlen;
                     return Ok(vec![]);
                 }
@@ -154,52 +288,132 @@ impl InputFormatTextBase for InputFormatCSV {
                         "output more than input, in header",
                         &state.path,
                         state.rows,
-                    ));
+                    )
+                    .into());
                 }
                 ReadRecordResult::OutputEndsFull => {
-                    return Err(csv_error(
-                        &format!(
-                            "too many fields, expect {}, got more than {}",
-                            num_fields,
-                            field_ends.len()
-                        ),
-                        &state.path,
-                        state.rows,
-                    ));
-                }
-                ReadRecordResult::Record => {
-                    if endlen < num_fields {
-                        return Err(csv_error(
-                            &format!("expect {} fields, only found {} ", num_fields, n_end),
-                            &state.path,
-                            state.rows,
-                        ));
-                    } else if endlen > num_fields + 1 {
-                        return Err(csv_error(
-                            &format!("too many fields, expect {}, got {}", num_fields, n_end),
-                            &state.path,
-                            state.rows,
-                        ));
-                    } else if endlen == num_fields + 1
-                        && field_ends[num_fields] != field_ends[num_fields - 1]
-                    {
+                    if reader.field_ends.len() >= MAX_FIELD_ENDS {
                         return Err(csv_error(
-                            "CSV allow ending with ',', but should not have data after it",
+                            &format!("too many fields in header, more than {}", MAX_FIELD_ENDS),
                             &state.path,
                             state.rows,
-                        ));
+                        )
+                        .into());
+                    }
+                    let len = (reader.field_ends.len() * 2).min(MAX_FIELD_ENDS);
+                    reader.field_ends.resize(len, 0);
+                    reader.field_quoted.resize(len, false);
+                }
+                ReadRecordResult::Record
+                    if state.ctx.format_settings.skip_blank_lines
+                        && is_blank_record(&reader.out, &reader.field_quoted[..endlen], false) =>
+                {
+                    reader.out.clear();
+                    reader.record_offset = buf_offset;
+                    endlen = 0;
+                }
+                ReadRecordResult::Record if state.header.is_some() => {
+                    // the types row follows the header, it is only checked against the columns
+                    let types_row = mem::take(&mut reader.out);
+                    let types = header_names(&types_row, &reader.field_ends[..endlen]);
+                    let mapping = state.column_mapping.as_ref().map(|m| m.as_slice());
+                    if let Some(msg) = check_declared_types(&state.ctx.schema, mapping, &types) {
+                        return Err(csv_error(&msg, &state.path, state.rows).into());
+                    }
+                    reader.expect_types_row = false;
+                    state.rows += 1;
+                    reader.record_offset = buf_offset;
+                    endlen = 0;
+                }
+                ReadRecordResult::Record => {
+                    let header = mem::take(&mut reader.out);
+                    let names = header_names(&header, &reader.field_ends[..endlen]);
+                    if reader.map_by_header {
+                        let mapping = build_column_mapping(
+                            &state.ctx.schema,
+                            &names,
+                            reader.header_case_sensitive,
+                            state.ctx.header_mapper.as_ref(),
+                        )
+                        .map_err(|e| csv_error(&e, &state.path, state.rows))?;
+                        tracing::debug!("csv aligner: column mapping by header {:?}", mapping);
+                        state.num_fields = mapping.len();
+                        reader.field_ends.resize(mapping.len() + 6, 0);
+                        reader.field_quoted.resize(mapping.len() + 6, false);
+                        state.column_mapping = Some(Arc::new(mapping));
+                    } else if let Some(msg) = check_header(
+                        &state.ctx.schema,
+                        &names,
+                        reader.header_case_sensitive,
+                        reader.lenient_header,
+                    ) {
+                        if reader.header_check == HeaderCheck::Error {
+                            return Err(csv_error(&msg, &state.path, state.rows).into());
+                        }
+                        tracing::warn!("csv header of {}: {}", state.path, msg);
                     }
+                    state.header = Some(names);
+                    // the header is the first row to skip
+                    state.rows_to_skip = state.rows_to_skip.saturating_sub(1);
+                    state.rows += 1;
+                    reader.record_offset = buf_offset;
+                    endlen = 0;
+                }
+                ReadRecordResult::End => {
+                    return Err(csv_error("unexpect eof in header", &state.path, state.rows).into());
+                }
+            }
+        }
+
+        let num_fields = state.num_fields;
+        let allow_extra_columns = state.ctx.format_settings.allow_extra_columns;
+        let allow_missing_columns = state.ctx.format_settings.allow_missing_columns;
+        let ignore_trailing_field = state.ctx.format_settings.ignore_trailing_field;
+        let skip_blank_lines = state.ctx.format_settings.skip_blank_lines;
+        let skip_empty_records = state.ctx.format_settings.skip_empty_records;
+
+        // the skipped rows (e.g. a title or units row above the header) may have any
+        // number of fields, only the record ends matter.
+        while state.rows_to_skip > 0 {
+            let (result, n_in, _, n_end) = reader.reader.read_record(
+                buf,
+                out_tmp,
+                &mut reader.field_ends[endlen..],
+                &mut reader.field_quoted[endlen..],
+            );
+            buf = &buf[n_in..];
+            buf_offset += n_in;
+            endlen += n_end;
 
+            match result {
+                ReadRecordResult::InputEmpty => {
+                    reader.n_end = endlen;
+                    return Ok(vec![]);
+                }
+                ReadRecordResult::OutputFull => {
+                    return Err(csv_error(
+                        "output more than input, in header",
+                        &state.path,
+                        state.rows,
+                    )
+                    .into());
+                }
+                ReadRecordResult::OutputEndsFull => {
+                    // the ends of the fields are not used
+                    endlen = 0;
+                }
+                ReadRecordResult::Record => {
                     state.rows_to_skip -= 1;
                     tracing::debug!(
                         "csv aligner: skip a header row, remain {}",
                         state.rows_to_skip
                     );
                     state.rows += 1;
+                    reader.record_offset = buf_offset;
                     endlen = 0;
                 }
                 ReadRecordResult::End => {
-                    return Err(csv_error("unexpect eof in header", &state.path, state.rows));
+                    return Err(csv_error("unexpect eof in header", &state.path, state.rows).into());
                 }
             }
         }
@@ -207,24 +421,47 @@ impl InputFormatTextBase for InputFormatCSV {
         let mut out_pos = 0usize;
         let mut row_batch_end: usize = 0;
 
-        let last_batch_remain_len = reader.out.len();
+        let mut last_batch_remain_len = reader.out.len();
 
         let mut row_batch = RowBatch {
             data: vec![],
             row_ends: vec![],
+            row_offsets: vec![],
             field_ends: vec![],
+            field_quoted: vec![],
+            column_mapping: state.column_mapping.clone(),
             path: state.path.to_string(),
+            file_index: state.file_index,
             batch_id: state.batch_id,
             offset: 0,
             start_row: Some(state.rows),
+            checksum: None,
         };
 
-        while !buf.is_empty() {
-            let (result, n_in, n_out, n_end) =
-                reader
-                    .reader
-                    .read_record(buf, &mut out_tmp[out_pos..], &mut field_ends[endlen..]);
+        // the empty input at the end tells the reader to emit the pending record
+        while !buf.is_empty() || is_end {
+            if buf.is_empty()
+                && reader.error_on_unterminated_quote
+                && reader.reader.in_quoted_field()
+            {
+                return Err(csv_error(
+                    &format!(
+                        "unterminated quoted field at the end of the file, the record starts at offset {}",
+                        reader.record_offset
+                    ),
+                    &state.path,
+                    start_row + row_batch.row_ends.len(),
+                )
+                .into());
+            }
+            let (result, n_in, n_out, n_end) = reader.reader.read_record(
+                buf,
+                &mut out_tmp[out_pos..],
+                &mut reader.field_ends[endlen..],
+                &mut reader.field_quoted[endlen..],
+            );
             buf = &buf[n_in..];
+            buf_offset += n_in;
             endlen += n_end;
             out_pos += n_out;
             match result {
@@ -234,125 +471,2239 @@ impl InputFormatTextBase for InputFormatCSV {
                         "output more than input",
                         &state.path,
                         start_row + row_batch.row_ends.len(),
-                    ));
+                    )
+                    .into());
+                }
+                ReadRecordResult::OutputEndsFull if reader.field_ends.len() < MAX_FIELD_ENDS => {
+                    let len = (reader.field_ends.len() * 2).min(MAX_FIELD_ENDS);
+                    reader.field_ends.resize(len, 0);
+                    reader.field_quoted.resize(len, false);
+                }
+                ReadRecordResult::OutputEndsFull if allow_extra_columns => {
+                    // the ends of extra fields are overwritten
+                    endlen = num_fields;
                 }
                 ReadRecordResult::OutputEndsFull => {
-                    return Err(csv_error(
-                        &format!(
-                            "too many fields, expect {}, got more than {}",
-                            num_fields,
-                            field_ends.len()
-                        ),
+                    let remain: &[u8] = if row_batch.row_ends.is_empty() {
+                        &reader.out
+                    } else {
+                        &[]
+                    };
+                    return Err(field_count_error(
+                        num_fields,
+                        reader.field_ends.len(),
+                        reader.first_row_fields,
                         &state.path,
                         start_row + row_batch.row_ends.len(),
-                    ));
+                    )
+                    .with_record_preview(record_preview(
+                        remain,
+                        &out_tmp[row_batch_end..out_pos],
+                        &reader.field_ends[..endlen],
+                        reader.error_preview_bytes,
+                    ))
+                    .into());
                 }
                 ReadRecordResult::Record => {
-                    if endlen < num_fields {
-                        return Err(csv_error(
-                            &format!("expect {} fields, only found {} ", num_fields, n_end),
-                            &state.path,
-                            start_row + row_batch.row_ends.len(),
-                        ));
-                    } else if endlen > num_fields + 1 {
-                        return Err(csv_error(
-                            &format!("too many fields, expect {}, got {}", num_fields, n_end),
+                    // the last byte of the record, which may be kept from the last read
+                    let last_byte = if out_pos > row_batch_end {
+                        out_tmp.get(out_pos - 1)
+                    } else if row_batch.row_ends.is_empty() {
+                        reader.out.last()
+                    } else {
+                        None
+                    };
+                    if reader.strip_trailing_cr && endlen > 0 && last_byte == Some(&b'\r') {
+                        // the `\r` of a `\r\n` ending is kept in the last field,
+                        // drop it from the field but leave it in the row data.
+                        reader.field_ends[endlen - 1] -= 1;
+                    }
+                    if skip_blank_lines || skip_empty_records {
+                        // the record may start in the remain of the last read
+                        let in_remain = row_batch.row_ends.is_empty();
+                        let remain: &[u8] = if in_remain { &reader.out } else { &[] };
+                        let record = &out_tmp[row_batch_end..out_pos];
+                        let quoted = &reader.field_quoted[..endlen];
+                        if is_blank_record(remain, quoted, skip_empty_records)
+                            && is_blank_record(record, quoted, skip_empty_records)
+                        {
+                            // drop the bytes of the record, not counted as a row
+                            if in_remain {
+                                reader.out.clear();
+                                last_batch_remain_len = 0;
+                            }
+                            out_pos = row_batch_end;
+                            reader.record_offset = buf_offset;
+                            endlen = 0;
+                            continue;
+                        }
+                    }
+                    let first_row_fields = *reader.first_row_fields.get_or_insert(endlen);
+                    let preview = || {
+                        let remain: &[u8] = if row_batch.row_ends.is_empty() {
+                            &reader.out
+                        } else {
+                            &[]
+                        };
+                        record_preview(
+                            remain,
+                            &out_tmp[row_batch_end..out_pos],
+                            &reader.field_ends[..endlen],
+                            reader.error_preview_bytes,
+                        )
+                    };
+                    if (!allow_missing_columns && endlen < num_fields)
+                        || (!allow_extra_columns && endlen > num_fields + 1)
+                    {
+                        return Err(field_count_error(
+                            num_fields,
+                            endlen,
+                            Some(first_row_fields),
                             &state.path,
                             start_row + row_batch.row_ends.len(),
-                        ));
-                    } else if endlen == num_fields + 1
-                        && field_ends[num_fields] != field_ends[num_fields - 1]
+                        )
+                        .with_record_preview(preview())
+                        .into());
+                    } else if !allow_extra_columns
+                        && !ignore_trailing_field
+                        && endlen == num_fields + 1
+                        && reader.field_ends[num_fields] != reader.field_ends[num_fields - 1]
                     {
                         return Err(csv_error(
                             "CSV allow ending with ',', but should not have data after it",
                             &state.path,
                             start_row + row_batch.row_ends.len(),
-                        ));
+                        )
+                        .with_record_preview(preview())
+                        .into());
+                    }
+                    if endlen < num_fields {
+                        // pad the missing trailing fields with empty ones, read as default
+                        let last_end = reader.field_ends[endlen - 1];
+                        reader.field_ends[endlen..num_fields].fill(last_end);
+                        reader.field_quoted[endlen..num_fields].fill(false);
                     }
                     row_batch
                         .field_ends
-                        .extend_from_slice(&field_ends[..num_fields]);
+                        .extend_from_slice(&reader.field_ends[..num_fields]);
+                    row_batch
+                        .field_quoted
+                        .extend_from_slice(&reader.field_quoted[..num_fields]);
                     row_batch.row_ends.push(last_batch_remain_len + out_pos);
+                    row_batch.row_offsets.push(reader.record_offset);
+                    reader.record_offset = buf_offset;
                     endlen = 0;
                     row_batch_end = out_pos;
                 }
-                ReadRecordResult::End => {
-                    return Err(csv_error(
-                        "unexpect eof",
-                        &state.path,
-                        start_row + row_batch.row_ends.len(),
-                    ));
-                }
+                ReadRecordResult::End => break,
             }
         }
 
         reader.n_end = endlen;
-        out_tmp.truncate(out_pos);
         if row_batch.row_ends.is_empty() {
             tracing::debug!(
                 "csv aligner: {} + {} bytes => 0 rows",
                 reader.out.len(),
                 buf_in.len(),
             );
-            reader.out.extend_from_slice(&out_tmp);
+            reader.out.extend_from_slice(&out_tmp[..out_pos]);
+            check_record_delimiter(
+                reader.max_bytes_without_record_delimiter,
+                &reader.out,
+                state.record_delimiter_end,
+                &state.path,
+                start_row,
+            )?;
+            check_record_size(&state.ctx, reader.out.len(), &state.path, start_row)?;
             Ok(vec![])
         } else {
-            let last_remain = mem::take(&mut reader.out);
+            // out_tmp is reused by the next call, so only the bytes of this batch are copied,
+            // appended to the remain of last batch
+            let mut data = mem::take(&mut reader.out);
+            let last_remain_len = data.len();
+            data.reserve_exact(row_batch_end);
+            data.extend_from_slice(&out_tmp[..row_batch_end]);
+            reader
+                .out
+                .extend_from_slice(&out_tmp[row_batch_end..out_pos]);
 
             state.batch_id += 1;
             state.rows += row_batch.row_ends.len();
-            reader.out.extend_from_slice(&out_tmp[row_batch_end..]);
+            check_record_size(&state.ctx, reader.out.len(), &state.path, state.rows)?;
 
             tracing::debug!(
                 "csv aligner: {} + {} bytes => {} rows + {} bytes remain",
-                last_remain.len(),
+                last_remain_len,
                 buf_in.len(),
                 row_batch.row_ends.len(),
                 reader.out.len()
             );
 
-            out_tmp.truncate(row_batch_end);
-            row_batch.data = if last_remain.is_empty() {
-                out_tmp
+            row_batch.data = data;
+            row_batch.offset = row_batch.row_offsets[0];
+            Ok(vec![row_batch])
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn read_row(
+        buf: &[u8],
+        deserializers: &mut [TypeDeserializerImpl],
+        schema: &DataSchemaRef,
+        field_ends: &[usize],
+        field_quoted: &[bool],
+        field_indexes: Option<&[Option<usize>]>,
+        format_settings: &FormatSettings,
+        path: &str,
+        row_index: usize,
+    ) -> std::result::Result<(), CsvParseError> {
+        // (number of columns appended for this row, error)
+        let mut error = None;
+        for (c, deserializer) in deserializers.iter_mut().enumerate() {
+            let i = match field_indexes {
+                None => c,
+                Some(field_indexes) => match field_indexes[c] {
+                    Some(i) => i,
+                    None => {
+                        // not in the file
+                        deserializer.de_default(format_settings);
+                        continue;
+                    }
+                },
+            };
+            let field_start = if i == 0 { 0 } else { field_ends[i - 1] };
+            let col_data = &buf[field_start..field_ends[i]];
+            let max_field_bytes = format_settings.max_field_bytes;
+            if max_field_bytes > 0 && col_data.len() > max_field_bytes {
+                // only the head of the field is reported
+                let head = &col_data[..MAX_REPORTED_FIELD_BYTES.min(col_data.len())];
+                let msg = format!(
+                    "field exceeds max length {} bytes, got {}",
+                    max_field_bytes,
+                    col_data.len()
+                );
+                let err_msg = format_column_error(schema, c, head, &msg);
+                error = Some((c, csv_error(&err_msg, path, row_index).with_field(c, head)));
+                break;
+            }
+            let rejected = &format_settings.rejected_control_chars;
+            let control_char_pos = match rejected.is_empty() {
+                true => None,
+                false => col_data.iter().position(|b| rejected.contains(b)),
+            };
+            if let Some(pos) = control_char_pos {
+                let msg = format!(
+                    "control char {:#04x} at byte {} of the field",
+                    col_data[pos], pos
+                );
+                let err_msg = format_column_error(schema, c, col_data, &msg);
+                error = Some((
+                    c,
+                    csv_error(&err_msg, path, row_index).with_field(c, col_data),
+                ));
+                break;
+            }
+            let is_string = is_string_column(schema, c);
+            let value = if is_string && !field_quoted[i] {
+                trim_field(col_data, format_settings)
             } else {
-                vec![last_remain, out_tmp].concat()
+                col_data
             };
-            Ok(vec![row_batch])
+            let without_separators: Vec<u8>;
+            let value = match format_settings.numeric_group_separator {
+                Some(sep) if is_numeric_column(schema, c) => {
+                    // e.g. `1,234,567.89`, which is quoted if `,` is the field delimiter
+                    without_separators = value.iter().filter(|b| **b != sep).cloned().collect();
+                    &without_separators[..]
+                }
+                _ => value,
+            };
+            let with_decimal_point: Vec<u8>;
+            let value = match format_settings.decimal_separator {
+                Some(sep) if is_float_column(schema, c) => {
+                    // e.g. `1.234,56` with `.` as the group separator, which is stripped above
+                    with_decimal_point = value
+                        .iter()
+                        .map(|b| if *b == sep { b'.' } else { *b })
+                        .collect();
+                    &with_decimal_point[..]
+                }
+                _ => value,
+            };
+            let value = match format_settings.column_padding.get(&c) {
+                Some(padding) if !is_string_numeric(format_settings, schema.field(c).name()) => {
+                    strip_padding(value, padding)
+                }
+                _ => value,
+            };
+            let mut reader = NestedCheckpointReader::new(value);
+            if !is_string {
+                reader.ignore_white_spaces().expect("must success");
+            }
+            let is_empty = reader.eof().expect("must success");
+            if is_empty && field_quoted[i] && is_string {
+                // `""` is an empty string, while an unquoted empty field is default/null.
+                deserializer
+                    .de_whole_text(col_data, format_settings)
+                    .expect("must success");
+            } else if is_empty {
+                if let Err(m) = de_empty_field(deserializer, schema, c, format_settings) {
+                    let err_msg = format_column_error(schema, c, col_data, &m);
+                    error = Some((
+                        c,
+                        csv_error(&err_msg, path, row_index).with_field(c, col_data),
+                    ));
+                    break;
+                }
+            } else if is_column_null(format_settings, c, col_data.trim()) {
+                deserializer.de_default(format_settings);
+            } else if !field_quoted[i]
+                && schema.field(c).is_nullable()
+                && is_null_value(format_settings, col_data.trim())
+            {
+                // a quoted `"null"` is a string
+                deserializer.de_default(format_settings);
+            } else if let Some(micros) =
+                match_epoch_timestamp(schema, c, col_data.trim(), format_settings)
+            {
+                let res = micros.and_then(|v| {
+                    deserializer
+                        .append_data_value(DataValue::Int64(v), format_settings)
+                        .map_err(|e| e.message())
+                });
+                if let Err(m) = res {
+                    let err_msg = format_column_error(schema, c, col_data, &m);
+                    error = Some((
+                        c,
+                        csv_error(&err_msg, path, row_index).with_field(c, col_data),
+                    ));
+                    break;
+                }
+            } else if let Some(v) = match_bool_literal(schema, c, col_data.trim(), format_settings)
+            {
+                deserializer
+                    .append_data_value(DataValue::Boolean(v), format_settings)
+                    .expect("must success");
+            } else if let Some(v) = match_float_literal(schema, c, col_data.trim(), format_settings)
+            {
+                deserializer
+                    .append_data_value(DataValue::Float64(v), format_settings)
+                    .expect("must success");
+            } else if is_string && format_settings.binary_encoding != BinaryEncoding::Raw {
+                let res = decode_binary(value, format_settings.binary_encoding)
+                    .and_then(|v| deserializer.de_whole_text(&v, format_settings));
+                if let Err(e) = res {
+                    let err_msg = format_column_error(schema, c, col_data, &e.message());
+                    error = Some((
+                        c,
+                        csv_error(&err_msg, path, row_index).with_field(c, col_data),
+                    ));
+                    break;
+                }
+            } else if is_string && format_settings.case_transform != CaseTransform::None {
+                let mut text = vec![];
+                let res = if format_settings.escape_char.is_some() {
+                    text.extend_from_slice(value);
+                    Ok(())
+                } else {
+                    // unescape first, escapes like `\n` are case sensitive
+                    reader
+                        .read_escaped_string_text(&mut text)
+                        .and_then(|_| reader.must_eof())
+                };
+                let res = res.and_then(|_| {
+                    let text = transform_case(&text, format_settings.case_transform);
+                    deserializer.de_whole_text(&text, format_settings)
+                });
+                if let Err(e) = res {
+                    let err_msg = format_column_error(schema, c, col_data, &e.message());
+                    error = Some((
+                        c,
+                        csv_error(&err_msg, path, row_index).with_field(c, col_data),
+                    ));
+                    break;
+                }
+            } else if format_settings.escape_char.is_some() && is_string {
+                // escapes inside quoted fields are already resolved by the csv reader,
+                // unescape again will break values like `a\\nb`.
+                if let Err(e) = deserializer.de_whole_text(value, format_settings) {
+                    let err_msg = format_column_error(schema, c, col_data, &e.message());
+                    error = Some((
+                        c,
+                        csv_error(&err_msg, path, row_index).with_field(c, col_data),
+                    ));
+                    break;
+                };
+            } else if is_string {
+                // appended from the batch data, without copying to a buffer if no escapes
+                if let Err(e) = deserializer.de_text_borrowed(value, format_settings) {
+                    let err_msg = format_column_error(schema, c, col_data, &e.message());
+                    error = Some((
+                        c,
+                        csv_error(&err_msg, path, row_index).with_field(c, col_data),
+                    ));
+                    break;
+                };
+            } else {
+                if let Err(e) = deserializer.de_text(&mut reader, format_settings) {
+                    let err_msg = format_column_error(schema, c, col_data, &e.message());
+                    error = Some((
+                        c,
+                        csv_error(&err_msg, path, row_index).with_field(c, col_data),
+                    ));
+                    break;
+                };
+                reader.ignore_white_spaces().expect("must success");
+                if reader.must_eof().is_err() {
+                    let err_msg = format_column_error(schema, c, col_data, "bad field end");
+                    error = Some((
+                        c + 1,
+                        csv_error(&err_msg, path, row_index).with_field(c, col_data),
+                    ));
+                    break;
+                }
+            }
         }
+        if let Some((num_appended, e)) = error {
+            rollback_row(deserializers, num_appended);
+            return Err(e);
+        }
+        Ok(())
     }
-}
 
-pub struct CsvReaderState {
-    pub reader: csv_core::Reader,
+    /// Each row without its record delimiter is the value of the only column.
+    fn deserialize_single_column<T: InputFormatTextBase>(
+        builder: &mut BlockBuilder<T>,
+        batch: RowBatch,
+    ) -> Result<()> {
+        if builder.mutable_columns.len() != 1 {
+            return Err(ErrorCode::BadArguments(format!(
+                "format_single_column expects exactly one column, got {}",
+                builder.mutable_columns.len()
+            )));
+        }
+        let mut start = 0usize;
+        for (i, end) in batch.row_ends.iter().enumerate() {
+            let row = &batch.data[start..*end];
+            let line = row.strip_suffix(b"\n").unwrap_or(row);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if let Err(e) =
+                builder.mutable_columns[0].de_whole_text(line, &builder.ctx.format_settings)
+            {
+                let msg = format!(
+                    "fail to parse line of {} at row {:?}: {}",
+                    batch.path,
+                    batch.start_row.map(|n| n + i),
+                    e.message()
+                );
+                builder.skip_error_row(
+                    ErrorCode::BadBytes(msg),
+                    row,
+                    batch.start_row.map(|n| n + i),
+                )?;
+            }
+            start = *end;
+        }
+        Ok(())
+    }
 
-    // remain from last read batch
-    pub out: Vec<u8>,
-    pub field_ends: Vec<usize>,
-    pub n_end: usize,
-}
+    /// Deserialize the records framed by the caller, e.g. a tool embedding the deserializer,
+    /// skipping the aligning. Each item is a record without its record delimiter and the ends of
+    /// its fields, relative to the start of the record. The fields are taken as unquoted.
+    pub fn deserialize_lines<'a, I>(
+        ctx: &Arc<InputContext>,
+        lines: I,
+        path: &str,
+    ) -> Result<Vec<DataBlock>>
+    where
+        I: IntoIterator<Item = (&'a [u8], &'a [usize])>,
+    {
+        let num_fields = ctx.schema.num_fields();
+        let format_settings = &ctx.format_settings;
+        let mut batch = RowBatch {
+            data: vec![],
+            row_ends: vec![],
+            row_offsets: vec![],
+            field_ends: vec![],
+            field_quoted: vec![],
+            column_mapping: None,
+            path: path.to_string(),
+            file_index: 0,
+            batch_id: 0,
+            offset: 0,
+            start_row: Some(0),
+            checksum: None,
+        };
+        for (row, (line, field_ends)) in lines.into_iter().enumerate() {
+            let n = field_ends.len();
+            if (!format_settings.allow_missing_columns && n < num_fields)
+                || (!format_settings.allow_extra_columns && n > num_fields)
+            {
+                return Err(field_count_error(num_fields, n, None, path, row).into());
+            }
+            if field_ends.windows(2).any(|w| w[0] > w[1])
+                || field_ends.last().map_or(false, |e| *e > line.len())
+            {
+                return Err(csv_error("bad field ends", path, row).into());
+            }
+            batch.row_offsets.push(batch.data.len());
+            batch.data.extend_from_slice(line);
+            batch.row_ends.push(batch.data.len());
+            // the missing trailing fields are empty, the extra ones are ignored
+            let last_end = field_ends.last().cloned().unwrap_or_default();
+            batch.field_ends.extend(
+                field_ends
+                    .iter()
+                    .cloned()
+                    .chain(std::iter::repeat(last_end))
+                    .take(num_fields),
+            );
+            batch
+                .field_quoted
+                .extend(std::iter::repeat(false).take(num_fields));
+        }
 
-impl CsvReaderState {
-    pub(crate) fn create(ctx: &Arc<InputContext>) -> Self {
-        let reader = csv_core::ReaderBuilder::new()
-            .delimiter(ctx.field_delimiter)
-            .quote(ctx.format_settings.quote_char)
-            .terminator(match ctx.record_delimiter {
-                RecordDelimiter::Crlf => csv_core::Terminator::CRLF,
-                RecordDelimiter::Any(v) => csv_core::Terminator::Any(v),
-            })
-            .build();
-        Self {
-            reader,
-            out: vec![],
-            field_ends: vec![0; ctx.schema.num_fields() + 6],
-            n_end: 0,
+        let mut builder = BlockBuilder::<Self>::create(ctx.clone());
+        let mut blocks = builder.deserialize(Some(batch))?;
+        blocks.extend(builder.deserialize(None)?);
+        if let Some(dedup) = &ctx.key_dedup {
+            blocks.extend(dedup.take_blocks()?);
         }
+        Ok(blocks)
     }
-}
-
-fn csv_error(msg: &str, path: &str, row: usize) -> ErrorCode {
-    let row = row + 1;
-    let msg = format!("fail to parse CSV {}:{} {} ", path, row, msg);
 
-    ErrorCode::BadBytes(msg)
+    /// Deserialize the rows of the batch in `rows`, return the malformed rows and the rows
+    /// dropped by the record filter or the key dedup (index in batch), and the positions of the
+    /// rows loaded if `ctx.key_dedup`.
+    /// Stop at the first malformed row unless `skip_error`.
+    #[allow(clippy::too_many_arguments)]
+    fn read_rows(
+        ctx: &InputContext,
+        deserializers: &mut [TypeDeserializerImpl],
+        schema: &DataSchemaRef,
+        format_settings: &FormatSettings,
+        batch: &RowBatch,
+        field_indexes: Option<&[Option<usize>]>,
+        rows: Range<usize>,
+        skip_error: bool,
+    ) -> (Vec<(usize, CsvParseError)>, Vec<usize>, Vec<RowPosition>) {
+        let n_column = match &batch.column_mapping {
+            Some(mapping) => mapping.len(),
+            None => deserializers.len(),
+        };
+        let start_row = batch.start_row.expect("must success");
+        let mut errors = vec![];
+        let mut filtered = vec![];
+        let mut positions = vec![];
+        for i in rows {
+            let field_end_idx = i * n_column;
+            let row = batch_row(batch, i);
+            if !Self::accept_record(ctx, row) {
+                filtered.push(i);
+                continue;
+            }
+            let field_ends = &batch.field_ends[field_end_idx..field_end_idx + n_column];
+            let dedup = ctx.key_dedup.as_ref().filter(|d| d.field < n_column);
+            let key = dedup.map(|d| {
+                // the fields are adjacent in the unescaped record
+                let start = if d.field == 0 {
+                    0
+                } else {
+                    field_ends[d.field - 1]
+                };
+                &row[start..field_ends[d.field]]
+            });
+            let position = (batch.file_index, batch.row_offsets[i]);
+            if let (Some(dedup), Some(key)) = (dedup, key) {
+                if dedup.has_better(key, position) {
+                    filtered.push(i);
+                    continue;
+                }
+            }
+            let result = Self::validate_record(ctx, row, field_ends)
+                .map_err(|e| {
+                    csv_error(
+                        &format!("record rejected: {}", e.message()),
+                        &batch.path,
+                        start_row + i,
+                    )
+                })
+                .and_then(|_| {
+                    Self::read_row(
+                        row,
+                        deserializers,
+                        schema,
+                        field_ends,
+                        &batch.field_quoted[field_end_idx..field_end_idx + n_column],
+                        field_indexes,
+                        format_settings,
+                        &batch.path,
+                        start_row + i,
+                    )
+                });
+            match result {
+                Ok(()) => match (dedup, key) {
+                    (Some(dedup), Some(key)) if !dedup.insert(key, position) => {
+                        let num_columns = deserializers.len();
+                        rollback_row(deserializers, num_columns);
+                        filtered.push(i);
+                    }
+                    _ if ctx.key_dedup.is_some() => positions.push(position),
+                    _ => {}
+                },
+                Err(e) => {
+                    errors.push((i, e));
+                    if !skip_error {
+                        break;
+                    }
+                }
+            }
+        }
+        (errors, filtered, positions)
+    }
+
+    /// Deserialize the records of a batch, shared by the CSV dialects.
+    pub(crate) fn deserialize_records<T: InputFormatTextBase>(
+        builder: &mut BlockBuilder<T>,
+        batch: RowBatch,
+    ) -> Result<()> {
+        if builder.ctx.settings.get_format_single_column()? > 0 {
+            return Self::deserialize_single_column(builder, batch);
+        }
+        // schema column -> file column
+        let field_indexes = batch.column_mapping.as_ref().map(|mapping| {
+            let mut field_indexes = vec![None; builder.ctx.schema.num_fields()];
+            for (i, c) in mapping.iter().enumerate() {
+                if let Some(c) = c {
+                    field_indexes[*c] = Some(i);
+                }
+            }
+            field_indexes
+        });
+        // output column -> file column, the fields of other columns are skipped
+        let field_indexes = match &builder.ctx.projection {
+            None => field_indexes,
+            Some(projection) => Some(
+                projection
+                    .iter()
+                    .map(|c| match &field_indexes {
+                        None => Some(*c),
+                        Some(field_indexes) => field_indexes[*c],
+                    })
+                    .collect(),
+            ),
+        };
+        let field_indexes = field_indexes.as_deref();
+        let start_row = batch.start_row.expect("must success");
+        let num_rows = batch.row_ends.len();
+        let skip_error = builder.ctx.error_mode != InputErrorMode::AbortOnError;
+        let ctx: &InputContext = &builder.ctx;
+        let schema = &ctx.output_schema;
+        let format_settings = &ctx.format_settings;
+
+        let num_parts = match builder.ctx.parallel_deserialize_rows {
+            0 => 1,
+            rows_per_thread => {
+                let max_threads = builder.ctx.settings.get_max_threads()? as usize;
+                max_threads.min(num_rows / rows_per_thread).max(1)
+            }
+        };
+        if num_parts == 1 {
+            let (errors, filtered, positions) = Self::read_rows(
+                ctx,
+                &mut builder.mutable_columns,
+                schema,
+                format_settings,
+                &batch,
+                field_indexes,
+                0..num_rows,
+                skip_error,
+            );
+            for (i, e) in errors {
+                let column = e.column;
+                builder.skip_error_field(
+                    e.into(),
+                    batch_row(&batch, i),
+                    Some(start_row + i),
+                    column,
+                )?;
+            }
+            for i in filtered {
+                builder.filter_out_row(Some(start_row + i));
+            }
+            builder.row_positions.extend(positions);
+            return Ok(());
+        }
+
+        // each thread fills its own deserializers, which are appended to the builder in order
+        let rows_per_part = (num_rows + num_parts - 1) / num_parts;
+        let parts = std::thread::scope(|s| {
+            let handles = (0..num_parts)
+                .map(|p| {
+                    let rows = p * rows_per_part..num_rows.min((p + 1) * rows_per_part);
+                    let batch = &batch;
+                    s.spawn(move || {
+                        let mut deserializers = schema.create_deserializers(rows.len());
+                        let (errors, filtered, positions) = Self::read_rows(
+                            ctx,
+                            &mut deserializers,
+                            schema,
+                            format_settings,
+                            batch,
+                            field_indexes,
+                            rows,
+                            skip_error,
+                        );
+                        (deserializers, errors, filtered, positions)
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("must success"))
+                .collect::<Vec<_>>()
+        });
+        for (deserializers, errors, filtered, positions) in parts {
+            for (i, e) in errors {
+                let column = e.column;
+                builder.skip_error_field(
+                    e.into(),
+                    batch_row(&batch, i),
+                    Some(start_row + i),
+                    column,
+                )?;
+            }
+            for i in filtered {
+                builder.filter_out_row(Some(start_row + i));
+            }
+            builder.row_positions.extend(positions);
+            builder.append_deserializers(deserializers);
+        }
+        Ok(())
+    }
+
+    /// Align the rest of the input at its end.
+    pub(crate) fn flush_records<T: InputFormatTextBase>(
+        state: &mut AligningState<T>,
+    ) -> Result<Vec<RowBatch>> {
+        let mut batches = vec![];
+        let reader = state.csv_reader.as_ref().expect("must success");
+        if reader.auto_delimiter_head.is_some() || reader.auto_field_delimiter_head.is_some() {
+            // no record delimiter in the whole input
+            let single_column = reader.single_column;
+            let mut head = vec![];
+            if reader.auto_delimiter_head.is_some() {
+                head = Self::detect_record_delimiter(state, &[], true)?.expect("must success");
+            }
+            let reader = state.csv_reader.as_ref().expect("must success");
+            if reader.auto_field_delimiter_head.is_some() {
+                head = Self::detect_field_delimiter(state, &head, true)?.expect("must success");
+            }
+            batches = if single_column {
+                state.align_by_record_delimiter(&head)
+            } else {
+                Self::split_records(state, &head, false)?
+            };
+        }
+        let reader = state.csv_reader.as_ref().expect("must success");
+        if reader.single_column {
+            batches.extend(state.flush());
+            return Ok(batches);
+        }
+        if (reader.map_by_header && state.column_mapping.is_none())
+            || reader.expect_types_row
+            || state.rows_to_skip > 0
+        {
+            // the input ends in the header
+            return Ok(batches);
+        }
+        batches.extend(Self::align_records(state, &[], true)?);
+        // nothing of this file is left for the next one, if the state is reused
+        let reader = state.csv_reader.as_mut().expect("must success");
+        reader.reset(0);
+        Ok(batches)
+    }
+}
+
+impl InputFormatTextBase for InputFormatCSV {
+    fn format_type() -> StageFileFormatType {
+        StageFileFormatType::Csv
+    }
+
+    fn get_format_settings(settings: &Arc<Settings>) -> Result<FormatSettings> {
+        let timezone = get_time_zone(settings)?;
+        let (date_format, timestamp_format) = get_datetime_formats(settings)?;
+        let quote_char = settings.get_format_quote_char()?.into_bytes();
+        // empty for the dialects without quoting
+        if quote_char.len() > 1 {
+            return Err(ErrorCode::InvalidArgument(
+                "quote_char can only be empty or contain one char",
+            ));
+        }
+        // any of them quotes a field if set, e.g. `"'` for files mixing both
+        let quote_chars = settings.get_format_quote_chars()?.into_bytes();
+        let quote_chars = if quote_chars.is_empty() {
+            quote_char
+        } else {
+            quote_chars
+        };
+        // any ASCII char may quote, e.g. `"`, `'` or `` ` ``
+        if quote_chars.iter().any(|c| !c.is_ascii()) {
+            return Err(ErrorCode::InvalidArgument(format!(
+                "quote_char should be an ASCII char, got {:?}",
+                quote_chars
+            )));
+        }
+        let is_quote_char = |c: Option<u8>| c.map_or(false, |c| quote_chars.contains(&c));
+        let escape_char = settings.get_format_escape_char()?.into_bytes();
+        if escape_char.len() > 1 {
+            return Err(ErrorCode::InvalidArgument(
+                "escape_char can only be empty or contain one char",
+            ));
+        }
+        // "double": `""` in a quoted field is a quote, the escape char (if any) also escapes.
+        // "backslash": only the escape char escapes, which is `\` by default.
+        let mut escape_char = escape_char.first().cloned();
+        let double_quote = match settings.get_format_quote_escape()?.to_lowercase().as_str() {
+            "double" => {
+                if is_quote_char(escape_char) {
+                    return Err(ErrorCode::InvalidArgument(
+                        "escape_char should not be the quote_char, which is escaped by doubling already",
+                    ));
+                }
+                true
+            }
+            "backslash" => {
+                if quote_chars.is_empty() {
+                    return Err(ErrorCode::InvalidArgument(
+                        "quote_escape 'backslash' requires a quote_char",
+                    ));
+                }
+                if escape_char.is_none() {
+                    escape_char = Some(b'\\');
+                }
+                if is_quote_char(escape_char) {
+                    return Err(ErrorCode::InvalidArgument(
+                        "quote_escape 'backslash' requires an escape_char other than the quote_char",
+                    ));
+                }
+                false
+            }
+            v => {
+                return Err(ErrorCode::InvalidArgument(format!(
+                    "quote_escape can only be 'double' or 'backslash', got '{}'",
+                    v
+                )));
+            }
+        };
+        let (trim_leading_spaces, trim_trailing_spaces) =
+            match settings.get_format_trim_fields()?.to_lowercase().as_str() {
+                "none" => (false, false),
+                "both" => (true, true),
+                "leading" => (true, false),
+                "trailing" => (false, true),
+                v => {
+                    return Err(ErrorCode::InvalidArgument(format!(
+                        "trim_fields can only be 'none', 'both', 'leading' or 'trailing', got '{}'",
+                        v
+                    )));
+                }
+            };
+        let decimal_separator = settings.get_format_decimal_separator()?.into_bytes();
+        if decimal_separator.len() > 1 {
+            return Err(ErrorCode::InvalidArgument(
+                "decimal_separator can only be empty or contain one char",
+            ));
+        }
+        let decimal_separator = decimal_separator.first().cloned().filter(|c| *c != b'.');
+        let numeric_group_separator = settings.get_format_numeric_group_separator()?.into_bytes();
+        if numeric_group_separator.len() > 1 {
+            return Err(ErrorCode::InvalidArgument(
+                "numeric_group_separator can only be empty or contain one char",
+            ));
+        }
+        if numeric_group_separator.first() == Some(&decimal_separator.unwrap_or(b'.')) {
+            return Err(ErrorCode::InvalidArgument(format!(
+                "numeric_group_separator should not be the decimal separator '{}'",
+                decimal_separator.unwrap_or(b'.') as char
+            )));
+        }
+        Ok(FormatSettings {
+            record_delimiter: settings.get_format_record_delimiter()?.into_bytes(),
+            field_delimiter: settings.get_format_field_delimiter()?.into_bytes(),
+            empty_as_default: settings.get_format_empty_as_default()? > 0,
+            empty_field_as: get_empty_field_as(settings)?,
+            column_defaults: parse_column_defaults(&settings.get_format_column_defaults()?)?,
+            quote_char: quote_chars.first().cloned(),
+            extra_quote_chars: quote_chars.iter().skip(1).cloned().collect(),
+            escape_char,
+            double_quote,
+            lenient_quotes: settings.get_format_lenient_quotes()? > 0,
+            merge_consecutive_delimiters: settings.get_format_merge_consecutive_delimiters()? > 0,
+            trim_leading_spaces,
+            trim_trailing_spaces,
+            comment_token: settings.get_format_comment_char()?.into_bytes(),
+            comment_requires_line_start: settings.get_format_comment_requires_line_start()? > 0,
+            numeric_group_separator: numeric_group_separator.first().cloned(),
+            decimal_separator,
+            allow_extra_columns: settings.get_format_allow_extra_columns()? > 0,
+            allow_missing_columns: settings.get_format_allow_missing_columns()? > 0,
+            ignore_trailing_field: settings.get_format_ignore_trailing_field()? > 0,
+            skip_empty_records: match settings.get_format_empty_records()?.to_lowercase().as_str() {
+                "keep" => false,
+                "skip" => true,
+                v => {
+                    return Err(ErrorCode::InvalidArgument(format!(
+                        "empty_records can only be 'keep' or 'skip', got '{}'",
+                        v
+                    )));
+                }
+            },
+            preserve_leading_zeros: settings.get_format_preserve_leading_zeros()? > 0,
+            preserve_string_numerics: settings
+                .get_format_preserve_string_numerics()?
+                .split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect(),
+            case_transform: get_case_transform(settings)?,
+            max_field_bytes: settings.get_format_max_field_bytes()? as usize,
+            rejected_control_chars: parse_control_chars(
+                &settings.get_format_reject_control_chars()?,
+            )?,
+            lenient_sign: settings.get_format_lenient_sign()? > 0,
+            saturate_integer_overflow: match settings
+                .get_format_integer_overflow()?
+                .to_lowercase()
+                .as_str()
+            {
+                "error" => false,
+                "saturate" => true,
+                v => {
+                    return Err(ErrorCode::InvalidArgument(format!(
+                        "integer_overflow can only be 'error' or 'saturate', got '{}'",
+                        v
+                    )));
+                }
+            },
+            binary_encoding: get_binary_encoding(settings)?,
+            timestamp_unit: get_timestamp_unit(settings)?,
+            true_bytes: parse_literals(&settings.get_format_true_values()?),
+            false_bytes: parse_literals(&settings.get_format_false_values()?),
+            nan_literals: parse_literals(&settings.get_format_nan_values()?),
+            inf_literals: parse_literals(&settings.get_format_inf_values()?),
+            bool_case_insensitive: settings.get_format_bool_case_insensitive()? > 0,
+            column_null_bytes: parse_column_null_values(
+                &settings.get_format_column_null_values()?,
+            )?,
+            column_padding: parse_column_padding(&settings.get_format_column_padding()?)?,
+            null_bytes: vec![b'\\', b'N'],
+            null_values: parse_literals(&settings.get_format_null_values()?),
+            null_values_case_sensitive: settings.get_format_null_values_case_sensitive()? > 0,
+            encoding: settings.get_format_encoding()?,
+            timezone,
+            date_format,
+            timestamp_format,
+            ..Default::default()
+        })
+    }
+
+    fn default_field_delimiter() -> u8 {
+        b','
+    }
+
+    fn deserialize(builder: &mut BlockBuilder<Self>, batch: RowBatch) -> Result<()> {
+        Self::deserialize_records(builder, batch)
+    }
+
+    fn align(state: &mut AligningState<Self>, buf_in: &[u8]) -> Result<Vec<RowBatch>> {
+        Self::align_records(state, buf_in, false)
+    }
+
+    fn align_flush(state: &mut AligningState<Self>) -> Result<Vec<RowBatch>> {
+        Self::flush_records(state)
+    }
+}
+
+pub enum CsvRecordReader {
+    Core(csv_core::Reader),
+    // for the dialects csv-core can not handle, e.g. multi-byte field delimiter, comment lines,
+    // or when whether a field is quoted matters
+    Splitter(CsvSplitter),
+}
+
+impl CsvRecordReader {
+    /// csv-core is used if it can handle the dialect, it is faster than `CsvSplitter`.
+    /// `track_quoted`: whether a field is quoted matters, e.g. `""` is an empty string while an
+    /// unquoted empty field is the default value, csv-core does not tell it.
+    pub fn try_create(
+        format_settings: &FormatSettings,
+        record_delimiter: &RecordDelimiter,
+        track_quoted: bool,
+    ) -> Result<Self> {
+        // also checks the dialect
+        let splitter = CsvSplitter::try_create(format_settings, record_delimiter)?;
+        let terminator = match record_delimiter {
+            RecordDelimiter::Crlf => csv_core::Terminator::CRLF,
+            RecordDelimiter::Any(v) => csv_core::Terminator::Any(*v),
+            RecordDelimiter::Multi(_) | RecordDelimiter::Auto => {
+                return Ok(CsvRecordReader::Splitter(splitter));
+            }
+        };
+        if track_quoted
+            || format_settings.field_delimiter.len() != 1
+            || !format_settings.comment_token.is_empty()
+            || !format_settings.extra_quote_chars.is_empty()
+            || format_settings.lenient_quotes
+            || format_settings.merge_consecutive_delimiters
+        {
+            return Ok(CsvRecordReader::Splitter(splitter));
+        }
+        let mut builder = csv_core::ReaderBuilder::new();
+        builder
+            .delimiter(format_settings.field_delimiter[0])
+            .escape(format_settings.escape_char)
+            .double_quote(format_settings.double_quote)
+            .terminator(terminator);
+        match format_settings.quote_char {
+            Some(quote) => builder.quote(quote),
+            None => builder.quoting(false),
+        };
+        Ok(CsvRecordReader::Core(builder.build()))
+    }
+
+    /// A reader of the same record delimiter, for the field delimiter `auto` once detected.
+    pub fn with_field_delimiter(
+        &self,
+        format_settings: &FormatSettings,
+        field_delimiter: u8,
+    ) -> Result<Self> {
+        match self {
+            CsvRecordReader::Splitter(splitter) => Ok(CsvRecordReader::Splitter(
+                splitter.with_field_delimiter(format_settings, field_delimiter)?,
+            )),
+            CsvRecordReader::Core(_) => Err(ErrorCode::UnexpectedError(
+                "should not happen: detect the field delimiter with csv-core",
+            )),
+        }
+    }
+
+    /// See `CsvSplitter::read_record`, `quoted` is all false for csv-core.
+    pub fn read_record(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+        ends: &mut [usize],
+        quoted: &mut [bool],
+    ) -> (ReadRecordResult, usize, usize, usize) {
+        match self {
+            CsvRecordReader::Core(reader) => {
+                let (result, n_in, n_out, n_end) = reader.read_record(input, output, ends);
+                quoted[..n_end].fill(false);
+                (result, n_in, n_out, n_end)
+            }
+            CsvRecordReader::Splitter(splitter) => {
+                splitter.read_record(input, output, ends, quoted)
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        match self {
+            CsvRecordReader::Core(reader) => reader.reset(),
+            CsvRecordReader::Splitter(splitter) => splitter.reset(),
+        }
+    }
+
+    /// Always false for csv-core, which closes the quoted field at the end of the input.
+    pub fn in_quoted_field(&self) -> bool {
+        match self {
+            CsvRecordReader::Core(_) => false,
+            CsvRecordReader::Splitter(splitter) => splitter.in_quoted_field(),
+        }
+    }
+
+    pub fn max_pending(&self) -> usize {
+        match self {
+            CsvRecordReader::Core(_) => 0,
+            CsvRecordReader::Splitter(splitter) => splitter.max_pending(),
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+// field_ends grows on demand up to so many fields of a record, in case of garbage input
+const MAX_FIELD_ENDS: usize = 65536;
+const MAX_REPORTED_FIELD_BYTES: usize = 64;
+
+pub struct CsvReaderState {
+    pub reader: CsvRecordReader,
+    // the reader tells the quoted fields, see `CsvRecordReader::try_create`
+    pub track_quoted: bool,
+    // map fields to columns by the names in the header, instead of position
+    pub map_by_header: bool,
+    // compare the header with the columns when loading by position
+    pub header_check: HeaderCheck,
+    pub header_case_sensitive: bool,
+    // the header is discarded anyway, its field count is not checked
+    pub lenient_header: bool,
+    // strip the `\r` before `\n` when the record delimiter is `\n`, for files mixing `\r\n` and `\n`
+    pub strip_trailing_cr: bool,
+    // split by the record delimiter only, each line is the value of the only column
+    pub single_column: bool,
+    // transcode to utf-8 for other encodings, keep the incomplete char at the end of last read
+    pub decoder: Option<Decoder>,
+    // the head of the file is kept until we know whether it starts with the UTF-8 BOM
+    pub bom_checked: bool,
+    pub head: Vec<u8>,
+    // the head of the file is kept until the record delimiter `auto` is detected
+    pub auto_delimiter_head: Option<Vec<u8>>,
+    // the first record is kept until the field delimiter `auto` is detected
+    pub auto_field_delimiter_head: Option<Vec<u8>>,
+
+    // remain from last read batch
+    pub out: Vec<u8>,
+    // scratch output of the splitter, reused across reads and only grown
+    pub out_tmp: Vec<u8>,
+    pub field_ends: Vec<usize>,
+    pub field_quoted: Vec<bool>,
+    pub n_end: usize,
+    // the offset in the file of the record being read
+    pub record_offset: usize,
+    // the number of fields of the first data record, to tell ragged records from a wrong schema
+    pub first_row_fields: Option<usize>,
+    // fail early if the file starts with so many bytes without any record delimiter, 0 for unlimited
+    pub max_bytes_without_record_delimiter: usize,
+    // the row of types after the header of CSVWithTypes is not read yet
+    pub expect_types_row: bool,
+    // fail on a quoted field not closed at the end of the file, instead of closing it there
+    pub error_on_unterminated_quote: bool,
+    // the bytes of the fields shown in the errors of the structure of a record, 0 for none
+    pub error_preview_bytes: usize,
+}
+
+impl CsvReaderState {
+    /// With `with_types`, the header is followed by a row of the types of the columns.
+    pub(crate) fn try_create(
+        ctx: &Arc<InputContext>,
+        offset: usize,
+        with_types: bool,
+    ) -> Result<Self> {
+        if let Some(sep) = ctx.format_settings.decimal_separator {
+            if ctx.format_settings.field_delimiter == [sep] {
+                return Err(ErrorCode::InvalidArgument(format!(
+                    "decimal_separator should not be the field_delimiter '{}'",
+                    sep as char
+                )));
+            }
+        }
+        let auto_delimiter = matches!(ctx.record_delimiter, RecordDelimiter::Auto);
+        let auto_field_delimiter = ctx
+            .format_settings
+            .field_delimiter
+            .eq_ignore_ascii_case(b"auto");
+        let placeholder;
+        let format_settings = if auto_field_delimiter {
+            placeholder = FormatSettings {
+                field_delimiter: vec![b','],
+                ..ctx.format_settings.clone()
+            };
+            &placeholder
+        } else {
+            &ctx.format_settings
+        };
+        let error_on_unterminated_quote = match ctx
+            .settings
+            .get_format_on_unterminated_quote()?
+            .to_lowercase()
+            .as_str()
+        {
+            "close" => false,
+            "error" => true,
+            v => {
+                return Err(ErrorCode::InvalidArgument(format!(
+                    "on_unterminated_quote can only be 'error' or 'close', got '{}'",
+                    v
+                )));
+            }
+        };
+        let schema = &ctx.schema;
+        let track_quoted = auto_field_delimiter
+            || error_on_unterminated_quote
+            || format_settings.skip_blank_lines
+            || format_settings.skip_empty_records
+            || (0..schema.num_fields())
+                .any(|c| is_string_column(schema, c) || schema.field(c).is_nullable());
+        // replaced once the record delimiter or the field delimiter `auto` is detected
+        let reader = CsvRecordReader::try_create(
+            format_settings,
+            if auto_delimiter {
+                &RecordDelimiter::Crlf
+            } else {
+                &ctx.record_delimiter
+            },
+            track_quoted,
+        )?;
+        let encoding = get_encoding(&ctx.format_settings.encoding)?;
+        let decoder = if encoding == UTF_8 {
+            None
+        } else {
+            // BOM is sniffed and removed
+            Some(encoding.new_decoder())
+        };
+        Ok(Self {
+            reader,
+            track_quoted,
+            // the names row of CSVWithTypes always maps the fields
+            map_by_header: with_types || ctx.settings.get_format_header_mapping()? > 0,
+            header_case_sensitive: ctx.settings.get_format_header_case_sensitive()? > 0,
+            header_check: get_header_check(&ctx.settings)?,
+            lenient_header: ctx.settings.get_format_lenient_header()? > 0,
+            strip_trailing_cr: (auto_delimiter
+                || matches!(ctx.record_delimiter, RecordDelimiter::Any(b'\n')))
+                && ctx.settings.get_format_strip_trailing_cr()? > 0,
+            single_column: ctx.settings.get_format_single_column()? > 0,
+            // the decoder removes BOM itself
+            bom_checked: decoder.is_some(),
+            head: vec![],
+            auto_delimiter_head: auto_delimiter.then(Vec::new),
+            auto_field_delimiter_head: auto_field_delimiter.then(Vec::new),
+            decoder,
+            out: vec![],
+            out_tmp: vec![],
+            field_ends: vec![0; ctx.schema.num_fields() + 6],
+            field_quoted: vec![false; ctx.schema.num_fields() + 6],
+            n_end: 0,
+            record_offset: offset,
+            first_row_fields: None,
+            max_bytes_without_record_delimiter: ctx
+                .settings
+                .get_input_max_bytes_without_record_delimiter()?
+                as usize,
+            expect_types_row: with_types,
+            error_preview_bytes: ctx.settings.get_input_error_preview_bytes()? as usize,
+            error_on_unterminated_quote,
+        })
+    }
+
+    /// Forget the incomplete record of the last file, to read another file from `offset`.
+    /// The settings and the detected record delimiter are kept.
+    pub fn reset(&mut self, offset: usize) {
+        self.reader.reset();
+        self.out.clear();
+        self.n_end = 0;
+        self.record_offset = offset;
+        self.first_row_fields = None;
+        self.decoder = self.decoder.as_ref().map(|d| d.encoding().new_decoder());
+        self.bom_checked = self.decoder.is_some();
+        self.head.clear();
+    }
+
+    /// The memory held by the incomplete record, which spans the chunks read so far,
+    /// e.g. to apply backpressure or warn about absurdly large records.
+    pub fn pending_bytes(&self) -> usize {
+        let field_size = mem::size_of::<usize>() + mem::size_of::<bool>();
+        self.out.len()
+            + self.n_end * field_size
+            + self.head.len()
+            + self.auto_delimiter_head.as_ref().map_or(0, |h| h.len())
+            + self
+                .auto_field_delimiter_head
+                .as_ref()
+                .map_or(0, |h| h.len())
+    }
+
+    /// return None if the buf read so far may still be a prefix of the BOM
+    fn strip_bom(&mut self, buf: &[u8]) -> Option<Vec<u8>> {
+        self.head.extend_from_slice(buf);
+        if self.head.len() < UTF8_BOM.len() && UTF8_BOM.starts_with(&self.head) {
+            return None;
+        }
+        self.bom_checked = true;
+        let mut head = mem::take(&mut self.head);
+        if head.starts_with(UTF8_BOM) {
+            head.drain(..UTF8_BOM.len());
+        }
+        Some(head)
+    }
+}
+
+fn batch_row(batch: &RowBatch, i: usize) -> &[u8] {
+    let start = if i == 0 { 0 } else { batch.row_ends[i - 1] };
+    &batch.data[start..batch.row_ends[i]]
+}
+
+/// The encoding of a WHATWG label, or of a common spelling not in them, e.g. `latin-1`.
+/// Like browsers, `latin1` and `iso-8859-1` are decoded as windows-1252, a superset of them
+/// except for the C1 controls.
+fn get_encoding(label: &str) -> Result<&'static Encoding> {
+    let label = label.trim().to_lowercase();
+    let label = match label.as_str() {
+        "latin-1" | "latin_1" => "latin1",
+        "windows1252" | "cp-1252" => "windows-1252",
+        label => label,
+    };
+    Encoding::for_label(label.as_bytes())
+        .ok_or_else(|| ErrorCode::InvalidArgument(format!("unknown encoding {}", label)))
+}
+
+fn transcode(decoder: &mut Decoder, buf: &[u8]) -> Result<Vec<u8>> {
+    let max_len = decoder
+        .max_utf8_buffer_length(buf.len())
+        .ok_or_else(|| ErrorCode::BadBytes("input too large to transcode"))?;
+    let mut out = vec![0u8; max_len];
+    let (_, n_in, n_out, _) = decoder.decode_to_utf8(buf, &mut out, false);
+    debug_assert_eq!(n_in, buf.len());
+    out.truncate(n_out);
+    Ok(out)
+}
+
+/// A record of one unquoted field of only whitespace, or of any number of such fields
+/// (e.g. `,,,`) if `any_fields`. The field delimiters are not in `data`.
+fn is_blank_record(data: &[u8], field_quoted: &[bool], any_fields: bool) -> bool {
+    let unquoted = match any_fields {
+        true => field_quoted.iter().all(|q| !q),
+        false => field_quoted == [false],
+    };
+    unquoted && data.iter().all(|b| b.is_ascii_whitespace())
+}
+
+fn is_column_null(format_settings: &FormatSettings, column_index: usize, data: &[u8]) -> bool {
+    match format_settings.column_null_bytes.get(&column_index) {
+        Some(null_values) => null_values.iter().any(|v| v == data),
+        None => false,
+    }
+}
+
+/// Whether an unquoted field is one of `format_settings.null_values`.
+pub(crate) fn is_null_value(format_settings: &FormatSettings, data: &[u8]) -> bool {
+    format_settings.null_values.iter().any(|v| {
+        if format_settings.null_values_case_sensitive {
+            v == data
+        } else {
+            v.eq_ignore_ascii_case(data)
+        }
+    })
+}
+
+/// The value of a boolean column matching `true_bytes` or `false_bytes`,
+/// other values are left to the deserializer.
+fn match_bool_literal(
+    schema: &DataSchemaRef,
+    column_index: usize,
+    data: &[u8],
+    format_settings: &FormatSettings,
+) -> Option<bool> {
+    let data_type = schema.field(column_index).data_type();
+    if remove_nullable(data_type).data_type_id() != TypeID::Boolean {
+        return None;
+    }
+    let matches = |literals: &[Vec<u8>]| {
+        literals.iter().any(|v| {
+            if format_settings.bool_case_insensitive {
+                v.eq_ignore_ascii_case(data)
+            } else {
+                v == data
+            }
+        })
+    };
+    if matches(&format_settings.true_bytes) {
+        Some(true)
+    } else if matches(&format_settings.false_bytes) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// The value of a float column matching `nan_literals` or `inf_literals` case-insensitively,
+/// other values are left to the deserializer.
+fn match_float_literal(
+    schema: &DataSchemaRef,
+    column_index: usize,
+    data: &[u8],
+    format_settings: &FormatSettings,
+) -> Option<f64> {
+    let data_type = schema.field(column_index).data_type();
+    if !remove_nullable(data_type).data_type_id().is_floating() {
+        return None;
+    }
+    let matches =
+        |literals: &[Vec<u8>], data: &[u8]| literals.iter().any(|v| v.eq_ignore_ascii_case(data));
+    if matches(&format_settings.nan_literals, data) {
+        return Some(f64::NAN);
+    }
+    // `-Inf` is the negative infinity of the literal `Inf`
+    let negative = data.first() == Some(&b'-');
+    let unsigned = data
+        .strip_prefix(b"-")
+        .or_else(|| data.strip_prefix(b"+"))
+        .unwrap_or(data);
+    if matches(&format_settings.inf_literals, unsigned)
+        || matches(&format_settings.inf_literals, data)
+    {
+        Some(if negative {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        })
+    } else {
+        None
+    }
+}
+
+/// parse `<code>[,<code>]...` of ASCII control chars, or `all` for all of them but `\t\n\r`
+fn parse_control_chars(s: &str) -> Result<Vec<u8>> {
+    let mut chars = vec![];
+    for item in s.split(',').map(|i| i.trim()).filter(|i| !i.is_empty()) {
+        if item.eq_ignore_ascii_case("all") {
+            chars.extend((0u8..32).chain([127]).filter(|c| !b"\t\n\r".contains(c)));
+            continue;
+        }
+        match item.parse::<u8>() {
+            Ok(c) if c.is_ascii_control() => chars.push(c),
+            _ => {
+                return Err(ErrorCode::InvalidArgument(format!(
+                    "bad reject_control_chars '{}', expect the code of an ASCII control char or 'all'",
+                    item
+                )));
+            }
+        }
+    }
+    Ok(chars)
+}
+
+/// parse `<value>[,<value>]...`
+fn parse_literals(s: &str) -> Vec<Vec<u8>> {
+    s.split(',')
+        .filter(|v| !v.is_empty())
+        .map(|v| v.as_bytes().to_vec())
+        .collect()
+}
+
+/// parse `<column index>:<null value>[,<column index>:<null value>]...`
+fn parse_column_null_values(s: &str) -> Result<HashMap<usize, Vec<Vec<u8>>>> {
+    let mut column_null_bytes: HashMap<usize, Vec<Vec<u8>>> = HashMap::new();
+    for item in s.split(',').filter(|i| !i.is_empty()) {
+        let (column_index, null_value) = item
+            .split_once(':')
+            .and_then(|(c, v)| c.trim().parse::<usize>().ok().map(|c| (c, v)))
+            .ok_or_else(|| {
+                ErrorCode::InvalidArgument(format!(
+                    "column_null_values expect <column index>:<null value>, got {}",
+                    item
+                ))
+            })?;
+        column_null_bytes
+            .entry(column_index)
+            .or_default()
+            .push(null_value.as_bytes().to_vec());
+    }
+    Ok(column_null_bytes)
+}
+
+/// parse `<column index>:<pad char>[:left|right|both][,...]`, the side is left by default.
+fn parse_column_padding(s: &str) -> Result<HashMap<usize, ColumnPadding>> {
+    let mut column_padding = HashMap::new();
+    for item in s.split(',').filter(|i| !i.trim().is_empty()) {
+        let bad = || {
+            ErrorCode::InvalidArgument(format!(
+                "column_padding expect <column index>:<pad char>[:left|right|both], got {}",
+                item
+            ))
+        };
+        let parts: Vec<&str> = item.trim().splitn(3, ':').collect();
+        let column_index = parts[0].trim().parse::<usize>().map_err(|_| bad())?;
+        let pad = match parts.get(1).map(|p| p.as_bytes()) {
+            Some([pad]) => *pad,
+            _ => return Err(bad()),
+        };
+        let side = match parts.get(2).map(|s| s.trim().to_lowercase()) {
+            None => PadSide::Left,
+            Some(s) if s == "left" => PadSide::Left,
+            Some(s) if s == "right" => PadSide::Right,
+            Some(s) if s == "both" => PadSide::Both,
+            Some(_) => return Err(bad()),
+        };
+        column_padding.insert(column_index, ColumnPadding { pad, side });
+    }
+    Ok(column_padding)
+}
+
+/// A field of zero padding only keeps one `0`, e.g. `000` is `0`.
+fn strip_padding<'a>(value: &'a [u8], padding: &ColumnPadding) -> &'a [u8] {
+    let pad = padding.pad;
+    let stripped = value;
+    let mut value = value;
+    if matches!(padding.side, PadSide::Left | PadSide::Both) {
+        let n = value.iter().take_while(|b| **b == pad).count();
+        value = &value[n..];
+    }
+    if matches!(padding.side, PadSide::Right | PadSide::Both) {
+        let n = value.iter().rev().take_while(|b| **b == pad).count();
+        value = &value[..value.len() - n];
+    }
+    if value.is_empty() && pad == b'0' && !stripped.is_empty() {
+        &stripped[..1]
+    } else {
+        value
+    }
+}
+
+/// What to do if the header differs from the columns when loading by position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeaderCheck {
+    None,
+    Error,
+    Warn,
+}
+
+fn get_header_check(settings: &Settings) -> Result<HeaderCheck> {
+    match settings.get_format_header_check()?.to_lowercase().as_str() {
+        "none" => Ok(HeaderCheck::None),
+        "error" => Ok(HeaderCheck::Error),
+        "warn" => Ok(HeaderCheck::Warn),
+        v => Err(ErrorCode::InvalidArgument(format!(
+            "header_check can only be 'none', 'error' or 'warn', got '{}'",
+            v
+        ))),
+    }
+}
+
+fn header_names(header: &[u8], field_ends: &[usize]) -> Vec<String> {
+    let mut field_start = 0;
+    field_ends
+        .iter()
+        .map(|field_end| {
+            let name = String::from_utf8_lossy(&header[field_start..*field_end])
+                .trim()
+                .to_string();
+            field_start = *field_end;
+            name
+        })
+        .collect()
+}
+
+/// Return the difference of the header and the columns of the schema in order, if any.
+/// With `lenient`, only the names present are compared, e.g. a merged title cell may shorten
+/// the header.
+fn check_header(
+    schema: &DataSchemaRef,
+    names: &[String],
+    case_sensitive: bool,
+    lenient: bool,
+) -> Option<String> {
+    if !lenient && names.len() != schema.num_fields() {
+        return Some(format!(
+            "header has {} columns {:?}, but the table has {}",
+            names.len(),
+            names,
+            schema.num_fields()
+        ));
+    }
+    names
+        .iter()
+        .zip(schema.fields())
+        .enumerate()
+        .find(|(_, (name, field))| {
+            if case_sensitive {
+                *name != field.name()
+            } else {
+                name.to_lowercase() != field.name().to_lowercase()
+            }
+        })
+        .map(|(c, (name, field))| {
+            format!(
+                "header column {} '{}' does not match column '{}'",
+                c + 1,
+                name,
+                field.name()
+            )
+        })
+}
+
+/// Return the first type in the types row not matching its column, if any.
+/// The fields are mapped to the columns by `mapping`, or by position without it.
+fn check_declared_types(
+    schema: &DataSchemaRef,
+    mapping: Option<&[Option<usize>]>,
+    types: &[String],
+) -> Option<String> {
+    let num_fields = mapping.map_or(schema.num_fields(), |m| m.len());
+    if types.len() != num_fields {
+        return Some(format!(
+            "types row has {} columns {:?}, but the names row has {}",
+            types.len(),
+            types,
+            num_fields
+        ));
+    }
+    types.iter().enumerate().find_map(|(i, declared)| {
+        let c = match mapping {
+            Some(mapping) => mapping[i]?,
+            None => i,
+        };
+        let field = schema.field(c);
+        if is_declared_type(declared, field.data_type()) {
+            None
+        } else {
+            Some(format!(
+                "column '{}' is declared as '{}' in the types row, but its type is {}",
+                field.name(),
+                declared,
+                field.data_type().name()
+            ))
+        }
+    })
+}
+
+/// Whether a declared type, e.g. `Int64`, `BIGINT` or `Nullable(String)`, names the type or
+/// its non-nullable type, case-insensitively.
+fn is_declared_type(declared: &str, data_type: &DataTypeImpl) -> bool {
+    let inner = remove_nullable(data_type);
+    [data_type, &inner].iter().any(|t| {
+        declared.eq_ignore_ascii_case(&t.name())
+            || declared.eq_ignore_ascii_case(&t.sql_name())
+            || t.aliases().iter().any(|a| declared.eq_ignore_ascii_case(a))
+    })
+}
+
+/// file column -> schema column, fields not in the schema are ignored.
+/// The header names are mapped to the column names by `header_mapper` if any.
+pub(crate) fn build_column_mapping(
+    schema: &DataSchemaRef,
+    header_names: &[String],
+    case_sensitive: bool,
+    header_mapper: Option<&HeaderMapper>,
+) -> std::result::Result<Vec<Option<usize>>, String> {
+    let mut mapping: Vec<Option<usize>> = Vec::with_capacity(header_names.len());
+    let mut names: Vec<&str> = Vec::with_capacity(header_names.len());
+    for name in header_names {
+        let target = match header_mapper {
+            Some(mapper) => mapper(name),
+            None => Some(name.clone()),
+        };
+        let columns: Vec<usize> = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| match &target {
+                None => false,
+                Some(target) if case_sensitive => f.name() == target,
+                Some(target) => f.name().to_lowercase() == target.to_lowercase(),
+            })
+            .map(|(i, _)| i)
+            .collect();
+        if columns.len() > 1 {
+            return Err(format!(
+                "header column '{}' matches more than one column: '{}' and '{}'",
+                name,
+                schema.field(columns[0]).name(),
+                schema.field(columns[1]).name()
+            ));
+        }
+        let column = columns.first().copied();
+        if let Some(c) = column {
+            if let Some(i) = mapping.iter().position(|m| *m == Some(c)) {
+                return Err(format!(
+                    "header columns '{}' and '{}' both match column '{}'",
+                    names[i],
+                    name,
+                    schema.field(c).name()
+                ));
+            }
+        }
+        mapping.push(column);
+        names.push(name);
+    }
+    Ok(mapping)
+}
+
+/// Fail if the incomplete record at `row` is larger than `ctx.max_record_bytes`,
+/// instead of buffering the rest of the file after an unterminated quote.
+fn check_record_size(
+    ctx: &InputContext,
+    pending: usize,
+    path: &str,
+    row: usize,
+) -> std::result::Result<(), CsvParseError> {
+    if ctx.max_record_bytes > 0 && pending > ctx.max_record_bytes {
+        let msg = format!(
+            "record exceeds max size {} bytes, possible unterminated quote",
+            ctx.max_record_bytes
+        );
+        return Err(csv_error(&msg, path, row));
+    }
+    Ok(())
+}
+
+/// Fail if the file starts with more than `limit` bytes without any record delimiter,
+/// which usually means the record delimiter is misconfigured, e.g. `\n` for a file of `\r`.
+/// A delimiter inside a quoted field still counts, that case is left to `check_record_size`.
+fn check_record_delimiter(
+    limit: usize,
+    pending: &[u8],
+    record_delimiter_end: u8,
+    path: &str,
+    row: usize,
+) -> std::result::Result<(), CsvParseError> {
+    if limit == 0 || row > 0 || pending.len() <= limit || pending.contains(&record_delimiter_end) {
+        return Ok(());
+    }
+    let preview = pending[..MAX_REPORTED_FIELD_BYTES.min(pending.len())]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let msg = format!(
+        "no record delimiter found in the first {} bytes, the record_delimiter may be misconfigured, the file starts with bytes [{}]",
+        pending.len(),
+        preview
+    );
+    Err(csv_error(&msg, path, row))
+}
+
+/// Trim an unquoted string field as `format_settings` asks.
+fn trim_field<'a>(value: &'a [u8], format_settings: &FormatSettings) -> &'a [u8] {
+    let mut value = value;
+    if format_settings.trim_leading_spaces {
+        value = value.trim_start();
+    }
+    if format_settings.trim_trailing_spaces {
+        value = value.trim_end();
+    }
+    value
+}
+
+fn is_numeric_column(schema: &DataSchemaRef, column_index: usize) -> bool {
+    let data_type = schema.field(column_index).data_type();
+    remove_nullable(data_type).data_type_id().is_numeric()
+}
+
+fn is_float_column(schema: &DataSchemaRef, column_index: usize) -> bool {
+    let data_type = schema.field(column_index).data_type();
+    remove_nullable(data_type).data_type_id().is_floating()
+}
+
+fn get_case_transform(settings: &Settings) -> Result<CaseTransform> {
+    match settings
+        .get_format_case_transform()?
+        .to_lowercase()
+        .as_str()
+    {
+        "none" => Ok(CaseTransform::None),
+        "upper" => Ok(CaseTransform::Upper),
+        "lower" => Ok(CaseTransform::Lower),
+        v => Err(ErrorCode::InvalidArgument(format!(
+            "case_transform can only be 'none', 'upper' or 'lower', got '{}'",
+            v
+        ))),
+    }
+}
+
+fn get_binary_encoding(settings: &Settings) -> Result<BinaryEncoding> {
+    match settings
+        .get_format_binary_encoding()?
+        .to_lowercase()
+        .as_str()
+    {
+        "raw" => Ok(BinaryEncoding::Raw),
+        "hex" => Ok(BinaryEncoding::Hex),
+        "base64" => Ok(BinaryEncoding::Base64),
+        v => Err(ErrorCode::InvalidArgument(format!(
+            "binary_encoding can only be 'raw', 'hex' or 'base64', got '{}'",
+            v
+        ))),
+    }
+}
+
+fn get_timestamp_unit(settings: &Settings) -> Result<TimestampUnit> {
+    match settings
+        .get_format_timestamp_unit()?
+        .to_lowercase()
+        .as_str()
+    {
+        "datetime" => Ok(TimestampUnit::Datetime),
+        "epoch_s" => Ok(TimestampUnit::EpochSeconds),
+        "epoch_ms" => Ok(TimestampUnit::EpochMillis),
+        "epoch_us" => Ok(TimestampUnit::EpochMicros),
+        v => Err(ErrorCode::InvalidArgument(format!(
+            "timestamp_unit can only be 'datetime', 'epoch_s', 'epoch_ms' or 'epoch_us', got '{}'",
+            v
+        ))),
+    }
+}
+
+/// The microseconds of an epoch integer of a timestamp column, None unless the values of
+/// timestamp columns are epoch integers.
+fn match_epoch_timestamp(
+    schema: &DataSchemaRef,
+    column_index: usize,
+    data: &[u8],
+    format_settings: &FormatSettings,
+) -> Option<std::result::Result<i64, String>> {
+    let data_type = schema.field(column_index).data_type();
+    if !remove_nullable(data_type).data_type_id().is_date_time() {
+        return None;
+    }
+    format_settings.timestamp_unit.epoch_to_micros(data)
+}
+
+fn decode_binary(value: &[u8], binary_encoding: BinaryEncoding) -> Result<Vec<u8>> {
+    match binary_encoding {
+        BinaryEncoding::Raw => Ok(value.to_vec()),
+        BinaryEncoding::Hex => {
+            hex::decode(value).map_err(|e| ErrorCode::BadBytes(format!("invalid hex value: {}", e)))
+        }
+        BinaryEncoding::Base64 => base64::decode(value)
+            .map_err(|e| ErrorCode::BadBytes(format!("invalid base64 value: {}", e))),
+    }
+}
+
+/// Transform the UTF-8 content, only ASCII letters are transformed if it is not valid UTF-8.
+fn transform_case(value: &[u8], case_transform: CaseTransform) -> Vec<u8> {
+    match (std::str::from_utf8(value), case_transform) {
+        (_, CaseTransform::None) => value.to_vec(),
+        (Ok(s), CaseTransform::Upper) => s.to_uppercase().into_bytes(),
+        (Ok(s), CaseTransform::Lower) => s.to_lowercase().into_bytes(),
+        (Err(_), CaseTransform::Upper) => value.to_ascii_uppercase(),
+        (Err(_), CaseTransform::Lower) => value.to_ascii_lowercase(),
+    }
+}
+
+fn is_string_column(schema: &DataSchemaRef, column_index: usize) -> bool {
+    let data_type = schema.field(column_index).data_type();
+    remove_nullable(data_type).data_type_id().is_string()
+}
+
+/// What is wrong with a record, to tell a ragged file from a wrong schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvErrorKind {
+    Parse,
+    // the record has the same number of fields as the first record, which does not match the table
+    SchemaMismatch,
+    // the record has a different number of fields from the first record
+    RaggedRecord,
+}
+
+/// The location of a CSV parse failure, converted to `ErrorCode::BadBytes` when leaving the format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvParseError {
+    pub path: String,
+    // 0-based
+    pub row: usize,
+    pub column: Option<usize>,
+    pub raw_field: Option<Vec<u8>>,
+    // the fields of the record as split, for the errors of the structure, e.g. `["a", "b"]`
+    pub record_preview: Option<String>,
+    pub message: String,
+    pub kind: CsvErrorKind,
+}
+
+impl CsvParseError {
+    pub fn with_field(mut self, column: usize, raw_field: &[u8]) -> Self {
+        self.column = Some(column);
+        self.raw_field = Some(raw_field.to_vec());
+        self
+    }
+
+    pub fn with_record_preview(mut self, preview: Option<String>) -> Self {
+        self.record_preview = preview;
+        self
+    }
+}
+
+impl Display for CsvParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            CsvErrorKind::Parse => write!(
+                f,
+                "fail to parse CSV {}:{} {} ",
+                self.path,
+                self.row + 1,
+                self.message
+            ),
+            kind => write!(
+                f,
+                "fail to parse CSV {}:{} {:?}: {} ",
+                self.path,
+                self.row + 1,
+                kind,
+                self.message
+            ),
+        }?;
+        match &self.record_preview {
+            Some(preview) => write!(f, "record: {} ", preview),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<CsvParseError> for ErrorCode {
+    fn from(e: CsvParseError) -> Self {
+        // the location is kept as is for `InputContext::load_error_of`
+        ErrorCode::BadBytes(e.to_string()).with_detail(e)
+    }
+}
+
+fn csv_error(msg: &str, path: &str, row: usize) -> CsvParseError {
+    CsvParseError {
+        path: path.to_string(),
+        row,
+        column: None,
+        raw_field: None,
+        record_preview: None,
+        message: msg.to_string(),
+        kind: CsvErrorKind::Parse,
+    }
+}
+
+/// The fields of a record for the error messages, cut after `max_bytes` bytes of the fields,
+/// None if `max_bytes` is 0. The record starts with `remain` kept from the last read.
+/// A UTF-8 character is not cut, the field ends before it.
+pub fn record_preview(
+    remain: &[u8],
+    data: &[u8],
+    field_ends: &[usize],
+    max_bytes: usize,
+) -> Option<String> {
+    if max_bytes == 0 {
+        return None;
+    }
+    let record = [remain, data].concat();
+    let mut preview = String::from("[");
+    let mut budget = max_bytes;
+    let mut start = 0;
+    for (i, end) in field_ends.iter().enumerate() {
+        let end = (*end).min(record.len());
+        let field = &record[start.min(end)..end];
+        start = end;
+        if i > 0 {
+            preview.push_str(", ");
+        }
+        if field.len() > budget {
+            let mut cut = budget;
+            while cut > 0 && field[cut] & 0xC0 == 0x80 {
+                cut -= 1;
+            }
+            if cut > 0 {
+                verbose_string(&field[..cut], &mut preview);
+            }
+            preview.push_str("...");
+            break;
+        }
+        budget -= field.len();
+        verbose_string(field, &mut preview);
+    }
+    preview.push(']');
+    Some(preview)
+}
+
+/// The error of a record with `num_fields` fields, while the table has `expect` columns.
+fn field_count_error(
+    expect: usize,
+    num_fields: usize,
+    first_row_fields: Option<usize>,
+    path: &str,
+    row: usize,
+) -> CsvParseError {
+    let (kind, msg) = match first_row_fields {
+        Some(first) if first != num_fields => (
+            CsvErrorKind::RaggedRecord,
+            format!(
+                "expect {} fields as the first record, got {}",
+                first, num_fields
+            ),
+        ),
+        _ if num_fields < expect => (
+            CsvErrorKind::SchemaMismatch,
+            format!("expect {} fields, only found {}", expect, num_fields),
+        ),
+        _ => (
+            CsvErrorKind::SchemaMismatch,
+            format!("too many fields, expect {}, got {}", expect, num_fields),
+        ),
+    };
+    CsvParseError {
+        kind,
+        ..csv_error(&msg, path, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_base::base::tokio::sync::mpsc;
+    use common_base::base::Progress;
+    use common_datavalues::prelude::*;
+    use common_exception::Result;
+    use common_io::prelude::FormatSettings;
+    use common_settings::Settings;
+    use csv_core::ReadRecordResult;
+
+    use super::batch_row;
+    use super::CsvRecordReader;
+    use super::InputFormatCSV;
+    use crate::processors::sources::input_formats::delimiter::RecordDelimiter;
+    use crate::processors::sources::input_formats::input_format_text::AligningState;
+    use crate::processors::sources::input_formats::input_pipeline::AligningStateTrait;
+    use crate::processors::sources::input_formats::input_split::SplitInfo;
+    use crate::processors::sources::input_formats::InputContext;
+
+    /// Align the chunks of a CSV file of `num_fields` string columns as a streaming load does,
+    /// return the offset and the fields of each row. The quoted fields are prefixed by `Q:`.
+    fn align_chunks(
+        chunks: &[&[u8]],
+        format_settings: &FormatSettings,
+        record_delimiter: &RecordDelimiter,
+        num_fields: usize,
+    ) -> Result<Vec<(usize, Vec<String>)>> {
+        let fields = (0..num_fields)
+            .map(|i| DataField::new_nullable(&format!("c{}", i), Vu8::to_data_type()))
+            .collect::<Vec<_>>();
+        let (_tx, rx) = mpsc::channel(1);
+        let mut ctx = futures::executor::block_on(InputContext::try_create_from_insert(
+            "CSV",
+            rx,
+            Settings::default_settings("test"),
+            DataSchemaRefExt::create(fields),
+            Arc::new(Progress::create()),
+            false,
+        ))?;
+        ctx.format_settings = format_settings.clone();
+        ctx.field_delimiter = format_settings.field_delimiter[0];
+        ctx.record_delimiter = record_delimiter.clone();
+        let ctx = Arc::new(ctx);
+        let split_info = Arc::new(SplitInfo::from_stream_split(
+            "test.csv".to_string(),
+            0,
+            None,
+        ));
+        let mut state = AligningState::<InputFormatCSV>::try_create(&ctx, &split_info)?;
+
+        let mut row_batches = vec![];
+        for chunk in chunks.iter().filter(|c| !c.is_empty()) {
+            row_batches.extend(state.align(Some(chunk.to_vec()))?);
+        }
+        row_batches.extend(state.align(None)?);
+
+        let mut rows = vec![];
+        for batch in &row_batches {
+            for i in 0..batch.row_ends.len() {
+                let row = batch_row(batch, i);
+                let mut start = 0;
+                let mut fields = vec![];
+                for f in i * num_fields..(i + 1) * num_fields {
+                    let end = batch.field_ends[f];
+                    let prefix = if batch.field_quoted[f] { "Q:" } else { "" };
+                    fields.push(prefix.to_string() + &String::from_utf8_lossy(&row[start..end]));
+                    start = end;
+                }
+                rows.push((batch.row_offsets[i], fields));
+            }
+        }
+        Ok(rows)
+    }
+
+    fn read_all(reader: &mut CsvRecordReader, data: &[u8]) -> Vec<Vec<String>> {
+        let mut out = vec![0u8; data.len() + reader.max_pending() + 1];
+        let mut ends = vec![0usize; 16];
+        let mut quoted = vec![false; 16];
+        let (mut out_pos, mut endlen) = (0, 0);
+        let mut records = vec![];
+        let mut buf = data;
+        loop {
+            let (result, n_in, n_out, n_end) = reader.read_record(
+                buf,
+                &mut out[out_pos..],
+                &mut ends[endlen..],
+                &mut quoted[endlen..],
+            );
+            buf = &buf[n_in..];
+            out_pos += n_out;
+            endlen += n_end;
+            match result {
+                ReadRecordResult::Record => {
+                    let mut start = 0;
+                    let mut fields = vec![];
+                    for end in &ends[..endlen] {
+                        fields.push(String::from_utf8_lossy(&out[start..*end]).to_string());
+                        start = *end;
+                    }
+                    records.push(fields);
+                    out_pos = 0;
+                    endlen = 0;
+                }
+                // the next empty input means EOF
+                ReadRecordResult::InputEmpty => {}
+                ReadRecordResult::End => return records,
+                r => panic!("unexpected {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn test_csv_core_for_simple_dialects() -> Result<()> {
+        let format_settings = FormatSettings {
+            field_delimiter: vec![b','],
+            quote_char: Some(b'"'),
+            ..Default::default()
+        };
+        let record_delimiter = RecordDelimiter::Crlf;
+        let data = b"a,\"b,c\"\n\"d\"\"e\",\r\n\n1,\"2\n3\"";
+        let expected = vec![vec!["a", "b,c"], vec!["d\"e", ""], vec!["1", "2\n3"]];
+
+        let mut core = CsvRecordReader::try_create(&format_settings, &record_delimiter, false)?;
+        assert!(matches!(core, CsvRecordReader::Core(_)));
+        assert_eq!(read_all(&mut core, data), expected);
+
+        // the splitter is only needed to tell the quoted fields
+        let mut splitter = CsvRecordReader::try_create(&format_settings, &record_delimiter, true)?;
+        assert!(matches!(splitter, CsvRecordReader::Splitter(_)));
+        assert_eq!(read_all(&mut splitter, data), expected);
+
+        // the dialects csv-core can not handle
+        let multi_byte = FormatSettings {
+            field_delimiter: b"||".to_vec(),
+            ..format_settings.clone()
+        };
+        assert!(matches!(
+            CsvRecordReader::try_create(&multi_byte, &record_delimiter, false)?,
+            CsvRecordReader::Splitter(_)
+        ));
+        let commented = FormatSettings {
+            comment_token: b"#".to_vec(),
+            ..format_settings.clone()
+        };
+        assert!(matches!(
+            CsvRecordReader::try_create(&commented, &record_delimiter, false)?,
+            CsvRecordReader::Splitter(_)
+        ));
+        assert!(matches!(
+            CsvRecordReader::try_create(
+                &format_settings,
+                &RecordDelimiter::Multi(b"\r\n\r\n".to_vec()),
+                false
+            )?,
+            CsvRecordReader::Splitter(_)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_align_records_across_chunks() -> Result<()> {
+        let format_settings = FormatSettings {
+            field_delimiter: vec![b','],
+            quote_char: Some(b'"'),
+            ..Default::default()
+        };
+        let record_delimiter = RecordDelimiter::Any(b'|');
+        let cases: Vec<(&str, Vec<(usize, Vec<&str>)>)> = vec![
+            // the record delimiter in quotes
+            ("\"a|b\",1|c,2|", vec![
+                (0, vec!["Q:a|b", "1"]),
+                (8, vec!["c", "2"]),
+            ]),
+            // after a doubled quote in quotes
+            ("a,\"x\"\"|y\"|b,2|", vec![
+                (0, vec!["a", "Q:x\"|y"]),
+                (10, vec!["b", "2"]),
+            ]),
+            // a quoted last field followed by the record delimiter
+            ("a,\"q|\"|b,\"|r\"", vec![
+                (0, vec!["a", "Q:q|"]),
+                (7, vec!["b", "Q:|r"]),
+            ]),
+            ("\"|\"|\"\"|x|", vec![
+                (0, vec!["Q:|"]),
+                (4, vec!["Q:"]),
+                (7, vec!["x"]),
+            ]),
+            // a quoted field spanning lines
+            ("a,\"b\nc|\"|d,\"\"\"|\"\"\"|", vec![
+                (0, vec!["a", "Q:b\nc|"]),
+                (9, vec!["d", "Q:\"|\""]),
+            ]),
+        ];
+        for (data, expected) in cases {
+            let data = data.as_bytes();
+            let num_fields = expected[0].1.len();
+            let expected: Vec<(usize, Vec<String>)> = expected
+                .iter()
+                .map(|(offset, r)| (*offset, r.iter().map(|f| f.to_string()).collect()))
+                .collect();
+            let align = |chunks: &[&[u8]]| {
+                align_chunks(chunks, &format_settings, &record_delimiter, num_fields)
+            };
+            assert_eq!(align(&[data])?, expected);
+            // the chunks may end anywhere, e.g. between a quote and the record delimiter
+            for i in 0..=data.len() {
+                for j in i..=data.len() {
+                    let chunks = [&data[..i], &data[i..j], &data[j..]];
+                    assert_eq!(align(&chunks)?, expected, "chunks {:?}", chunks);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_align_byte_by_byte() -> Result<()> {
+        let csv = FormatSettings {
+            field_delimiter: vec![b','],
+            quote_char: Some(b'"'),
+            ..Default::default()
+        };
+        let escaped = FormatSettings {
+            escape_char: Some(b'\\'),
+            double_quote: false,
+            ..csv.clone()
+        };
+        let commented = FormatSettings {
+            comment_token: b"#".to_vec(),
+            ..csv.clone()
+        };
+        let cases: Vec<(&FormatSettings, RecordDelimiter, &str)> = vec![
+            (
+                &csv,
+                RecordDelimiter::Crlf,
+                "a,\"b\nc\"\n\"d\r\n\",e\r\n\"\"\"\n\"\"\",f",
+            ),
+            (
+                &csv,
+                RecordDelimiter::Any(b'|'),
+                "\"|\",1|\"a||b\",\"\"|,|\"x\"\"|\"\"\",2",
+            ),
+            (
+                &csv,
+                RecordDelimiter::Multi(b"|;".to_vec()),
+                "\"a|;b\",1|;c,\"|\"|;\"|;\",|;",
+            ),
+            (
+                &escaped,
+                RecordDelimiter::Crlf,
+                "\"a\\\"\nb\",c\n\"\\\\\",d\n\"\\\n\",e\n",
+            ),
+            (
+                &commented,
+                RecordDelimiter::Crlf,
+                "#\"\n\"#\n\",1\n  # x\n2,3",
+            ),
+        ];
+        for (format_settings, record_delimiter, data) in cases {
+            let data = data.as_bytes();
+            let expected = align_chunks(&[data], format_settings, &record_delimiter, 2)?;
+            assert!(!expected.is_empty());
+            // each chunk ends in the middle of a record, a quoted field or a record delimiter
+            let chunks: Vec<&[u8]> = data.chunks(1).collect();
+            assert_eq!(
+                align_chunks(&chunks, format_settings, &record_delimiter, 2)?,
+                expected,
+                "data {:?}",
+                String::from_utf8_lossy(data)
+            );
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,60 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+use common_meta_types::StageFileFormatType;
+use common_settings::Settings;
+
+use crate::processors::sources::input_formats::impls::input_format_csv::InputFormatCSV;
+use crate::processors::sources::input_formats::input_format_text::AligningState;
+use crate::processors::sources::input_formats::input_format_text::BlockBuilder;
+use crate::processors::sources::input_formats::input_format_text::InputFormatTextBase;
+use crate::processors::sources::input_formats::input_format_text::RowBatch;
+
+/// CSV with a row of column names and a row of their types first,
+/// like `CSVWithNamesAndTypes` of ClickHouse.
+///
+/// The fields are mapped to the columns by the names, as `format_header_mapping` does.
+/// The types are not used to read the values, a type not of its column is an error,
+/// e.g. `Int64` for a String column. A non-nullable type is accepted for a nullable column.
+pub struct InputFormatCSVWithTypes {}
+
+impl InputFormatTextBase for InputFormatCSVWithTypes {
+    fn format_type() -> StageFileFormatType {
+        StageFileFormatType::CsvWithTypes
+    }
+
+    fn get_format_settings(settings: &Arc<Settings>) -> Result<FormatSettings> {
+        InputFormatCSV::get_format_settings(settings)
+    }
+
+    fn default_field_delimiter() -> u8 {
+        b','
+    }
+
+    fn deserialize(builder: &mut BlockBuilder<Self>, batch: RowBatch) -> Result<()> {
+        InputFormatCSV::deserialize_records(builder, batch)
+    }
+
+    fn align(state: &mut AligningState<Self>, buf_in: &[u8]) -> Result<Vec<RowBatch>> {
+        InputFormatCSV::align_records(state, buf_in, false)
+    }
+
+    fn align_flush(state: &mut AligningState<Self>) -> Result<Vec<RowBatch>> {
+        InputFormatCSV::flush_records(state)
+    }
+}
@@ -0,0 +1,245 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::sync::Arc;
+
+use bstr::ByteSlice;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::TypeDeserializer;
+use common_datavalues::TypeDeserializerImpl;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_formats::verbose_string;
+use common_io::prelude::BufferReadExt;
+use common_io::prelude::FixedWidthColumn;
+use common_io::prelude::FormatSettings;
+use common_io::prelude::NestedCheckpointReader;
+use common_meta_types::StageFileFormatType;
+use common_settings::Settings;
+
+use crate::processors::sources::input_formats::impls::input_format_tsv::format_column_error;
+use crate::processors::sources::input_formats::input_format_text::de_empty_field;
+use crate::processors::sources::input_formats::input_format_text::get_datetime_formats;
+use crate::processors::sources::input_formats::input_format_text::get_empty_field_as;
+use crate::processors::sources::input_formats::input_format_text::get_time_zone;
+use crate::processors::sources::input_formats::input_format_text::parse_column_defaults;
+use crate::processors::sources::input_formats::input_format_text::rollback_row;
+use crate::processors::sources::input_formats::input_format_text::AligningState;
+use crate::processors::sources::input_formats::input_format_text::BlockBuilder;
+use crate::processors::sources::input_formats::input_format_text::InputFormatTextBase;
+use crate::processors::sources::input_formats::input_format_text::RowBatch;
+
+/// Each field occupies a fixed byte range of the record, as `format_fixed_width_columns` says.
+pub struct InputFormatFixedWidth {}
+
+impl InputFormatFixedWidth {
+    #[allow(clippy::too_many_arguments)]
+    fn read_row(
+        buf: &[u8],
+        deserializers: &mut [TypeDeserializerImpl],
+        schema: &DataSchemaRef,
+        projection: Option<&[usize]>,
+        format_settings: &FormatSettings,
+        path: &str,
+        offset: usize,
+        row_index: Option<usize>,
+    ) -> Result<()> {
+        let line = buf.strip_suffix(b"\n").unwrap_or(buf);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut err_msg = None;
+        let mut num_appended = 0;
+        for (c, deserializer) in deserializers.iter_mut().enumerate() {
+            let column_index = projection.map_or(c, |p| p[c]);
+            let column = &format_settings.fixed_width_columns[column_index];
+            let end = column.start + column.len;
+            if end > line.len() && !format_settings.allow_short_records {
+                err_msg = Some(format!(
+                    "record of {} bytes is shorter than the end {} of column {}",
+                    line.len(),
+                    end,
+                    column_index
+                ));
+                break;
+            }
+            let col_data = &line[column.start.min(line.len())..end.min(line.len())];
+            let value = trim_padding(col_data, column.pad);
+            if value.is_empty() {
+                if let Err(m) = de_empty_field(deserializer, schema, column_index, format_settings)
+                {
+                    err_msg = Some(format_column_error(schema, column_index, col_data, &m));
+                    break;
+                }
+            } else {
+                let mut reader = NestedCheckpointReader::new(value);
+                if let Err(e) = deserializer.de_text(&mut reader, format_settings) {
+                    err_msg = Some(format_column_error(
+                        schema,
+                        column_index,
+                        col_data,
+                        &e.message(),
+                    ));
+                    break;
+                }
+                if reader.must_eof().is_err() {
+                    num_appended += 1;
+                    err_msg = Some(format_column_error(
+                        schema,
+                        column_index,
+                        col_data,
+                        "bad field end",
+                    ));
+                    break;
+                }
+            }
+            num_appended += 1;
+        }
+
+        if let Some(m) = err_msg {
+            rollback_row(deserializers, num_appended);
+            let row_info = if let Some(r) = row_index {
+                format!("at row {},", r)
+            } else {
+                String::new()
+            };
+            let mut msg = format!(
+                "fail to parse fixed-width {} at offset {}, {} reason={}, row data: ",
+                path, offset, row_info, m
+            );
+            verbose_string(buf, &mut msg);
+            Err(ErrorCode::BadBytes(msg))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl InputFormatTextBase for InputFormatFixedWidth {
+    fn format_type() -> StageFileFormatType {
+        StageFileFormatType::FixedWidth
+    }
+
+    fn is_splittable() -> bool {
+        true
+    }
+
+    fn get_format_settings(settings: &Arc<Settings>) -> Result<FormatSettings> {
+        let timezone = get_time_zone(settings)?;
+        let (date_format, timestamp_format) = get_datetime_formats(settings)?;
+        Ok(FormatSettings {
+            record_delimiter: settings.get_format_record_delimiter()?.into_bytes(),
+            empty_as_default: settings.get_format_empty_as_default()? > 0,
+            empty_field_as: get_empty_field_as(settings)?,
+            column_defaults: parse_column_defaults(&settings.get_format_column_defaults()?)?,
+            fixed_width_columns: parse_fixed_width_columns(
+                &settings.get_format_fixed_width_columns()?,
+            )?,
+            allow_short_records: settings.get_format_allow_short_records()? > 0,
+            quote_char: None,
+            timezone,
+            date_format,
+            timestamp_format,
+            ..Default::default()
+        })
+    }
+
+    fn default_field_delimiter() -> u8 {
+        // not used, the fields are not delimited
+        b' '
+    }
+
+    fn deserialize(builder: &mut BlockBuilder<Self>, batch: RowBatch) -> Result<()> {
+        let num_columns = builder.ctx.format_settings.fixed_width_columns.len();
+        if num_columns != builder.ctx.schema.num_fields() {
+            return Err(ErrorCode::BadArguments(format!(
+                "format_fixed_width_columns has {} columns, but the table has {}",
+                num_columns,
+                builder.ctx.schema.num_fields()
+            )));
+        }
+        tracing::debug!(
+            "fixed-width deserializing row batch {}, id={}, start_row={:?}, offset={}",
+            batch.path,
+            batch.batch_id,
+            batch.start_row,
+            batch.offset
+        );
+        let mut start = 0usize;
+        let start_row = batch.start_row;
+        for (i, end) in batch.row_ends.iter().enumerate() {
+            let buf = &batch.data[start..*end];
+            if let Err(e) = Self::read_row(
+                buf,
+                &mut builder.mutable_columns,
+                &builder.ctx.schema,
+                builder.ctx.projection.as_deref(),
+                &builder.ctx.format_settings,
+                &batch.path,
+                batch.offset + start,
+                start_row.map(|n| n + i),
+            ) {
+                builder.skip_error_row(e, buf, start_row.map(|n| n + i))?;
+            }
+            start = *end;
+        }
+        Ok(())
+    }
+
+    fn align(state: &mut AligningState<Self>, buf: &[u8]) -> Result<Vec<RowBatch>> {
+        Ok(state.align_by_record_delimiter(buf))
+    }
+}
+
+/// Trim the padding around a field, a zero padded field keeps at least one digit.
+fn trim_padding(data: &[u8], pad: u8) -> &[u8] {
+    let mut data = data.trim();
+    if pad == b'0' {
+        while data.len() > 1 && data[0] == b'0' && data[1].is_ascii_digit() {
+            data = &data[1..];
+        }
+    }
+    data
+}
+
+/// Parse the column specs like `0:10,10:8:zero`.
+fn parse_fixed_width_columns(s: &str) -> Result<Vec<FixedWidthColumn>> {
+    let bad = |spec: &str| {
+        ErrorCode::InvalidArgument(format!(
+            "bad fixed_width_columns '{}', expect 'start:len' or 'start:len:zero'",
+            spec
+        ))
+    };
+    let mut columns = vec![];
+    for spec in s
+        .split(',')
+        .map(|spec| spec.trim())
+        .filter(|s| !s.is_empty())
+    {
+        let parts: Vec<&str> = spec.split(':').collect();
+        let (start, len) = match parts[..] {
+            [start, len] | [start, len, _] => (
+                start.trim().parse::<usize>().map_err(|_| bad(spec))?,
+                len.trim().parse::<usize>().map_err(|_| bad(spec))?,
+            ),
+            _ => return Err(bad(spec)),
+        };
+        let pad = match parts.get(2).map(|p| p.trim().to_lowercase()) {
+            None => b' ',
+            Some(p) if p == "space" => b' ',
+            Some(p) if p == "zero" => b'0',
+            Some(_) => return Err(bad(spec)),
+        };
+        columns.push(FixedWidthColumn { start, len, pad });
+    }
+    Ok(columns)
+}
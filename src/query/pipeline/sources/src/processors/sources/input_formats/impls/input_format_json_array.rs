@@ -0,0 +1,226 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::mem;
+use std::sync::Arc;
+
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+use common_meta_types::StageFileFormatType;
+use common_settings::Settings;
+
+use crate::processors::sources::input_formats::impls::input_format_ndjson::InputFormatNDJson;
+use crate::processors::sources::input_formats::input_format_text::AligningState;
+use crate::processors::sources::input_formats::input_format_text::BlockBuilder;
+use crate::processors::sources::input_formats::input_format_text::InputFormatTextBase;
+use crate::processors::sources::input_formats::input_format_text::RowBatch;
+
+/// A file of one JSON array, each element of which is a row.
+///
+/// The elements are carved out by the aligner, and deserialized the same way as NDJSON.
+pub struct InputFormatJsonArray {}
+
+impl InputFormatTextBase for InputFormatJsonArray {
+    fn format_type() -> StageFileFormatType {
+        StageFileFormatType::Json
+    }
+
+    fn get_format_settings(settings: &Arc<Settings>) -> Result<FormatSettings> {
+        InputFormatNDJson::get_format_settings(settings)
+    }
+
+    fn default_field_delimiter() -> u8 {
+        b','
+    }
+
+    fn deserialize(builder: &mut BlockBuilder<Self>, batch: RowBatch) -> Result<()> {
+        InputFormatNDJson::deserialize_batch(
+            &builder.ctx,
+            &mut builder.mutable_columns,
+            batch,
+            "JSON array",
+        )
+    }
+
+    fn align(state: &mut AligningState<Self>, buf: &[u8]) -> Result<Vec<RowBatch>> {
+        let reader = state.json_array_reader.as_mut().expect("must success");
+        let mut row_ends = vec![];
+        reader
+            .read(buf, &mut row_ends)
+            .map_err(|e| json_array_error(&state.path, state.offset + e.0, e.1))?;
+        state.offset += buf.len();
+        if row_ends.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let last_end = row_ends[row_ends.len() - 1];
+        let remain = reader.out[last_end..].to_vec();
+        let mut data = mem::replace(&mut reader.out, remain);
+        data.truncate(last_end);
+        let row_batch = RowBatch {
+            data,
+            row_ends,
+            path: state.path.to_string(),
+            file_index: state.file_index,
+            batch_id: state.batch_id,
+            start_row: Some(state.rows),
+            ..Default::default()
+        };
+        tracing::debug!(
+            "json array aligner: batch {}, {} rows",
+            row_batch.batch_id,
+            row_batch.row_ends.len()
+        );
+        state.batch_id += 1;
+        state.rows += row_batch.row_ends.len();
+        Ok(vec![row_batch])
+    }
+
+    fn align_flush(state: &mut AligningState<Self>) -> Result<Vec<RowBatch>> {
+        let reader = state.json_array_reader.as_ref().expect("must success");
+        match reader.state {
+            // an empty file has no rows
+            JsonArrayState::BeforeArray | JsonArrayState::End => Ok(vec![]),
+            _ => Err(json_array_error(
+                &state.path,
+                state.offset,
+                "unexpected eof, expect ']'",
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum JsonArrayState {
+    BeforeArray,
+    BeforeElement,
+    InElement,
+    AfterElement,
+    End,
+}
+
+/// Splits the elements out of a JSON array, which may be cut at any byte between reads.
+///
+/// The brackets, the commas and the blanks between elements are dropped,
+/// the bytes of the elements are appended to `out`.
+pub struct JsonArrayReaderState {
+    state: JsonArrayState,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    // the complete elements not taken yet, and the incomplete one
+    out: Vec<u8>,
+}
+
+impl Default for JsonArrayReaderState {
+    fn default() -> Self {
+        Self {
+            state: JsonArrayState::BeforeArray,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            out: vec![],
+        }
+    }
+}
+
+impl JsonArrayReaderState {
+    /// push the end of each complete element in `out` to `row_ends`,
+    /// return the position in `buf` and the message on error.
+    fn read(
+        &mut self,
+        buf: &[u8],
+        row_ends: &mut Vec<usize>,
+    ) -> std::result::Result<(), (usize, &'static str)> {
+        for (i, b) in buf.iter().enumerate() {
+            let b = *b;
+            match self.state {
+                JsonArrayState::BeforeArray => match b {
+                    b'[' => self.state = JsonArrayState::BeforeElement,
+                    _ if b.is_ascii_whitespace() => {}
+                    _ => return Err((i, "expect '[' at the beginning")),
+                },
+                JsonArrayState::BeforeElement => match b {
+                    // `[]`, or a trailing comma
+                    b']' => self.state = JsonArrayState::End,
+                    b',' => return Err((i, "expect an element before ','")),
+                    _ if b.is_ascii_whitespace() => {}
+                    _ => {
+                        self.state = JsonArrayState::InElement;
+                        self.read_element_byte(b, row_ends);
+                    }
+                },
+                JsonArrayState::InElement => {
+                    if !self.in_string && self.depth == 0 && (b == b',' || b == b']') {
+                        // end of a scalar element
+                        row_ends.push(self.out.len());
+                        self.state = if b == b',' {
+                            JsonArrayState::BeforeElement
+                        } else {
+                            JsonArrayState::End
+                        };
+                    } else {
+                        self.read_element_byte(b, row_ends);
+                    }
+                }
+                JsonArrayState::AfterElement => match b {
+                    b',' => self.state = JsonArrayState::BeforeElement,
+                    b']' => self.state = JsonArrayState::End,
+                    _ if b.is_ascii_whitespace() => {}
+                    _ => return Err((i, "expect ',' or ']' after an element")),
+                },
+                JsonArrayState::End => {
+                    if !b.is_ascii_whitespace() {
+                        return Err((i, "unexpected data after ']'"));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_element_byte(&mut self, b: u8, row_ends: &mut Vec<usize>) {
+        self.out.push(b);
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if b == b'\\' {
+                self.escaped = true;
+            } else if b == b'"' {
+                self.in_string = false;
+            }
+            return;
+        }
+        match b {
+            b'"' => self.in_string = true,
+            b'{' | b'[' => self.depth += 1,
+            b'}' | b']' => {
+                self.depth = self.depth.saturating_sub(1);
+                if self.depth == 0 {
+                    row_ends.push(self.out.len());
+                    self.state = JsonArrayState::AfterElement;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn json_array_error(path: &str, offset: usize, msg: &str) -> ErrorCode {
+    ErrorCode::BadBytes(format!(
+        "fail to parse JSON array {} at offset {}: {}",
+        path, offset, msg
+    ))
+}
@@ -30,6 +30,7 @@ use crate::processors::sources::input_formats::input_format_text::AligningState;
 use crate::processors::sources::input_formats::input_format_text::BlockBuilder;
 use crate::processors::sources::input_formats::input_format_text::InputFormatTextBase;
 use crate::processors::sources::input_formats::input_format_text::RowBatch;
+use crate::processors::sources::input_formats::InputContext;
 
 pub struct InputFormatNDJson {}
 
@@ -49,11 +50,23 @@ impl InputFormatNDJson {
             }
         }
 
+        if !json.is_object() {
+            return Err(ErrorCode::BadBytes("expect a JSON object"));
+        }
+
         for (f, deser) in schema.fields().iter().zip(deserializers.iter_mut()) {
             let value = if format_settings.ident_case_sensitive {
-                &json[f.name().to_owned()]
+                json.get(f.name())
             } else {
-                &json[f.name().to_lowercase()]
+                json.get(f.name().to_lowercase())
+            };
+            let value = match value {
+                Some(value) => value,
+                None => {
+                    // the key is missing
+                    deser.de_default(format_settings);
+                    continue;
+                }
             };
 
             deser.de_json(value, format_settings).map_err(|e| {
@@ -93,26 +106,44 @@ impl InputFormatTextBase for InputFormatNDJson {
     }
 
     fn deserialize(builder: &mut BlockBuilder<Self>, batch: RowBatch) -> Result<()> {
-        let columns = &mut builder.mutable_columns;
+        Self::deserialize_batch(&builder.ctx, &mut builder.mutable_columns, batch, "NDJSON")
+    }
+
+    fn align(state: &mut AligningState<Self>, buf: &[u8]) -> Result<Vec<RowBatch>> {
+        let mut reader = state.ndjson_reader.take().expect("must success");
+        let record_delimiter_end = state.record_delimiter_end;
+        let row_batches =
+            state.align_by_row_ends(buf, |buf| reader.find_row_ends(buf, record_delimiter_end));
+        state.ndjson_reader = Some(reader);
+        Ok(row_batches)
+    }
+}
+
+impl InputFormatNDJson {
+    /// Each row of the batch is a JSON object, also used by `InputFormatJsonArray`.
+    pub fn deserialize_batch(
+        ctx: &InputContext,
+        columns: &mut [TypeDeserializerImpl],
+        batch: RowBatch,
+        format_name: &str,
+    ) -> Result<()> {
         let mut start = 0usize;
         let start_row = batch.start_row;
         for (i, end) in batch.row_ends.iter().enumerate() {
             let buf = &batch.data[start..*end];
             let buf = buf.trim();
             if !buf.is_empty() {
-                if let Err(e) = Self::read_row(
-                    buf,
-                    columns,
-                    &builder.ctx.format_settings,
-                    &builder.ctx.schema,
-                ) {
+                if let Err(e) =
+                    Self::read_row(buf, columns, &ctx.format_settings, &ctx.output_schema)
+                {
                     let row_info = if let Some(r) = start_row {
                         format!("row={},", r + i)
                     } else {
                         String::new()
                     };
                     let msg = format!(
-                        "fail to parse NDJSON: {},  path={}, offset={}, {}",
+                        "fail to parse {}: {},  path={}, offset={}, {}",
+                        format_name,
                         &batch.path,
                         e,
                         batch.offset + start,
@@ -125,9 +156,43 @@ impl InputFormatTextBase for InputFormatNDJson {
         }
         Ok(())
     }
+}
 
-    fn align(state: &mut AligningState<Self>, buf: &[u8]) -> Result<Vec<RowBatch>> {
-        Ok(state.align_by_record_delimiter(buf))
+/// Tracks the nesting of the JSON text across reads, so a record delimiter inside an object,
+/// an array or a string (e.g. a pretty-printed object) does not end the row.
+///
+/// Splits of a file are assumed to start at the top level, so records spanning lines should be
+/// loaded without `split_size`.
+#[derive(Default)]
+pub struct NdJsonReaderState {
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl NdJsonReaderState {
+    fn find_row_ends(&mut self, buf: &[u8], record_delimiter_end: u8) -> Vec<usize> {
+        let mut row_ends = vec![];
+        for (i, b) in buf.iter().enumerate() {
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if *b == b'\\' {
+                    self.escaped = true;
+                } else if *b == b'"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+            match *b {
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' => self.depth = self.depth.saturating_sub(1),
+                b if b == record_delimiter_end && self.depth == 0 => row_ends.push(i + 1),
+                _ => {}
+            }
+        }
+        row_ends
     }
 }
 
@@ -47,7 +47,6 @@ use common_settings::Settings;
 use futures::AsyncRead;
 use futures::AsyncSeek;
 use opendal::Operator;
-use similar_asserts::traits::MakeDiff;
 
 use crate::processors::sources::input_formats::delimiter::RecordDelimiter;
 use crate::processors::sources::input_formats::input_context::CopyIntoPlan;
@@ -95,7 +94,7 @@ impl InputFormat for InputFormatParquet {
         schema: &DataSchemaRef,
     ) -> Result<Vec<Arc<SplitInfo>>> {
         let mut infos = vec![];
-        for path in &plan.files {
+        for (file_index, path) in plan.files.iter().enumerate() {
             let obj = op.object(path);
             let size = obj.metadata().await?.content_length() as usize;
             let mut reader = obj.seekable_reader(..(size as u64));
@@ -103,10 +102,11 @@ impl InputFormat for InputFormatParquet {
                 .await
                 .map_err(|e| ErrorCode::ParquetError(e.to_string()))?;
             let row_groups = mem::take(&mut file_meta.row_groups);
-            let fields = Arc::new(get_fields(&file_meta, schema)?);
+            let fields = Arc::new(get_fields(&file_meta, schema, path)?);
             let read_file_meta = Arc::new(FileMeta { fields });
             let file_info = Arc::new(FileInfo {
                 path: path.clone(),
+                file_index,
                 size,
                 num_splits: row_groups.len(),
                 compress_alg: None,
@@ -337,7 +337,11 @@ impl AligningStateTrait for AligningState {
             let mut cursor = Cursor::new(file_in_memory);
             let file_meta =
                 read_metadata(&mut cursor).map_err(|e| ErrorCode::ParquetError(e.to_string()))?;
-            let read_fields = Arc::new(get_fields(&file_meta, &self.ctx.schema)?);
+            let read_fields = Arc::new(get_fields(
+                &file_meta,
+                &self.ctx.schema,
+                &self.split_info.file.path,
+            )?);
 
             let mut row_batches = Vec::with_capacity(file_meta.row_groups.len());
             for row_group in file_meta.row_groups.into_iter() {
@@ -358,10 +362,11 @@ impl AligningStateTrait for AligningState {
     }
 }
 
-fn get_fields(file_meta: &FileMetaData, schema: &DataSchemaRef) -> Result<Vec<Field>> {
+/// The parquet fields of the schema columns, matched by name.
+fn get_fields(file_meta: &FileMetaData, schema: &DataSchemaRef, path: &str) -> Result<Vec<Field>> {
     let infer_schema = read::infer_schema(file_meta)?;
     let mut read_fields = Vec::with_capacity(schema.num_fields());
-    for f in schema.fields().iter() {
+    for (i, f) in schema.fields().iter().enumerate() {
         if let Some(m) = infer_schema
             .fields
             .iter()
@@ -370,19 +375,24 @@ fn get_fields(file_meta: &FileMetaData, schema: &DataSchemaRef) -> Result<Vec<Fi
         {
             let tf = DataField::from(m);
             if remove_nullable(tf.data_type()) != remove_nullable(f.data_type()) {
-                let pair = (f, m);
-                let diff = pair.make_diff("expected_field", "infer_field");
                 return Err(ErrorCode::ParquetError(format!(
-                    "parquet schema mismatch, differ: {}",
-                    diff
+                    "parquet schema mismatch, column {} '{}' ({}) of file {}: the column in file is {}",
+                    i,
+                    f.name(),
+                    f.data_type(),
+                    path,
+                    tf.data_type(),
                 )));
             }
 
             read_fields.push(m.clone());
         } else {
             return Err(ErrorCode::ParquetError(format!(
-                "schema field size mismatch, expected to find column: {}",
-                f.name()
+                "fail to decode column {} '{}' ({}) of parquet file {}: not found in file",
+                i,
+                f.name(),
+                f.data_type(),
+                path,
             )));
         }
     }
@@ -406,3 +416,71 @@ pub fn split_column_metas_by_field(
     });
     r
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use common_arrow::parquet::read::read_metadata;
+    use common_datablocks::serialize_data_blocks;
+    use common_datablocks::DataBlock;
+    use common_datavalues::prelude::*;
+    use common_exception::Result;
+
+    use super::get_fields;
+    use super::ErrorCode;
+    use super::FileMetaData;
+
+    fn file_meta() -> Result<FileMetaData> {
+        let schema = DataSchemaRefExt::create(vec![
+            DataField::new("a", i32::to_data_type()),
+            DataField::new("b", StringType::new_impl()),
+        ]);
+        let block = DataBlock::create(schema.clone(), vec![
+            Series::from_data(vec![1i32, 2]),
+            Series::from_data(vec!["x", "y"]),
+        ]);
+        let mut buf = vec![];
+        serialize_data_blocks(vec![block], &schema, &mut buf)?;
+        read_metadata(&mut Cursor::new(buf)).map_err(|e| ErrorCode::ParquetError(e.to_string()))
+    }
+
+    #[test]
+    fn test_get_fields_by_name() -> Result<()> {
+        let file_meta = file_meta()?;
+        let schema = DataSchemaRefExt::create(vec![
+            DataField::new("B", StringType::new_impl()),
+            DataField::new_nullable("a", i32::to_data_type()),
+        ]);
+        let fields = get_fields(&file_meta, &schema, "t.parquet")?;
+        let names = fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["b", "a"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_fields_errors() -> Result<()> {
+        let file_meta = file_meta()?;
+
+        let schema = DataSchemaRefExt::create(vec![
+            DataField::new("a", i32::to_data_type()),
+            DataField::new("b", i64::to_data_type()),
+        ]);
+        let err = get_fields(&file_meta, &schema, "t.parquet").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::ParquetError("").code());
+        assert!(
+            err.message()
+                .starts_with("parquet schema mismatch, column 1 'b' (int64) of file t.parquet"),
+            "{}",
+            err.message()
+        );
+
+        let schema = DataSchemaRefExt::create(vec![DataField::new("c", i32::to_data_type())]);
+        let err = get_fields(&file_meta, &schema, "t.parquet").unwrap_err();
+        assert_eq!(
+            err.message(),
+            "fail to decode column 0 'c' (int32) of parquet file t.parquet: not found in file"
+        );
+        Ok(())
+    }
+}
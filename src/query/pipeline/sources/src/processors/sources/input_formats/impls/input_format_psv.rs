@@ -0,0 +1,74 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::sync::Arc;
+
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+use common_meta_types::StageFileFormatType;
+use common_settings::Settings;
+
+use crate::processors::sources::input_formats::impls::input_format_tsv::deserialize_separated;
+use crate::processors::sources::input_formats::input_format_text::get_datetime_formats;
+use crate::processors::sources::input_formats::input_format_text::get_empty_field_as;
+use crate::processors::sources::input_formats::input_format_text::get_time_zone;
+use crate::processors::sources::input_formats::input_format_text::parse_column_defaults;
+use crate::processors::sources::input_formats::input_format_text::AligningState;
+use crate::processors::sources::input_formats::input_format_text::BlockBuilder;
+use crate::processors::sources::input_formats::input_format_text::InputFormatTextBase;
+use crate::processors::sources::input_formats::input_format_text::RowBatch;
+
+/// Pipe separated values, parsed like TSV with `|` between fields.
+pub struct InputFormatPSV {}
+
+impl InputFormatTextBase for InputFormatPSV {
+    fn format_type() -> StageFileFormatType {
+        StageFileFormatType::Psv
+    }
+
+    fn is_splittable() -> bool {
+        true
+    }
+
+    fn get_format_settings(settings: &Arc<Settings>) -> Result<FormatSettings> {
+        let timezone = get_time_zone(settings)?;
+        let (date_format, timestamp_format) = get_datetime_formats(settings)?;
+        Ok(FormatSettings {
+            record_delimiter: settings.get_format_record_delimiter()?.into_bytes(),
+            field_delimiter: settings.get_format_field_delimiter()?.into_bytes(),
+            empty_as_default: settings.get_format_empty_as_default()? > 0,
+            empty_field_as: get_empty_field_as(settings)?,
+            column_defaults: parse_column_defaults(&settings.get_format_column_defaults()?)?,
+            null_bytes: vec![b'\\', b'N'],
+            // pipe separated exports rarely quote
+            quote_char: None,
+            timezone,
+            date_format,
+            timestamp_format,
+            ..Default::default()
+        })
+    }
+
+    fn default_field_delimiter() -> u8 {
+        b'|'
+    }
+
+    fn deserialize(builder: &mut BlockBuilder<Self>, batch: RowBatch) -> Result<()> {
+        deserialize_separated(builder, batch)
+    }
+
+    fn align(state: &mut AligningState<Self>, buf: &[u8]) -> Result<Vec<RowBatch>> {
+        Ok(state.align_by_record_delimiter(buf))
+    }
+}
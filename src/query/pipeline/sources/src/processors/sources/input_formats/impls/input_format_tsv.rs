@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use common_datavalues::DataSchemaRef;
 use common_datavalues::TypeDeserializer;
+use common_datavalues::TypeDeserializerImpl;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_formats::verbose_string;
@@ -25,7 +26,14 @@ use common_io::prelude::NestedCheckpointReader;
 use common_meta_types::StageFileFormatType;
 use common_settings::Settings;
 
+use crate::processors::sources::input_formats::input_context::InputContext;
+use crate::processors::sources::input_formats::input_context::RowPosition;
+use crate::processors::sources::input_formats::input_format_text::de_empty_field;
+use crate::processors::sources::input_formats::input_format_text::get_datetime_formats;
+use crate::processors::sources::input_formats::input_format_text::get_empty_field_as;
 use crate::processors::sources::input_formats::input_format_text::get_time_zone;
+use crate::processors::sources::input_formats::input_format_text::parse_column_defaults;
+use crate::processors::sources::input_formats::input_format_text::rollback_row;
 use crate::processors::sources::input_formats::input_format_text::AligningState;
 use crate::processors::sources::input_formats::input_format_text::BlockBuilder;
 use crate::processors::sources::input_formats::input_format_text::InputFormatTextBase;
@@ -37,49 +45,44 @@ impl InputFormatTSV {
     #[allow(clippy::too_many_arguments)]
     fn read_row(
         buf: &[u8],
+        field_delimiter: u8,
+        format_name: &str,
         deserializers: &mut Vec<common_datavalues::TypeDeserializerImpl>,
         schema: &DataSchemaRef,
+        projection: Option<&[usize]>,
         format_settings: &FormatSettings,
         path: &str,
         batch_id: usize,
         offset: usize,
         row_index: Option<usize>,
     ) -> Result<()> {
-        let num_columns = deserializers.len();
+        let num_columns = schema.num_fields();
         let mut column_index = 0;
+        let mut num_appended = 0;
         let mut field_start = 0;
         let mut pos = 0;
         let mut err_msg = None;
         let buf_len = buf.len();
         while pos <= buf_len {
-            if pos == buf_len || buf[pos] == b'\t' {
+            if pos == buf_len || buf[pos] == field_delimiter {
                 let col_data = &buf[field_start..pos];
-                if col_data.is_empty() {
-                    deserializers[column_index].de_default(format_settings);
-                } else {
-                    let mut reader = NestedCheckpointReader::new(col_data);
-                    reader.ignores(|c: u8| c == b' ').expect("must success");
-                    if let Err(e) =
-                        deserializers[column_index].de_text(&mut reader, format_settings)
-                    {
-                        err_msg = Some(format_column_error(
-                            schema,
-                            column_index,
-                            col_data,
-                            &e.message(),
-                        ));
-                        break;
-                    };
-                    reader.ignore_white_spaces().expect("must success");
-                    if reader.must_eof().is_err() {
-                        err_msg = Some(format_column_error(
-                            schema,
-                            column_index,
-                            col_data,
-                            "bad field end",
-                        ));
+                // the output column of the field, the projection is in the schema order
+                let output_index = match projection {
+                    None => Some(column_index),
+                    Some(projection) => projection.binary_search(&column_index).ok(),
+                };
+                if let Some(output_index) = output_index.filter(|_| column_index < num_columns) {
+                    if let Err(m) = read_field(
+                        &mut deserializers[output_index],
+                        schema,
+                        column_index,
+                        col_data,
+                        format_settings,
+                    ) {
+                        err_msg = Some(m);
                         break;
                     }
+                    num_appended += 1;
                 }
                 column_index += 1;
                 field_start = pos + 1;
@@ -99,13 +102,15 @@ impl InputFormatTSV {
         }
 
         if let Some(m) = err_msg {
+            rollback_row(deserializers, num_appended);
             let row_info = if let Some(r) = row_index {
                 format!("at row {},", r)
             } else {
                 String::new()
             };
             let mut msg = format!(
-                "fail to parse tsv {} batch {} at offset {}, {} reason={}, row data: ",
+                "fail to parse {} {} batch {} at offset {}, {} reason={}, row data: ",
+                format_name,
                 path,
                 batch_id,
                 offset + pos,
@@ -131,12 +136,17 @@ impl InputFormatTextBase for InputFormatTSV {
 
     fn get_format_settings(settings: &Arc<Settings>) -> Result<FormatSettings> {
         let timezone = get_time_zone(settings)?;
+        let (date_format, timestamp_format) = get_datetime_formats(settings)?;
         Ok(FormatSettings {
             record_delimiter: settings.get_format_record_delimiter()?.into_bytes(),
             field_delimiter: settings.get_format_field_delimiter()?.into_bytes(),
             empty_as_default: settings.get_format_empty_as_default()? > 0,
+            empty_field_as: get_empty_field_as(settings)?,
+            column_defaults: parse_column_defaults(&settings.get_format_column_defaults()?)?,
             null_bytes: vec![b'\\', b'N'],
             timezone,
+            date_format,
+            timestamp_format,
             ..Default::default()
         })
     }
@@ -146,32 +156,7 @@ impl InputFormatTextBase for InputFormatTSV {
     }
 
     fn deserialize(builder: &mut BlockBuilder<Self>, batch: RowBatch) -> Result<()> {
-        tracing::debug!(
-            "tsv deserializing row batch {}, id={}, start_row={:?}, offset={}",
-            batch.path,
-            batch.batch_id,
-            batch.start_row,
-            batch.offset
-        );
-        let schema = &builder.ctx.schema;
-        let columns = &mut builder.mutable_columns;
-        let mut start = 0usize;
-        let start_row = batch.start_row;
-        for (i, end) in batch.row_ends.iter().enumerate() {
-            let buf = &batch.data[start..*end]; // include \n
-            Self::read_row(
-                buf,
-                columns,
-                schema,
-                &builder.ctx.format_settings,
-                &batch.path,
-                batch.batch_id,
-                batch.offset + start,
-                start_row.map(|n| n + i),
-            )?;
-            start = *end;
-        }
-        Ok(())
+        deserialize_separated(builder, batch)
     }
 
     fn align(state: &mut AligningState<Self>, buf: &[u8]) -> Result<Vec<RowBatch>> {
@@ -179,6 +164,164 @@ impl InputFormatTextBase for InputFormatTSV {
     }
 }
 
+/// Deserialize the rows of a format whose fields are separated by `T::default_field_delimiter()`,
+/// without quoting.
+pub(crate) fn deserialize_separated<T: InputFormatTextBase>(
+    builder: &mut BlockBuilder<T>,
+    batch: RowBatch,
+) -> Result<()> {
+    let format_name = format!("{:?}", T::format_type()).to_lowercase();
+    tracing::debug!(
+        "{} deserializing row batch {}, id={}, start_row={:?}, offset={}",
+        format_name,
+        batch.path,
+        batch.batch_id,
+        batch.start_row,
+        batch.offset
+    );
+    let field_delimiter = T::default_field_delimiter();
+    let mut field_ends = vec![];
+    let mut start = 0usize;
+    let start_row = batch.start_row;
+    for (i, end) in batch.row_ends.iter().enumerate() {
+        let buf = &batch.data[start..*end]; // include \n
+        let record = buf.strip_suffix(b"\n").unwrap_or(buf);
+        if !T::accept_record(&builder.ctx, record) {
+            builder.filter_out_row(start_row.map(|n| n + i));
+            start = *end;
+            continue;
+        }
+        field_ends.clear();
+        field_ends.extend(
+            record
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| **b == field_delimiter)
+                .map(|(pos, _)| pos),
+        );
+        field_ends.push(record.len());
+        let key = dedup_key(&builder.ctx, record, &field_ends);
+        let position = (batch.file_index, batch.offset + start);
+        if let (Some(dedup), Some(key)) = (&builder.ctx.key_dedup, key) {
+            if dedup.has_better(key, position) {
+                builder.filter_out_row(start_row.map(|n| n + i));
+                start = *end;
+                continue;
+            }
+        }
+        let result = T::validate_record(&builder.ctx, record, &field_ends)
+            .map_err(|e| {
+                ErrorCode::BadBytes(format!(
+                    "fail to parse {} {} at offset {}, record rejected: {}",
+                    format_name,
+                    batch.path,
+                    batch.offset + start,
+                    e.message()
+                ))
+            })
+            .and_then(|_| {
+                InputFormatTSV::read_row(
+                    buf,
+                    field_delimiter,
+                    &format_name,
+                    &mut builder.mutable_columns,
+                    &builder.ctx.schema,
+                    builder.ctx.projection.as_deref(),
+                    &builder.ctx.format_settings,
+                    &batch.path,
+                    batch.batch_id,
+                    batch.offset + start,
+                    start_row.map(|n| n + i),
+                )
+            });
+        match result {
+            Ok(()) => dedup_loaded_row(builder, key, position, start_row.map(|n| n + i)),
+            Err(e) => builder.skip_error_row(e, buf, start_row.map(|n| n + i))?,
+        }
+        start = *end;
+    }
+    Ok(())
+}
+
+/// Take the key of a row read successfully, the row is rolled back if a better row of the
+/// key is loaded meanwhile, else its position is kept for `KeyDedup::take_blocks`.
+pub(crate) fn dedup_loaded_row<T: InputFormatTextBase>(
+    builder: &mut BlockBuilder<T>,
+    key: Option<&[u8]>,
+    position: RowPosition,
+    row_index: Option<usize>,
+) {
+    let taken = match (&builder.ctx.key_dedup, key) {
+        (None, _) => return,
+        (Some(dedup), Some(key)) => dedup.insert(key, position),
+        // rows without the key field are all kept
+        (Some(_), None) => true,
+    };
+    if taken {
+        builder.row_positions.push(position);
+    } else {
+        let num_columns = builder.mutable_columns.len();
+        rollback_row(&mut builder.mutable_columns, num_columns);
+        builder.filter_out_row(row_index);
+    }
+}
+
+/// Deserialize an unquoted field of the column `column_index` of the schema,
+/// nothing is appended if it fails.
+pub(crate) fn read_field(
+    deserializer: &mut TypeDeserializerImpl,
+    schema: &DataSchemaRef,
+    column_index: usize,
+    col_data: &[u8],
+    format_settings: &FormatSettings,
+) -> std::result::Result<(), String> {
+    if col_data.is_empty() {
+        return de_empty_field(deserializer, schema, column_index, format_settings)
+            .map_err(|m| format_column_error(schema, column_index, col_data, &m));
+    }
+    let mut reader = NestedCheckpointReader::new(col_data);
+    reader.ignores(|c: u8| c == b' ').expect("must success");
+    if let Err(e) = deserializer.de_text(&mut reader, format_settings) {
+        return Err(format_column_error(
+            schema,
+            column_index,
+            col_data,
+            &e.message(),
+        ));
+    }
+    reader.ignore_white_spaces().expect("must success");
+    if reader.must_eof().is_err() {
+        let _ = deserializer.pop_data_value();
+        return Err(format_column_error(
+            schema,
+            column_index,
+            col_data,
+            "bad field end",
+        ));
+    }
+    Ok(())
+}
+
+/// The key field of the record for `InputContext::key_dedup` if any, `field_ends` are the
+/// positions of the field delimiters and the end of the record.
+pub(crate) fn dedup_key<'a>(
+    ctx: &InputContext,
+    record: &'a [u8],
+    field_ends: &[usize],
+) -> Option<&'a [u8]> {
+    match &ctx.key_dedup {
+        Some(dedup) if dedup.field < field_ends.len() => {
+            let start = if dedup.field == 0 {
+                0
+            } else {
+                field_ends[dedup.field - 1] + 1
+            };
+            Some(&record[start..field_ends[dedup.field]])
+        }
+        _ => None,
+    }
+}
+
 pub fn format_column_error(
     schema: &DataSchemaRef,
     column_index: usize,
@@ -189,7 +332,7 @@ pub fn format_column_error(
     verbose_string(col_data, &mut data);
     let field = &schema.fields()[column_index];
     format!(
-        "fail to decode column {} ({} {}): {}, [column_data]=[{}]",
+        "fail to decode column {} '{}' ({}): {}, [column_data]=[{}]",
         column_index,
         field.name(),
         field.data_type(),
@@ -0,0 +1,270 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::mem;
+use std::sync::Arc;
+
+use common_datavalues::DataSchemaRef;
+use common_datavalues::TypeDeserializer;
+use common_datavalues::TypeDeserializerImpl;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_formats::verbose_string;
+use common_io::prelude::FormatSettings;
+use common_meta_types::StageFileFormatType;
+use common_settings::Settings;
+
+use crate::processors::sources::input_formats::impls::input_format_csv::build_column_mapping;
+use crate::processors::sources::input_formats::impls::input_format_tsv::dedup_key;
+use crate::processors::sources::input_formats::impls::input_format_tsv::dedup_loaded_row;
+use crate::processors::sources::input_formats::impls::input_format_tsv::read_field;
+use crate::processors::sources::input_formats::impls::input_format_tsv::InputFormatTSV;
+use crate::processors::sources::input_formats::input_format_text::rollback_row;
+use crate::processors::sources::input_formats::input_format_text::AligningState;
+use crate::processors::sources::input_formats::input_format_text::BlockBuilder;
+use crate::processors::sources::input_formats::input_format_text::InputFormatTextBase;
+use crate::processors::sources::input_formats::input_format_text::RowBatch;
+
+/// TSV with a line of column names first, like `TSVWithNames` of ClickHouse.
+///
+/// The fields are mapped to the columns by the names, a name not of the table is an error,
+/// and the columns not named are filled with their default values.
+pub struct InputFormatTSVWithNames {}
+
+impl InputFormatTSVWithNames {
+    #[allow(clippy::too_many_arguments)]
+    fn read_row(
+        buf: &[u8],
+        num_fields: usize,
+        field_indexes: &[Option<usize>],
+        deserializers: &mut [TypeDeserializerImpl],
+        schema: &DataSchemaRef,
+        projection: Option<&[usize]>,
+        format_settings: &FormatSettings,
+        path: &str,
+        offset: usize,
+        row_index: Option<usize>,
+    ) -> Result<()> {
+        let record = buf.strip_suffix(b"\n").unwrap_or(buf);
+        let record = record.strip_suffix(b"\r").unwrap_or(record);
+        let fields: Vec<&[u8]> = record.split(|b| *b == b'\t').collect();
+        let mut err_msg = None;
+        let mut num_appended = 0;
+        if fields.len() != num_fields {
+            err_msg = Some(format!(
+                "need {} fields as the names line, find {}",
+                num_fields,
+                fields.len()
+            ));
+        } else {
+            for (c, deserializer) in deserializers.iter_mut().enumerate() {
+                let column_index = projection.map_or(c, |p| p[c]);
+                match field_indexes[c] {
+                    None => deserializer.de_default(format_settings),
+                    Some(i) => {
+                        if let Err(m) = read_field(
+                            deserializer,
+                            schema,
+                            column_index,
+                            fields[i],
+                            format_settings,
+                        ) {
+                            err_msg = Some(m);
+                            break;
+                        }
+                    }
+                }
+                num_appended += 1;
+            }
+        }
+
+        if let Some(m) = err_msg {
+            rollback_row(deserializers, num_appended);
+            let row_info = if let Some(r) = row_index {
+                format!("at row {},", r)
+            } else {
+                String::new()
+            };
+            let mut msg = format!(
+                "fail to parse tsv with names {} at offset {}, {} reason={}, row data: ",
+                path, offset, row_info, m
+            );
+            verbose_string(buf, &mut msg);
+            Err(ErrorCode::BadBytes(msg))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Map the fields to the columns by the names line, without its record delimiter.
+    fn read_names(state: &mut AligningState<Self>, line: &[u8]) -> Result<()> {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.is_empty() {
+            return Err(ErrorCode::BadBytes(format!(
+                "fail to parse tsv with names {}: missing the names line",
+                state.path
+            )));
+        }
+        let names: Vec<String> = line
+            .split(|b| *b == b'\t')
+            .map(|name| String::from_utf8_lossy(name).trim().to_string())
+            .collect();
+        let bad_names = |msg: &str| {
+            ErrorCode::BadBytes(format!(
+                "fail to parse tsv with names {}: {}, names line: {:?}",
+                state.path, msg, names
+            ))
+        };
+        let mapping = build_column_mapping(
+            &state.ctx.schema,
+            &names,
+            state.ctx.settings.get_format_header_case_sensitive()? > 0,
+            state.ctx.header_mapper.as_ref(),
+        )
+        .map_err(|e| bad_names(&e))?;
+        if let Some(i) = mapping.iter().position(|m| m.is_none()) {
+            return Err(bad_names(&format!(
+                "'{}' is not a column of the table",
+                names[i]
+            )));
+        }
+        tracing::debug!("tsv with names aligner: column mapping {:?}", mapping);
+        state.num_fields = mapping.len();
+        state.column_mapping = Some(Arc::new(mapping));
+        state.header = Some(names);
+        state.rows += 1;
+        Ok(())
+    }
+
+    fn set_column_mapping(
+        state: &AligningState<Self>,
+        mut batches: Vec<RowBatch>,
+    ) -> Vec<RowBatch> {
+        for batch in batches.iter_mut() {
+            batch.column_mapping = state.column_mapping.clone();
+        }
+        batches
+    }
+}
+
+impl InputFormatTextBase for InputFormatTSVWithNames {
+    fn format_type() -> StageFileFormatType {
+        StageFileFormatType::TsvWithNames
+    }
+
+    fn get_format_settings(settings: &Arc<Settings>) -> Result<FormatSettings> {
+        InputFormatTSV::get_format_settings(settings)
+    }
+
+    fn default_field_delimiter() -> u8 {
+        b'\t'
+    }
+
+    fn deserialize(builder: &mut BlockBuilder<Self>, batch: RowBatch) -> Result<()> {
+        let mapping = batch.column_mapping.clone().ok_or_else(|| {
+            ErrorCode::UnexpectedError("should not happen: tsv with names batch without names")
+        })?;
+        let projection = builder.ctx.projection.clone();
+        // output column -> field
+        let field_indexes: Vec<Option<usize>> = (0..builder.mutable_columns.len())
+            .map(|c| {
+                let column_index = projection.as_ref().map_or(c, |p| p[c]);
+                mapping.iter().position(|m| *m == Some(column_index))
+            })
+            .collect();
+        let mut field_ends = vec![];
+        let mut start = 0usize;
+        let start_row = batch.start_row;
+        for (i, end) in batch.row_ends.iter().enumerate() {
+            let buf = &batch.data[start..*end];
+            let record = buf.strip_suffix(b"\n").unwrap_or(buf);
+            if !Self::accept_record(&builder.ctx, record) {
+                builder.filter_out_row(start_row.map(|n| n + i));
+                start = *end;
+                continue;
+            }
+            field_ends.clear();
+            field_ends.extend(
+                record
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| **b == b'\t')
+                    .map(|(pos, _)| pos),
+            );
+            field_ends.push(record.len());
+            let key = dedup_key(&builder.ctx, record, &field_ends);
+            let position = (batch.file_index, batch.offset + start);
+            if let (Some(dedup), Some(key)) = (&builder.ctx.key_dedup, key) {
+                if dedup.has_better(key, position) {
+                    builder.filter_out_row(start_row.map(|n| n + i));
+                    start = *end;
+                    continue;
+                }
+            }
+            let result = Self::validate_record(&builder.ctx, record, &field_ends).and_then(|_| {
+                Self::read_row(
+                    buf,
+                    mapping.len(),
+                    &field_indexes,
+                    &mut builder.mutable_columns,
+                    &builder.ctx.schema,
+                    projection.as_deref(),
+                    &builder.ctx.format_settings,
+                    &batch.path,
+                    batch.offset + start,
+                    start_row.map(|n| n + i),
+                )
+            });
+            match result {
+                Ok(()) => dedup_loaded_row(builder, key, position, start_row.map(|n| n + i)),
+                Err(e) => builder.skip_error_row(e, buf, start_row.map(|n| n + i))?,
+            }
+            start = *end;
+        }
+        Ok(())
+    }
+
+    fn align(state: &mut AligningState<Self>, buf: &[u8]) -> Result<Vec<RowBatch>> {
+        let mut buf = buf;
+        if state.column_mapping.is_none() {
+            // the names line is kept in tail_of_last_batch until it is complete
+            match buf.iter().position(|b| *b == b'\n') {
+                None => {
+                    state.tail_of_last_batch.extend_from_slice(buf);
+                    state.offset += buf.len();
+                    return Ok(vec![]);
+                }
+                Some(pos) => {
+                    let mut line = mem::take(&mut state.tail_of_last_batch);
+                    line.extend_from_slice(&buf[..pos]);
+                    Self::read_names(state, &line)?;
+                    state.offset += pos + 1;
+                    buf = &buf[pos + 1..];
+                }
+            }
+        }
+        let batches = state.align_by_record_delimiter(buf);
+        Ok(Self::set_column_mapping(state, batches))
+    }
+
+    fn align_flush(state: &mut AligningState<Self>) -> Result<Vec<RowBatch>> {
+        if state.column_mapping.is_none() {
+            // the names line without the record delimiter, and no data
+            let line = mem::take(&mut state.tail_of_last_batch);
+            Self::read_names(state, &line)?;
+        }
+        let batches = state.flush();
+        Ok(Self::set_column_mapping(state, batches))
+    }
+}
@@ -12,16 +12,31 @@
 //  See the License for the specific language governing permissions and
 //  limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::mem;
 use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
 
 use common_base::base::tokio::sync::mpsc::Receiver;
 use common_base::base::Progress;
+use common_datablocks::DataBlock;
+use common_datavalues::BooleanColumn;
+use common_datavalues::DataField;
 use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::ScalarColumn;
+use common_datavalues::Series;
+use common_datavalues::SeriesFrom;
+use common_datavalues::ToDataType;
+use common_datavalues::Vu8;
 use common_exception::ErrorCode;
 use common_exception::Result;
 use common_io::prelude::FormatSettings;
@@ -33,10 +48,17 @@ use opendal::io_util::CompressAlgorithm;
 use opendal::Operator;
 
 use crate::processors::sources::input_formats::delimiter::RecordDelimiter;
+use crate::processors::sources::input_formats::impls::input_format_csv::CsvParseError;
 use crate::processors::sources::input_formats::impls::input_format_csv::InputFormatCSV;
+use crate::processors::sources::input_formats::impls::input_format_csv_with_types::InputFormatCSVWithTypes;
+use crate::processors::sources::input_formats::impls::input_format_fixed_width::InputFormatFixedWidth;
+use crate::processors::sources::input_formats::impls::input_format_json_array::InputFormatJsonArray;
 use crate::processors::sources::input_formats::impls::input_format_ndjson::InputFormatNDJson;
 use crate::processors::sources::input_formats::impls::input_format_parquet::InputFormatParquet;
+use crate::processors::sources::input_formats::impls::input_format_psv::InputFormatPSV;
 use crate::processors::sources::input_formats::impls::input_format_tsv::InputFormatTSV;
+use crate::processors::sources::input_formats::impls::input_format_tsv_with_names::InputFormatTSVWithNames;
+use crate::processors::sources::input_formats::input_format_text::check_column_defaults;
 use crate::processors::sources::input_formats::input_format_text::InputFormatText;
 use crate::processors::sources::input_formats::input_pipeline::StreamingReadBatch;
 use crate::processors::sources::input_formats::input_split::SplitInfo;
@@ -72,6 +94,207 @@ pub struct StreamPlan {
     pub compression: StageFileCompression,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputErrorMode {
+    AbortOnError,
+    // skip malformed rows, abort when more than max_errors rows are skipped
+    SkipError(usize),
+    // skip malformed rows without aborting, keep the errors of the first max_errors of them
+    // to return as rows, see `InputContext::take_load_errors_block`
+    CollectErrors(usize),
+}
+
+impl InputErrorMode {
+    pub fn try_create(settings: &Settings) -> Result<Self> {
+        let max_errors = settings.get_input_max_error_rows()? as usize;
+        if settings.get_input_collect_errors()? > 0 {
+            if max_errors == 0 {
+                return Err(ErrorCode::InvalidArgument(
+                    "input_collect_errors needs input_max_error_rows > 0 to bound the errors kept",
+                ));
+            }
+            Ok(InputErrorMode::CollectErrors(max_errors))
+        } else if max_errors == 0 {
+            Ok(InputErrorMode::AbortOnError)
+        } else {
+            Ok(InputErrorMode::SkipError(max_errors))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupMode {
+    KeepFirst,
+    KeepLast,
+}
+
+/// The (file index, row offset) of a record, the order of the records in a load.
+pub type RowPosition = (usize, usize);
+
+/// The keys of the records loaded, to keep the first or the last record of each key in the
+/// order of the files and the offsets in them, whichever order they are read in parallel.
+/// A record is skipped before it is read if a better record of its key is loaded. The records
+/// loaded but replaced by a better one later are dropped when the load ends, so the blocks are
+/// held until then. A key is taken only when its record is read successfully, so a malformed
+/// record does not hide the other records of its key.
+pub struct KeyDedup {
+    // the index of the key field in the record
+    pub field: usize,
+    pub mode: DedupMode,
+    // the records of the keys beyond it are not deduplicated
+    max_keys: usize,
+    // key -> the position of the record kept
+    keys: Mutex<HashMap<Vec<u8>, RowPosition>>,
+    // the records loaded then replaced by a better one of the same key
+    replaced: Mutex<HashSet<RowPosition>>,
+    // the blocks built, with the position of each row
+    blocks: Mutex<Vec<(DataBlock, Vec<RowPosition>)>>,
+    warned: AtomicBool,
+}
+
+impl KeyDedup {
+    pub fn create(field: usize, mode: DedupMode, max_keys: usize) -> Self {
+        KeyDedup {
+            field,
+            mode,
+            max_keys,
+            keys: Mutex::new(HashMap::new()),
+            replaced: Mutex::new(HashSet::new()),
+            blocks: Mutex::new(vec![]),
+            warned: AtomicBool::new(false),
+        }
+    }
+
+    pub fn try_create(settings: &Settings) -> Result<Option<Self>> {
+        let key = settings.get_input_dedup_key()? as usize;
+        if key == 0 {
+            return Ok(None);
+        }
+        let mode = match settings.get_input_dedup_mode()?.to_lowercase().as_str() {
+            "keep_first" => DedupMode::KeepFirst,
+            "keep_last" => DedupMode::KeepLast,
+            mode => {
+                return Err(ErrorCode::InvalidArgument(format!(
+                    "invalid input_dedup_mode '{}', expect 'keep_first' or 'keep_last'",
+                    mode
+                )));
+            }
+        };
+        let max_keys = settings.get_input_dedup_max_keys()? as usize;
+        Ok(Some(Self::create(key - 1, mode, max_keys)))
+    }
+
+    /// Whether the record at `a` is kept over the one at `b` of the same key.
+    fn is_better(&self, a: RowPosition, b: RowPosition) -> bool {
+        match self.mode {
+            DedupMode::KeepFirst => a < b,
+            DedupMode::KeepLast => a > b,
+        }
+    }
+
+    /// Whether a better record of the key is loaded, checked before the record is read.
+    pub fn has_better(&self, key: &[u8], position: RowPosition) -> bool {
+        let keys = self.keys.lock().expect("must success");
+        match keys.get(key) {
+            Some(kept) => self.is_better(*kept, position),
+            None => false,
+        }
+    }
+
+    /// Take the key of a record read successfully, return false if a better record of the key
+    /// is loaded meanwhile, then the values of the record should be rolled back.
+    /// The record kept before is replaced if this one is better.
+    pub fn insert(&self, key: &[u8], position: RowPosition) -> bool {
+        let mut keys = self.keys.lock().expect("must success");
+        match keys.get_mut(key) {
+            Some(kept) if self.is_better(*kept, position) => false,
+            Some(kept) => {
+                let old = mem::replace(kept, position);
+                self.replaced.lock().expect("must success").insert(old);
+                true
+            }
+            None => {
+                if keys.len() < self.max_keys {
+                    keys.insert(key.to_vec(), position);
+                } else if !self.warned.swap(true, Ordering::Relaxed) {
+                    tracing::warn!(
+                        "more than {} keys in the load, the records of the new keys are not deduplicated",
+                        self.max_keys
+                    );
+                }
+                true
+            }
+        }
+    }
+
+    /// Whether some keys are not taken because of `max_keys`.
+    pub fn is_full(&self) -> bool {
+        self.warned.load(Ordering::Relaxed)
+    }
+
+    /// Hold a block built until the load ends, `positions` are of its rows.
+    pub fn hold_block(&self, block: DataBlock, positions: Vec<RowPosition>) {
+        self.blocks
+            .lock()
+            .expect("must success")
+            .push((block, positions));
+    }
+
+    /// Take the blocks held when all the records are read, without the rows replaced.
+    pub fn take_blocks(&self) -> Result<Vec<DataBlock>> {
+        let blocks = mem::take(&mut *self.blocks.lock().expect("must success"));
+        let replaced = self.replaced.lock().expect("must success");
+        let mut output = Vec::with_capacity(blocks.len());
+        for (block, positions) in blocks {
+            let filter =
+                BooleanColumn::from_owned_iterator(positions.iter().map(|p| !replaced.contains(p)));
+            let block = DataBlock::filter_block_with_bool_column(block, &filter)?;
+            if !block.is_empty() {
+                output.push(block);
+            }
+        }
+        Ok(output)
+    }
+}
+
+impl Debug for KeyDedup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyDedup")
+            .field("field", &self.field)
+            .field("mode", &self.mode)
+            .field("max_keys", &self.max_keys)
+            .finish()
+    }
+}
+
+/// Totals of the data aligned to rows, reported when each split reaches its end.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputLoadStats {
+    pub rows: usize,
+    pub bytes: usize,
+    pub batches: usize,
+    // malformed rows skipped under InputErrorMode::SkipError, reported by the block builders
+    pub skipped_rows: usize,
+    // rows dropped by `InputContext::record_filter` or `InputContext::key_dedup`, reported by
+    // the block builders
+    pub filtered_rows: usize,
+    // the time spent in splitting the data into rows and in reading the values of rows,
+    // measured only if `InputContext::phase_timing`
+    pub align_nanos: u64,
+    pub deserialize_nanos: u64,
+}
+
+/// A malformed row skipped under `InputErrorMode::CollectErrors`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+    pub file: String,
+    // 1-based, None if the rows of the file are not counted, e.g. when split
+    pub row: Option<usize>,
+    pub column: Option<String>,
+    pub message: String,
+    pub raw: Vec<u8>,
+}
+
 pub enum InputSource {
     Operator(Operator),
     // need Mutex because Arc<InputContext> is immutable and mpsc receiver can not clone
@@ -103,15 +326,54 @@ impl InputSource {
     }
 }
 
+/// Called with a record and the ends of its fields before it is deserialized,
+/// a record is skipped or rejected as malformed if it returns an error.
+pub type RecordValidator = Arc<dyn Fn(&[u8], &[usize]) -> Result<()> + Send + Sync>;
+
+/// Called with a record before it is validated and deserialized, a record is dropped silently
+/// if it returns false, e.g. to load only the lines containing some bytes of a huge file
+/// without converting the values of the others.
+pub type RecordFilter = Arc<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+/// Called with `(bytes_consumed, rows_parsed)` of a split each time rows are aligned from it.
+/// It runs on the parsing thread, so it should be cheap and never block.
+pub type AlignProgressCallback = Arc<dyn Fn(usize, usize) + Send + Sync>;
+
+/// Maps a header name of the file to the name of the column it is read into,
+/// None to ignore the file column. Called once for each header, only by header-based mapping.
+pub type HeaderMapper = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// The value of a column filled for each row by the source, instead of read from the data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VirtualColumn {
+    /// the path of the file of the row
+    FileName,
+    /// the 1-based number of the row among the data rows of its split,
+    /// i.e. of its file if the file is not split
+    LineNumber,
+    /// the offset in the (decompressed) file of the start of the row, the same each time the
+    /// file is loaded, e.g. a dedup key with the file name. NULL for JSON array.
+    RowOffset,
+}
+
 pub struct InputContext {
     pub plan: InputPlan,
     pub schema: DataSchemaRef,
+    // the schema columns to deserialize and output, in the schema order, None for all
+    pub projection: Option<Vec<usize>>,
+    pub output_schema: DataSchemaRef,
+    // the schema of the output blocks, output_schema with the virtual columns
+    pub block_schema: DataSchemaRef,
+    // (index in block_schema, value) of the virtual columns, not in schema or output_schema
+    pub virtual_columns: Vec<(usize, VirtualColumn)>,
     pub source: InputSource,
     pub format: Arc<dyn InputFormat>,
     pub splits: Vec<Arc<SplitInfo>>,
 
     // row format only
     pub rows_to_skip: usize,
+    // the trailing rows of each file to drop, held back until the end of the file
+    pub rows_to_skip_footer: usize,
     pub field_delimiter: u8,
     pub record_delimiter: RecordDelimiter,
 
@@ -122,6 +384,38 @@ pub struct InputContext {
     pub read_batch_size: usize,
     pub rows_per_block: usize,
     pub block_memory_size_threshold: usize,
+    pub error_mode: InputErrorMode,
+    // 0 means rows of a batch are always deserialized in one thread
+    pub parallel_deserialize_rows: usize,
+    // 0 means unlimited
+    pub max_record_bytes: usize,
+    // parse and check the input only, no block is output
+    pub validate_only: bool,
+    // at most so many rows are aligned from all the files, e.g. to preview a stage file.
+    // the rows dropped later, as malformed, filtered or duplicate, are counted too. the splits
+    // are aligned one by one in the order of files and offsets then, so the first rows are taken
+    pub max_rows: Option<usize>,
+    aligned_rows: AtomicUsize,
+    // the rows of a file are output in batches of at least so many rows or bytes,
+    // 0 means a batch per read
+    pub batch_target_rows: usize,
+    pub batch_target_bytes: usize,
+    // measure the time of aligning and deserializing, logged when the load ends
+    pub phase_timing: bool,
+    // checksum the data of each row batch when aligned and verify it before deserialized,
+    // to tell whether the data is corrupted in parsing or later in the pipeline
+    pub batch_checksum: bool,
+    // rejects records by rules beyond the structure, e.g. cross-field checks
+    pub record_validator: Option<RecordValidator>,
+    // all records are loaded if None
+    pub record_filter: Option<RecordFilter>,
+    // the records of duplicate keys are loaded if None
+    pub key_dedup: Option<KeyDedup>,
+    pub progress_callback: Option<AlignProgressCallback>,
+    pub header_mapper: Option<HeaderMapper>,
+
+    load_stats: Mutex<InputLoadStats>,
+    load_errors: Mutex<Vec<LoadError>>,
 
     pub scan_progress: Arc<Progress>,
 }
@@ -130,25 +424,70 @@ impl Debug for InputContext {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("InputContext")
             .field("plan", &self.plan)
+            .field("projection", &self.projection)
+            .field("virtual_columns", &self.virtual_columns)
             .field("rows_to_skip", &self.rows_to_skip)
+            .field("rows_to_skip_footer", &self.rows_to_skip_footer)
             .field("field_delimiter", &self.field_delimiter)
             .field("record_delimiter", &self.record_delimiter)
             .field("format_settings", &self.format_settings)
             .field("rows_per_block", &self.rows_per_block)
             .field("read_batch_size", &self.read_batch_size)
+            .field("error_mode", &self.error_mode)
+            .field("parallel_deserialize_rows", &self.parallel_deserialize_rows)
+            .field("max_record_bytes", &self.max_record_bytes)
+            .field("validate_only", &self.validate_only)
+            .field("max_rows", &self.max_rows)
+            .field("batch_target_rows", &self.batch_target_rows)
+            .field("batch_target_bytes", &self.batch_target_bytes)
+            .field("phase_timing", &self.phase_timing)
+            .field("batch_checksum", &self.batch_checksum)
+            .field("record_validator", &self.record_validator.is_some())
+            .field("record_filter", &self.record_filter.is_some())
+            .field("key_dedup", &self.key_dedup)
+            .field("progress_callback", &self.progress_callback.is_some())
+            .field("header_mapper", &self.header_mapper.is_some())
             .field("num_splits", &self.splits.len())
             .finish()
     }
 }
 
+impl Drop for InputContext {
+    fn drop(&mut self) {
+        if self.phase_timing {
+            let stats = self.get_load_stats();
+            tracing::info!(
+                "input load end, {} rows in {} batches, align {:?}, deserialize {:?}",
+                stats.rows,
+                stats.batches,
+                Duration::from_nanos(stats.align_nanos),
+                Duration::from_nanos(stats.deserialize_nanos),
+            );
+        }
+    }
+}
+
 impl InputContext {
     pub fn get_input_format(format: &StageFileFormatType) -> Result<Arc<dyn InputFormat>> {
         match format {
             StageFileFormatType::Tsv => Ok(Arc::new(InputFormatText::<InputFormatTSV>::create())),
+            StageFileFormatType::TsvWithNames => Ok(Arc::new(InputFormatText::<
+                InputFormatTSVWithNames,
+            >::create())),
+            StageFileFormatType::Psv => Ok(Arc::new(InputFormatText::<InputFormatPSV>::create())),
+            StageFileFormatType::FixedWidth => {
+                Ok(Arc::new(InputFormatText::<InputFormatFixedWidth>::create()))
+            }
             StageFileFormatType::Csv => Ok(Arc::new(InputFormatText::<InputFormatCSV>::create())),
+            StageFileFormatType::CsvWithTypes => Ok(Arc::new(InputFormatText::<
+                InputFormatCSVWithTypes,
+            >::create())),
             StageFileFormatType::NdJson => {
                 Ok(Arc::new(InputFormatText::<InputFormatNDJson>::create()))
             }
+            StageFileFormatType::Json => {
+                Ok(Arc::new(InputFormatText::<InputFormatJsonArray>::create()))
+            }
             StageFileFormatType::Parquet => Ok(Arc::new(InputFormatParquet {})),
             format => Err(ErrorCode::LogicalError(format!(
                 "Unsupported file format: {:?}",
@@ -169,6 +508,10 @@ impl InputContext {
         if files.is_empty() {
             return Err(ErrorCode::BadArguments("no file to copy"));
         }
+        let max_rows = match settings.get_input_max_total_rows()? {
+            0 => None,
+            n => Some(n as usize),
+        };
         let plan = Box::new(CopyIntoPlan { stage_info, files });
         let read_batch_size = settings.get_input_read_buffer_size()? as usize;
         let file_format_options = &plan.stage_info.file_format_options;
@@ -177,6 +520,15 @@ impl InputContext {
             .get_splits(&plan, &operator, &settings, &schema)
             .await?;
         let rows_per_block = MIN_ROW_PER_BLOCK;
+        let error_mode = InputErrorMode::try_create(&settings)?;
+        let parallel_deserialize_rows = settings.get_input_parallel_deserialize_rows()? as usize;
+        let max_record_bytes = settings.get_input_max_record_bytes()? as usize;
+        let validate_only = settings.get_input_validate_only()? > 0;
+        let batch_target_rows = settings.get_input_batch_target_rows()? as usize;
+        let batch_target_bytes = settings.get_input_batch_target_bytes()? as usize;
+        let phase_timing = settings.get_input_phase_timing()? > 0;
+        let batch_checksum = settings.get_input_batch_checksum()? > 0;
+        let key_dedup = KeyDedup::try_create(&settings)?;
         let record_delimiter = {
             if file_format_options.record_delimiter.is_empty() {
                 format.default_record_delimiter()
@@ -186,6 +538,7 @@ impl InputContext {
         };
 
         let rows_to_skip = file_format_options.skip_header as usize;
+        let rows_to_skip_footer = settings.get_format_skip_footer()? as usize;
         let field_delimiter = {
             if file_format_options.field_delimiter.is_empty() {
                 format.default_field_delimiter()
@@ -193,9 +546,20 @@ impl InputContext {
                 file_format_options.field_delimiter.as_bytes()[0]
             }
         };
+        let mut format_settings = format_settings;
+        format_settings.field_delimiter = if file_format_options.field_delimiter.is_empty() {
+            vec![field_delimiter]
+        } else {
+            file_format_options.field_delimiter.as_bytes().to_vec()
+        };
+        check_column_defaults(&schema, &format_settings)?;
         Ok(InputContext {
             format,
+            output_schema: schema.clone(),
+            block_schema: schema.clone(),
+            virtual_columns: vec![],
             schema,
+            projection: None,
             splits,
             settings,
             format_settings,
@@ -203,11 +567,29 @@ impl InputContext {
             rows_per_block,
             read_batch_size,
             rows_to_skip,
+            rows_to_skip_footer,
             field_delimiter,
             scan_progress,
             source: InputSource::Operator(operator),
             plan: InputPlan::CopyInto(plan),
             block_memory_size_threshold: DEFAULT_BLOCK_SIZE_IN_MEM_SIZE_THRESHOLD,
+            error_mode,
+            parallel_deserialize_rows,
+            max_record_bytes,
+            validate_only,
+            max_rows,
+            aligned_rows: AtomicUsize::new(0),
+            batch_target_rows,
+            batch_target_bytes,
+            phase_timing,
+            batch_checksum,
+            record_validator: None,
+            record_filter: None,
+            key_dedup,
+            progress_callback: None,
+            header_mapper: None,
+            load_stats: Mutex::new(InputLoadStats::default()),
+            load_errors: Mutex::new(vec![]),
         })
     }
 
@@ -221,6 +603,7 @@ impl InputContext {
     ) -> Result<Self> {
         let (format_name, rows_to_skip) = remove_clickhouse_format_suffix(format_name);
         let rows_to_skip = std::cmp::max(settings.get_format_skip_header()? as usize, rows_to_skip);
+        let rows_to_skip_footer = settings.get_format_skip_footer()? as usize;
 
         let format_type =
             StageFileFormatType::from_str(format_name).map_err(ErrorCode::UnknownFormat)?;
@@ -228,6 +611,19 @@ impl InputContext {
         let format_settings = format.get_format_settings(&settings)?;
         let read_batch_size = settings.get_input_read_buffer_size()? as usize;
         let rows_per_block = MIN_ROW_PER_BLOCK;
+        let error_mode = InputErrorMode::try_create(&settings)?;
+        let parallel_deserialize_rows = settings.get_input_parallel_deserialize_rows()? as usize;
+        let max_record_bytes = settings.get_input_max_record_bytes()? as usize;
+        let validate_only = settings.get_input_validate_only()? > 0;
+        let batch_target_rows = settings.get_input_batch_target_rows()? as usize;
+        let batch_target_bytes = settings.get_input_batch_target_bytes()? as usize;
+        let phase_timing = settings.get_input_phase_timing()? > 0;
+        let batch_checksum = settings.get_input_batch_checksum()? > 0;
+        let key_dedup = KeyDedup::try_create(&settings)?;
+        let max_rows = match settings.get_input_max_total_rows()? {
+            0 => None,
+            n => Some(n as usize),
+        };
         let field_delimiter = settings.get_format_field_delimiter()?;
         let field_delimiter = {
             if field_delimiter.is_empty() {
@@ -249,9 +645,14 @@ impl InputContext {
             compression,
         };
 
+        check_column_defaults(&schema, &format_settings)?;
         Ok(InputContext {
             format,
+            output_schema: schema.clone(),
+            block_schema: schema.clone(),
+            virtual_columns: vec![],
             schema,
+            projection: None,
             settings,
             format_settings,
             record_delimiter,
@@ -259,14 +660,200 @@ impl InputContext {
             read_batch_size,
             field_delimiter,
             rows_to_skip,
+            rows_to_skip_footer,
             scan_progress,
             source: InputSource::Stream(Mutex::new(Some(stream_receiver))),
             plan: InputPlan::StreamingLoad(plan),
             splits: vec![],
             block_memory_size_threshold: DEFAULT_BLOCK_SIZE_IN_MEM_SIZE_THRESHOLD,
+            error_mode,
+            parallel_deserialize_rows,
+            max_record_bytes,
+            validate_only,
+            max_rows,
+            aligned_rows: AtomicUsize::new(0),
+            batch_target_rows,
+            batch_target_bytes,
+            phase_timing,
+            batch_checksum,
+            record_validator: None,
+            record_filter: None,
+            key_dedup,
+            progress_callback: None,
+            header_mapper: None,
+            load_stats: Mutex::new(InputLoadStats::default()),
+            load_errors: Mutex::new(vec![]),
         })
     }
 
+    /// Only deserialize and output the columns named in `names`, the others are skipped.
+    pub fn with_projection(mut self, names: &[String]) -> Result<Self> {
+        let mut projection = names
+            .iter()
+            .map(|name| self.schema.index_of(name))
+            .collect::<Result<Vec<_>>>()?;
+        projection.sort_unstable();
+        projection.dedup();
+        self.output_schema = Arc::new(self.schema.project(&projection));
+        self.block_schema = self.output_schema.clone();
+        self.projection = Some(projection);
+        Ok(self)
+    }
+
+    /// Fill the column `name` with `column` instead of reading it from the data,
+    /// if the schema has it. Only for the text formats, call it after `with_projection`.
+    pub fn with_virtual_column(mut self, name: &str, column: VirtualColumn) -> Self {
+        let index = match self.schema.index_of(name) {
+            Ok(index) => index,
+            Err(_) => return self,
+        };
+        let others = (0..self.schema.num_fields())
+            .filter(|c| *c != index)
+            .collect::<Vec<_>>();
+        self.schema = Arc::new(self.schema.project(&others));
+        match &mut self.projection {
+            None => self.output_schema = self.schema.clone(),
+            Some(projection) => {
+                projection.retain(|c| *c != index);
+                for c in projection.iter_mut().filter(|c| **c > index) {
+                    *c -= 1;
+                }
+                self.output_schema = Arc::new(self.schema.project(projection));
+            }
+        }
+        // not projected if it is not in block_schema
+        if let Ok(block_index) = self.block_schema.index_of(name) {
+            self.virtual_columns.push((block_index, column));
+            self.virtual_columns.sort_by_key(|(c, _)| *c);
+        }
+        self
+    }
+
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    pub fn with_record_validator(mut self, validator: RecordValidator) -> Self {
+        self.record_validator = Some(validator);
+        self
+    }
+
+    pub fn with_record_filter(mut self, filter: RecordFilter) -> Self {
+        self.record_filter = Some(filter);
+        self
+    }
+
+    pub fn with_progress_callback(mut self, callback: AlignProgressCallback) -> Self {
+        self.progress_callback = Some(callback);
+        self
+    }
+
+    pub fn with_header_mapper(mut self, mapper: HeaderMapper) -> Self {
+        self.header_mapper = Some(mapper);
+        self
+    }
+
+    /// Count `n` more aligned rows, return how many of them are within `max_rows`.
+    pub fn take_rows(&self, n: usize) -> usize {
+        match self.max_rows {
+            None => n,
+            Some(max_rows) => {
+                let prev = self.aligned_rows.fetch_add(n, Ordering::Relaxed);
+                max_rows.saturating_sub(prev).min(n)
+            }
+        }
+    }
+
+    pub fn is_row_limit_reached(&self) -> bool {
+        match self.max_rows {
+            None => false,
+            Some(max_rows) => self.aligned_rows.load(Ordering::Relaxed) >= max_rows,
+        }
+    }
+
+    pub fn add_load_stats(&self, stats: &InputLoadStats) {
+        let mut guard = self.load_stats.lock().expect("must success");
+        guard.rows += stats.rows;
+        guard.bytes += stats.bytes;
+        guard.batches += stats.batches;
+        guard.skipped_rows += stats.skipped_rows;
+        guard.filtered_rows += stats.filtered_rows;
+        guard.align_nanos += stats.align_nanos;
+        guard.deserialize_nanos += stats.deserialize_nanos;
+    }
+
+    pub fn get_load_stats(&self) -> InputLoadStats {
+        *self.load_stats.lock().expect("must success")
+    }
+
+    /// Keep the error of a skipped row, unless the errors of `InputErrorMode::CollectErrors`
+    /// rows are kept already.
+    pub fn add_load_error(&self, error: LoadError) {
+        if let InputErrorMode::CollectErrors(max_errors) = self.error_mode {
+            let mut guard = self.load_errors.lock().expect("must success");
+            if guard.len() < max_errors {
+                guard.push(error);
+            }
+        }
+    }
+
+    pub fn take_load_errors(&self) -> Vec<LoadError> {
+        mem::take(&mut *self.load_errors.lock().expect("must success"))
+    }
+
+    /// The location of the malformed value that aborted the load, if `e` is raised with one,
+    /// e.g. from a `CsvParseError`.
+    pub fn load_error_of(&self, e: &ErrorCode) -> Option<LoadError> {
+        let detail = e.detail::<CsvParseError>()?;
+        let fields = self.output_schema.fields();
+        Some(LoadError {
+            file: detail.path.clone(),
+            row: Some(detail.row + 1),
+            column: detail
+                .column
+                .filter(|c| *c < fields.len())
+                .map(|c| fields[c].name().to_string()),
+            message: e.message(),
+            raw: detail.raw_field.clone().unwrap_or_default(),
+        })
+    }
+
+    /// The schema of `take_load_errors_block`.
+    pub fn load_errors_schema() -> DataSchemaRef {
+        DataSchemaRefExt::create(vec![
+            DataField::new("file", Vu8::to_data_type()),
+            DataField::new_nullable("row", u64::to_data_type()),
+            DataField::new_nullable("column", Vu8::to_data_type()),
+            DataField::new("message", Vu8::to_data_type()),
+            DataField::new("raw", Vu8::to_data_type()),
+        ])
+    }
+
+    /// The errors collected so far as rows of `(file, row, column, message, raw)`.
+    pub fn take_load_errors_block(&self) -> DataBlock {
+        let errors = self.take_load_errors();
+        let mut file: Vec<Vec<u8>> = Vec::with_capacity(errors.len());
+        let mut row: Vec<Option<u64>> = Vec::with_capacity(errors.len());
+        let mut column: Vec<Option<Vec<u8>>> = Vec::with_capacity(errors.len());
+        let mut message: Vec<Vec<u8>> = Vec::with_capacity(errors.len());
+        let mut raw: Vec<Vec<u8>> = Vec::with_capacity(errors.len());
+        for e in errors {
+            file.push(e.file.into_bytes());
+            row.push(e.row.map(|r| r as u64));
+            column.push(e.column.map(|c| c.into_bytes()));
+            message.push(e.message.into_bytes());
+            raw.push(e.raw);
+        }
+        DataBlock::create(Self::load_errors_schema(), vec![
+            Series::from_data(file),
+            Series::from_data(row),
+            Series::from_data(column),
+            Series::from_data(message),
+            Series::from_data(raw),
+        ])
+    }
+
     pub fn num_prefetch_splits(&self) -> Result<usize> {
         Ok(self.settings.get_max_threads()? as usize)
     }
@@ -283,6 +870,33 @@ impl InputContext {
         Self::get_compression_alg_copy(opt, path)
     }
 
+    /// With `auto` compression, files without a known extension are detected by the magic bytes.
+    pub fn need_detect_compression(&self, path: &str) -> bool {
+        let opt = match &self.plan {
+            InputPlan::CopyInto(p) => p.stage_info.file_format_options.compression,
+            InputPlan::StreamingLoad(p) => p.compression,
+        };
+        matches!(opt, StageFileCompression::Auto)
+            && CompressAlgorithm::from_path(path).is_none()
+            && !Self::is_lz4_compressed_copy(opt, path)
+    }
+
+    pub fn is_lz4_compressed(&self, path: &str) -> bool {
+        let opt = match &self.plan {
+            InputPlan::CopyInto(p) => p.stage_info.file_format_options.compression,
+            InputPlan::StreamingLoad(p) => p.compression,
+        };
+        Self::is_lz4_compressed_copy(opt, path)
+    }
+
+    pub fn is_lz4_compressed_copy(compress_option: StageFileCompression, path: &str) -> bool {
+        match compress_option {
+            StageFileCompression::Lz4 => true,
+            StageFileCompression::Auto => path.ends_with(".lz4"),
+            _ => false,
+        }
+    }
+
     pub fn get_compression_alg_copy(
         compress_option: StageFileCompression,
         path: &str,
@@ -296,6 +910,8 @@ impl InputContext {
             StageFileCompression::Deflate => Some(CompressAlgorithm::Zlib),
             StageFileCompression::RawDeflate => Some(CompressAlgorithm::Deflate),
             StageFileCompression::Xz => Some(CompressAlgorithm::Xz),
+            // not supported by opendal, decoded by `Decompressor::Lz4` instead
+            StageFileCompression::Lz4 => None,
             StageFileCompression::Lzo => {
                 return Err(ErrorCode::UnImplement("compress type lzo is unimplemented"));
             }
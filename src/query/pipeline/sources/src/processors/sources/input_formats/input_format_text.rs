@@ -12,31 +12,48 @@
 //  See the License for the specific language governing permissions and
 //  limitations under the License.
 
+use std::collections::HashMap;
+use std::hash::Hasher;
 use std::marker::PhantomData;
 use std::mem;
 use std::sync::Arc;
+use std::time::Instant;
 
 use chrono_tz::Tz;
 use common_datablocks::DataBlock;
+use common_datavalues::remove_nullable;
+use common_datavalues::Column;
+use common_datavalues::ColumnRef;
 use common_datavalues::DataSchemaRef;
+use common_datavalues::DataType;
+use common_datavalues::Series;
 use common_datavalues::TypeDeserializer;
 use common_datavalues::TypeDeserializerImpl;
 use common_exception::ErrorCode;
 use common_exception::Result;
+use common_io::prelude::EmptyFieldAs;
 use common_io::prelude::FormatSettings;
 use common_meta_types::StageFileFormatType;
 use common_pipeline_core::Pipeline;
 use common_settings::Settings;
-use opendal::io_util::DecompressDecoder;
-use opendal::io_util::DecompressState;
+use opendal::io_util::CompressAlgorithm;
 use opendal::Operator;
+use twox_hash::XxHash64;
 
 use super::InputFormat;
 use crate::processors::sources::input_formats::beyond_end_reader::BeyondEndReader;
+use crate::processors::sources::input_formats::decompressor::Decompressor;
 use crate::processors::sources::input_formats::delimiter::RecordDelimiter;
 use crate::processors::sources::input_formats::impls::input_format_csv::CsvReaderState;
+use crate::processors::sources::input_formats::impls::input_format_json_array::JsonArrayReaderState;
+use crate::processors::sources::input_formats::impls::input_format_ndjson::NdJsonReaderState;
 use crate::processors::sources::input_formats::input_context::CopyIntoPlan;
 use crate::processors::sources::input_formats::input_context::InputContext;
+use crate::processors::sources::input_formats::input_context::InputErrorMode;
+use crate::processors::sources::input_formats::input_context::InputLoadStats;
+use crate::processors::sources::input_formats::input_context::LoadError;
+use crate::processors::sources::input_formats::input_context::RowPosition;
+use crate::processors::sources::input_formats::input_context::VirtualColumn;
 use crate::processors::sources::input_formats::input_pipeline::AligningStateTrait;
 use crate::processors::sources::input_formats::input_pipeline::BlockBuilderTrait;
 use crate::processors::sources::input_formats::input_pipeline::InputFormatPipe;
@@ -44,6 +61,8 @@ use crate::processors::sources::input_formats::input_split::split_by_size;
 use crate::processors::sources::input_formats::input_split::FileInfo;
 use crate::processors::sources::input_formats::input_split::SplitInfo;
 
+const GZIP_MAGIC: &[u8] = &[0x1F, 0x8B];
+
 pub trait InputFormatTextBase: Sized + Send + Sync + 'static {
     fn format_type() -> StageFileFormatType;
 
@@ -61,7 +80,31 @@ pub trait InputFormatTextBase: Sized + Send + Sync + 'static {
 
     fn deserialize(builder: &mut BlockBuilder<Self>, batch: RowBatch) -> Result<()>;
 
+    /// Check a record before it is deserialized, `field_ends` are the ends of its fields in it.
+    /// A failed record is handled like a malformed one. Only `ctx.record_validator` (if any)
+    /// is called by default.
+    fn validate_record(ctx: &InputContext, record: &[u8], field_ends: &[usize]) -> Result<()> {
+        match &ctx.record_validator {
+            Some(validator) => validator(record, field_ends),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether a record is loaded, checked before it is validated. A dropped record is not an
+    /// error. Only `ctx.record_filter` (if any) is called by default.
+    fn accept_record(ctx: &InputContext, record: &[u8]) -> bool {
+        match &ctx.record_filter {
+            Some(filter) => filter(record),
+            None => true,
+        }
+    }
+
     fn align(state: &mut AligningState<Self>, buf: &[u8]) -> Result<Vec<RowBatch>>;
+
+    /// Called at the end of the input, after the last `align`.
+    fn align_flush(state: &mut AligningState<Self>) -> Result<Vec<RowBatch>> {
+        Ok(state.flush())
+    }
 }
 
 pub struct InputFormatText<T: InputFormatTextBase> {
@@ -119,7 +162,7 @@ impl<T: InputFormatTextBase> InputFormat for InputFormatText<T> {
         _schema: &DataSchemaRef,
     ) -> Result<Vec<Arc<SplitInfo>>> {
         let mut infos = vec![];
-        for path in &plan.files {
+        for (file_index, path) in plan.files.iter().enumerate() {
             let obj = op.object(path);
             let size = obj.metadata().await?.content_length() as usize;
             let compress_alg = InputContext::get_compression_alg_copy(
@@ -127,7 +170,12 @@ impl<T: InputFormatTextBase> InputFormat for InputFormatText<T> {
                 path,
             )?;
             let split_size = plan.stage_info.copy_options.split_size;
-            if compress_alg.is_none() && T::is_splittable() && split_size > 0 {
+            let is_compressed = compress_alg.is_some()
+                || InputContext::is_lz4_compressed_copy(
+                    plan.stage_info.file_format_options.compression,
+                    path,
+                );
+            if !is_compressed && T::is_splittable() && split_size > 0 {
                 let split_offsets = split_by_size(size, split_size as usize);
                 let num_file_splits = split_offsets.len();
                 tracing::debug!(
@@ -139,6 +187,7 @@ impl<T: InputFormatTextBase> InputFormat for InputFormatText<T> {
                 );
                 let file = Arc::new(FileInfo {
                     path: path.clone(),
+                    file_index,
                     size,
                     num_splits: split_offsets.len(),
                     compress_alg,
@@ -156,6 +205,7 @@ impl<T: InputFormatTextBase> InputFormat for InputFormatText<T> {
             } else {
                 let file = Arc::new(FileInfo {
                     path: path.clone(),
+                    file_index,
                     size, // dummy
                     num_splits: 1,
                     compress_alg,
@@ -178,36 +228,189 @@ impl<T: InputFormatTextBase> InputFormat for InputFormatText<T> {
 pub struct RowBatch {
     pub data: Vec<u8>,
     pub row_ends: Vec<usize>,
+    // the offset in the (decompressed) file of the start of each row, parallel with row_ends,
+    // e.g. to resume a load. empty for JSON array.
+    pub row_offsets: Vec<usize>,
     pub field_ends: Vec<usize>,
+    // whether each field is quoted, parallel with field_ends, for CSV only
+    pub field_quoted: Vec<bool>,
+    // file column -> schema column, for CSV mapped by header only
+    pub column_mapping: Option<Arc<Vec<Option<usize>>>>,
 
     // for error info
     pub path: String,
+    // the position of the file in the load, with row_offsets to tell the order of the rows
+    pub file_index: usize,
     pub batch_id: usize,
     pub offset: usize,
     pub start_row: Option<usize>,
+    // of data, only if `InputContext::batch_checksum`
+    pub checksum: Option<u64>,
+}
+
+impl RowBatch {
+    /// Split off the first `n` rows as a new batch, the remaining rows are kept in self.
+    pub fn take_rows(&mut self, n: usize) -> RowBatch {
+        let num_rows = self.row_ends.len();
+        if n >= num_rows {
+            return mem::take(self);
+        }
+        let num_fields = self.field_ends.len() / num_rows;
+        let data_end = if n == 0 { 0 } else { self.row_ends[n - 1] };
+        let row_offsets = self.row_offsets.split_off(n.min(self.row_offsets.len()));
+        let field_quoted = self
+            .field_quoted
+            .split_off((n * num_fields).min(self.field_quoted.len()));
+        let offset = row_offsets
+            .first()
+            .copied()
+            .unwrap_or(self.offset + data_end);
+        let rest = RowBatch {
+            data: self.data.split_off(data_end),
+            row_ends: self.row_ends[n..].iter().map(|e| e - data_end).collect(),
+            row_offsets,
+            field_ends: self.field_ends.split_off(n * num_fields),
+            field_quoted,
+            column_mapping: self.column_mapping.clone(),
+            path: self.path.clone(),
+            file_index: self.file_index,
+            batch_id: self.batch_id,
+            offset,
+            start_row: self.start_row.map(|r| r + n),
+            checksum: None,
+        };
+        self.row_ends.truncate(n);
+        mem::replace(self, rest)
+    }
+
+    pub fn data_checksum(&self) -> u64 {
+        let mut hasher = XxHash64::with_seed(0);
+        hasher.write(&self.data);
+        hasher.finish()
+    }
+
+    /// Verify the checksum taken when the batch was aligned, if any.
+    pub fn check_checksum(&self) -> Result<()> {
+        match self.checksum {
+            Some(checksum) if checksum != self.data_checksum() => {
+                Err(ErrorCode::LogicalError(format!(
+                    "checksum mismatch of row batch {} of {} at offset {}, the data is changed after aligned",
+                    self.batch_id, self.path, self.offset
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Append the rows of the next batch of the same file.
+    pub fn append(&mut self, other: RowBatch) {
+        let data_len = self.data.len();
+        self.row_ends
+            .extend(other.row_ends.into_iter().map(|end| end + data_len));
+        self.row_offsets.extend(other.row_offsets);
+        self.field_ends.extend(other.field_ends);
+        self.field_quoted.extend(other.field_quoted);
+        self.data.extend(other.data);
+    }
+}
+
+/// Holds back the last `num_rows` rows of the batches passed, e.g. the footer rows of a file,
+/// which are known only at the end of the file. These rows are kept in memory until more
+/// rows arrive, so a large footer costs memory.
+pub struct TailRows {
+    num_rows: usize,
+    held: Option<RowBatch>,
+}
+
+impl TailRows {
+    pub fn create(num_rows: usize) -> Self {
+        TailRows {
+            num_rows,
+            held: None,
+        }
+    }
+
+    /// Pass the rows of the batches, except the last `num_rows` rows passed so far.
+    pub fn push(&mut self, row_batches: Vec<RowBatch>) -> Vec<RowBatch> {
+        if self.num_rows == 0 {
+            return row_batches;
+        }
+        let mut output = vec![];
+        for batch in row_batches {
+            if batch.row_ends.is_empty() {
+                continue;
+            }
+            match &mut self.held {
+                Some(held) => held.append(batch),
+                None => self.held = Some(batch),
+            }
+            let held = self.held.as_mut().expect("must success");
+            let num_rows = held.row_ends.len();
+            if num_rows > self.num_rows {
+                output.push(held.take_rows(num_rows - self.num_rows));
+            }
+        }
+        output
+    }
+
+    /// The rows held back, taken at the end.
+    pub fn take(&mut self) -> Option<RowBatch> {
+        self.held.take()
+    }
 }
 
 pub struct AligningState<T> {
-    ctx: Arc<InputContext>,
+    pub ctx: Arc<InputContext>,
     split_info: Arc<SplitInfo>,
     pub path: String,
+    pub file_index: usize,
     pub record_delimiter_end: u8,
     pub field_delimiter: u8,
     pub batch_id: usize,
     pub rows: usize,
+    // the offset in the file of the next byte to align
     pub offset: usize,
     pub rows_to_skip: usize,
+    // holds back the footer rows, which are in the last split of the file only
+    footer: TailRows,
     pub tail_of_last_batch: Vec<u8>,
+    // the fields expected in each record, the columns of the schema, or the fields of the
+    // header for CSV mapped by header, where the columns missing in the file take defaults
     pub num_fields: usize,
-    pub decoder: Option<DecompressDecoder>,
+    pub decoder: Option<Decompressor>,
+    // the head of the file kept until the compression is detected
+    pub compression_head: Option<Vec<u8>>,
     pub csv_reader: Option<CsvReaderState>,
+    pub ndjson_reader: Option<NdJsonReaderState>,
+    pub json_array_reader: Option<JsonArrayReaderState>,
+    pub column_mapping: Option<Arc<Vec<Option<usize>>>>,
+    // the names of the header row of the file, for CSV read as the header only
+    pub header: Option<Vec<String>>,
+    pub load_stats: InputLoadStats,
+    // the rows not output yet, until the batch target of ctx is reached
+    pending_batch: Option<RowBatch>,
     phantom: PhantomData<T>,
 }
 
 impl<T: InputFormatTextBase> AligningState<T> {
     pub fn align_by_record_delimiter(&mut self, buf_in: &[u8]) -> Vec<RowBatch> {
+        self.align_by_row_ends(buf_in, |buf| {
+            buf.iter()
+                .enumerate()
+                .filter(|(_, b)| **b == b'\n')
+                .map(|(i, _)| i + 1)
+                .collect()
+        })
+    }
+
+    /// `find_row_ends` returns the end of each complete row in the buffer,
+    /// the bytes after the last end are kept for the next call.
+    pub fn align_by_row_ends<F>(&mut self, buf_in: &[u8], mut find_row_ends: F) -> Vec<RowBatch>
+    where F: FnMut(&[u8]) -> Vec<usize> {
         let record_delimiter_end = self.record_delimiter_end;
         let size_last_remain = self.tail_of_last_batch.len();
+        let buf_in_offset = self.offset;
+        self.offset += buf_in.len();
         let mut buf = buf_in;
         if self.rows_to_skip > 0 {
             let mut i = 0;
@@ -231,27 +434,35 @@ impl<T: InputFormatTextBase> AligningState<T> {
             return vec![];
         }
 
-        let mut output = RowBatch::default();
-        let rows = &mut output.row_ends;
-        for (i, b) in buf.iter().enumerate() {
-            if *b == b'\n' {
-                rows.push(i + 1 + size_last_remain)
-            }
-        }
+        let row_ends = find_row_ends(buf)
+            .into_iter()
+            .map(|end| end + size_last_remain)
+            .collect();
+        let mut output = RowBatch {
+            row_ends,
+            ..Default::default()
+        };
+        let rows = &output.row_ends;
         if rows.is_empty() {
             self.tail_of_last_batch.extend_from_slice(buf);
             vec![]
         } else {
             let batch_end = rows[rows.len() - 1] - size_last_remain;
+            // the tail of last batch is just before buf
+            let data_offset = buf_in_offset + (buf_in.len() - buf.len()) - size_last_remain;
+            output.row_offsets = Some(0)
+                .into_iter()
+                .chain(rows[..rows.len() - 1].iter().copied())
+                .map(|start| data_offset + start)
+                .collect();
             output.data = mem::take(&mut self.tail_of_last_batch);
             output.data.extend_from_slice(&buf[..batch_end]);
             self.tail_of_last_batch.extend_from_slice(&buf[batch_end..]);
-            let size = output.data.len();
             output.path = self.path.to_string();
+            output.file_index = self.file_index;
             output.start_row = Some(self.rows);
-            output.offset = self.offset;
+            output.offset = data_offset;
             output.batch_id = self.batch_id;
-            self.offset += size;
             self.rows += rows.len();
             self.batch_id += 1;
             tracing::debug!(
@@ -266,21 +477,113 @@ impl<T: InputFormatTextBase> AligningState<T> {
         }
     }
 
-    fn flush(&mut self) -> Vec<RowBatch> {
+    /// return None if more data is needed to detect the compression.
+    fn detect_compression(&mut self, data: Vec<u8>) -> Option<Vec<u8>> {
+        let head = match self.compression_head.as_mut() {
+            None => return Some(data),
+            Some(head) => head,
+        };
+        head.extend_from_slice(&data);
+        if head.len() < GZIP_MAGIC.len() {
+            return None;
+        }
+        let data = mem::take(head);
+        self.compression_head = None;
+        if data.starts_with(GZIP_MAGIC) {
+            tracing::debug!("detect gzip compression for {}", self.path);
+            self.decoder = Some(Decompressor::new_opendal(CompressAlgorithm::Gzip));
+        }
+        Some(data)
+    }
+
+    /// Drop the footer rows at the end of the file.
+    fn skip_footer(&mut self, row_batches: Vec<RowBatch>, is_end: bool) -> Vec<RowBatch> {
+        let output = self.footer.push(row_batches);
+        if is_end {
+            if let Some(footer) = self.footer.take() {
+                tracing::debug!(
+                    "skip {} footer rows of {}",
+                    footer.row_ends.len(),
+                    self.path
+                );
+            }
+        }
+        output
+    }
+
+    /// Drop the rows beyond `ctx.max_rows`, the skipped header rows are not in the batches.
+    fn limit_rows(&self, row_batches: Vec<RowBatch>) -> Vec<RowBatch> {
+        if self.ctx.max_rows.is_none() {
+            return row_batches;
+        }
+        let mut limited = Vec::with_capacity(row_batches.len());
+        for mut batch in row_batches {
+            let num_rows = batch.row_ends.len();
+            let n = self.ctx.take_rows(num_rows);
+            if n == 0 {
+                break;
+            }
+            if n < num_rows {
+                let num_fields = batch.field_ends.len() / num_rows;
+                batch.row_ends.truncate(n);
+                batch.row_offsets.truncate(n);
+                batch.field_ends.truncate(n * num_fields);
+                batch.field_quoted.truncate(n * num_fields);
+                batch.data.truncate(batch.row_ends[n - 1]);
+            }
+            limited.push(batch);
+        }
+        limited
+    }
+
+    /// Merge the batches until `ctx.batch_target_rows` or `ctx.batch_target_bytes` is reached,
+    /// the pending rows are always output at the end.
+    fn accumulate(&mut self, row_batches: Vec<RowBatch>, is_end: bool) -> Vec<RowBatch> {
+        let target_rows = self.ctx.batch_target_rows;
+        let target_bytes = self.ctx.batch_target_bytes;
+        if target_rows == 0 && target_bytes == 0 {
+            return row_batches;
+        }
+        let mut output = vec![];
+        for batch in row_batches {
+            match &mut self.pending_batch {
+                Some(pending) => pending.append(batch),
+                None => self.pending_batch = Some(batch),
+            }
+            let pending = self.pending_batch.as_ref().expect("must success");
+            if (target_rows > 0 && pending.row_ends.len() >= target_rows)
+                || (target_bytes > 0 && pending.data.len() >= target_bytes)
+            {
+                output.extend(self.pending_batch.take());
+            }
+        }
+        if is_end {
+            output.extend(self.pending_batch.take());
+        }
+        output
+    }
+
+    pub fn flush(&mut self) -> Vec<RowBatch> {
         if self.tail_of_last_batch.is_empty() {
             vec![]
         } else {
             // last row
             let data = mem::take(&mut self.tail_of_last_batch);
             let end = data.len();
+            let offset = self.offset - end;
             let row_batch = RowBatch {
                 data,
                 row_ends: vec![end],
+                row_offsets: vec![offset],
                 field_ends: vec![],
+                field_quoted: vec![],
+                column_mapping: None,
                 path: self.path.to_string(),
+                file_index: self.file_index,
                 batch_id: self.batch_id,
-                offset: self.offset,
+                offset,
                 start_row: Some(self.rows),
+                checksum: None,
             };
             tracing::debug!(
                 "align flush batch {}, bytes = {}, start_row = {}",
@@ -303,11 +606,57 @@ impl<T: InputFormatTextBase> AligningStateTrait for AligningState<T> {
         } else {
             (T::is_splittable() && split_info.num_file_splits > 1) as usize
         };
+        let footer = if split_info.seq_in_file + 1 == split_info.num_file_splits {
+            TailRows::create(ctx.rows_to_skip_footer)
+        } else {
+            TailRows::create(0)
+        };
         let path = split_info.file.path.clone();
 
-        let decoder = ctx.get_compression_alg(&path)?.map(DecompressDecoder::new);
-        let csv_reader = if T::format_type() == StageFileFormatType::Csv {
-            Some(CsvReaderState::create(ctx))
+        let decoder = Decompressor::try_create(ctx, &path)?;
+        let compression_head =
+            if split_info.num_file_splits == 1 && ctx.need_detect_compression(&path) {
+                Some(vec![])
+            } else {
+                None
+            };
+        let is_csv = matches!(
+            T::format_type(),
+            StageFileFormatType::Csv | StageFileFormatType::CsvWithTypes
+        );
+        if !is_csv {
+            match &ctx.record_delimiter {
+                RecordDelimiter::Multi(v) => {
+                    return Err(ErrorCode::InvalidArgument(format!(
+                        "multi-byte record_delimiter {:?} is only supported by CSV",
+                        String::from_utf8_lossy(v)
+                    )));
+                }
+                RecordDelimiter::Auto => {
+                    return Err(ErrorCode::InvalidArgument(
+                        "record_delimiter 'auto' is only supported by CSV",
+                    ));
+                }
+                _ => {}
+            }
+        }
+        let csv_reader = if is_csv {
+            let with_types = T::format_type() == StageFileFormatType::CsvWithTypes;
+            Some(CsvReaderState::try_create(
+                ctx,
+                split_info.offset,
+                with_types,
+            )?)
+        } else {
+            None
+        };
+        let ndjson_reader = if T::format_type() == StageFileFormatType::NdJson {
+            Some(NdJsonReaderState::default())
+        } else {
+            None
+        };
+        let json_array_reader = if T::format_type() == StageFileFormatType::Json {
+            Some(JsonArrayReaderState::default())
         } else {
             None
         };
@@ -316,9 +665,18 @@ impl<T: InputFormatTextBase> AligningStateTrait for AligningState<T> {
             ctx: ctx.clone(),
             split_info: split_info.clone(),
             path,
+            file_index: split_info.file.file_index,
             decoder,
+            compression_head,
             rows_to_skip,
+            footer,
             csv_reader,
+            ndjson_reader,
+            json_array_reader,
+            column_mapping: None,
+            header: None,
+            load_stats: InputLoadStats::default(),
+            pending_batch: None,
             tail_of_last_batch: vec![],
             rows: 0,
             batch_id: 0,
@@ -331,19 +689,70 @@ impl<T: InputFormatTextBase> AligningStateTrait for AligningState<T> {
     }
 
     fn align(&mut self, read_batch: Option<Vec<u8>>) -> Result<Vec<RowBatch>> {
-        let row_batches = if let Some(data) = read_batch {
-            let buf = if let Some(decoder) = self.decoder.as_mut() {
-                decompress(decoder, &data)?
-            } else {
-                data
-            };
-            T::align(self, &buf)?
+        let start = self.ctx.phase_timing.then(Instant::now);
+        let is_end = read_batch.is_none();
+        let row_batches = if self.ctx.is_row_limit_reached() {
+            // the data beyond the limit is dropped without parsing
+            vec![]
+        } else if let Some(data) = read_batch {
+            self.load_stats.bytes += data.len();
+            match self.detect_compression(data) {
+                Some(data) => match self.decoder.take() {
+                    Some(mut decoder) => {
+                        // feed the aligner chunk by chunk, the decompressed data may be huge
+                        let mut row_batches = vec![];
+                        let chunk_size = self.ctx.read_batch_size;
+                        let res = decoder.decompress(&data, chunk_size, |buf| {
+                            row_batches.extend(T::align(self, buf)?);
+                            Ok(())
+                        });
+                        self.decoder = Some(decoder);
+                        res?;
+                        row_batches
+                    }
+                    None => T::align(self, &data)?,
+                },
+                None => vec![],
+            }
         } else {
-            if let Some(decoder) = &self.decoder {
-                assert_eq!(decoder.state(), DecompressState::Done)
+            let mut row_batches = match self.compression_head.take() {
+                // too short to be compressed
+                Some(head) if !head.is_empty() => T::align(self, &head)?,
+                _ => vec![],
+            };
+            if let Some(decoder) = &mut self.decoder {
+                if !decoder.is_done() {
+                    return Err(ErrorCode::InvalidCompressionData(format!(
+                        "truncated compressed file {}",
+                        self.path
+                    )));
+                }
             }
-            self.flush()
+            row_batches.extend(T::align_flush(self)?);
+            row_batches
         };
+        let row_batches = self.skip_footer(row_batches, is_end);
+        let row_batches = self.limit_rows(row_batches);
+        let mut row_batches = self.accumulate(row_batches, is_end);
+        if self.ctx.batch_checksum {
+            for b in row_batches.iter_mut() {
+                b.checksum = Some(b.data_checksum());
+            }
+        }
+        if let Some(callback) = &self.ctx.progress_callback {
+            if !row_batches.is_empty() {
+                callback(self.offset - self.split_info.offset, self.rows);
+            }
+        }
+        self.load_stats.batches += row_batches.len();
+        self.load_stats.rows += row_batches.iter().map(|b| b.row_ends.len()).sum::<usize>();
+        if let Some(start) = start {
+            self.load_stats.align_nanos += start.elapsed().as_nanos() as u64;
+        }
+        if is_end {
+            tracing::debug!("aligner of {} end with {:?}", self.path, self.load_stats);
+            self.ctx.add_load_stats(&self.load_stats);
+        }
         Ok(row_batches)
     }
 
@@ -357,30 +766,258 @@ impl<T: InputFormatTextBase> AligningStateTrait for AligningState<T> {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct RejectedRow {
+    // copied out of RowBatch.data, which is dropped after deserialized
+    pub data: Vec<u8>,
+    pub row_index: Option<usize>,
+    pub message: String,
+}
+
 pub struct BlockBuilder<T> {
     pub ctx: Arc<InputContext>,
     pub mutable_columns: Vec<TypeDeserializerImpl>,
+    // the columns deserialized by other threads, which are before the values of
+    // mutable_columns, parallel with mutable_columns and concatenated by `flush`
+    finished_columns: Vec<Vec<ColumnRef>>,
     pub num_rows: usize,
+    // malformed rows skipped by this builder under InputErrorMode::SkipError
+    pub num_skipped_rows: usize,
+    // dropped by `InputContext::record_filter` or `InputContext::key_dedup`
+    pub num_filtered_rows: usize,
+    // the skipped rows, collected only under InputErrorMode::SkipError
+    pub rejected_rows: Option<Vec<RejectedRow>>,
+    // the (file index, row offset) of the rows loaded, kept only with ctx.key_dedup
+    pub row_positions: Vec<RowPosition>,
+    // parallel with ctx.virtual_columns
+    pub virtual_columns: Vec<TypeDeserializerImpl>,
+    // the rows of the current batch skipped, whose virtual values are not filled
+    skipped_row_indexes: Vec<Option<usize>>,
+    // the file of the current batch, kept only under InputErrorMode::CollectErrors
+    path: String,
+    // measured only if ctx.phase_timing
+    deserialize_nanos: u64,
     phantom: PhantomData<T>,
 }
 
 impl<T: InputFormatTextBase> BlockBuilder<T> {
+    /// Called after the values of a malformed row are rolled back,
+    /// return the error if the row can not be skipped.
+    pub fn skip_error_row(
+        &mut self,
+        e: ErrorCode,
+        row: &[u8],
+        row_index: Option<usize>,
+    ) -> Result<()> {
+        self.skip_error_field(e, row, row_index, None)
+    }
+
+    /// Like `skip_error_row`, with the index in `ctx.output_schema` of the malformed field
+    /// if known.
+    pub fn skip_error_field(
+        &mut self,
+        e: ErrorCode,
+        row: &[u8],
+        row_index: Option<usize>,
+        column: Option<usize>,
+    ) -> Result<()> {
+        match self.ctx.error_mode {
+            InputErrorMode::AbortOnError => Err(e),
+            InputErrorMode::CollectErrors(_) => {
+                self.num_rows -= 1;
+                self.num_skipped_rows += 1;
+                if !self.virtual_columns.is_empty() {
+                    self.skipped_row_indexes.push(row_index);
+                }
+                self.ctx.add_load_error(LoadError {
+                    file: self.path.clone(),
+                    row: row_index.map(|r| r + 1),
+                    column: column.map(|c| self.ctx.output_schema.field(c).name().to_string()),
+                    message: e.message(),
+                    raw: row.to_vec(),
+                });
+                Ok(())
+            }
+            InputErrorMode::SkipError(max_errors) => {
+                self.num_rows -= 1;
+                self.num_skipped_rows += 1;
+                if !self.virtual_columns.is_empty() {
+                    self.skipped_row_indexes.push(row_index);
+                }
+                if self.num_skipped_rows > max_errors {
+                    Err(e.add_message_back(format!(
+                        "(abort after skipping {} malformed rows)",
+                        max_errors
+                    )))
+                } else {
+                    tracing::warn!("skip malformed row: {}", e.message());
+                    if let Some(rejected_rows) = self.rejected_rows.as_mut() {
+                        rejected_rows.push(RejectedRow {
+                            data: row.to_vec(),
+                            row_index,
+                            message: e.message(),
+                        });
+                    }
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Append the values deserialized by another thread after the values so far, column by column.
+    pub fn append_deserializers(&mut self, mut others: Vec<TypeDeserializerImpl>) {
+        let fields = self.ctx.output_schema.fields();
+        for (((deserializer, other), finished), field) in self
+            .mutable_columns
+            .iter_mut()
+            .zip(others.iter_mut())
+            .zip(self.finished_columns.iter_mut())
+            .zip(fields)
+        {
+            let column = deserializer.finish_to_column();
+            if !column.is_empty() {
+                finished.push(column);
+                *deserializer = field
+                    .data_type()
+                    .create_deserializer(self.ctx.rows_per_block);
+            }
+            finished.push(other.finish_to_column());
+        }
+    }
+
+    /// Drop a row not accepted by `InputFormatTextBase::accept_record`, or of a duplicate key.
+    pub fn filter_out_row(&mut self, row_index: Option<usize>) {
+        self.num_rows -= 1;
+        self.num_filtered_rows += 1;
+        if !self.virtual_columns.is_empty() {
+            self.skipped_row_indexes.push(row_index);
+        }
+    }
+
+    /// Fill the virtual columns for the rows of a batch which are not skipped.
+    fn append_virtual_values(
+        &mut self,
+        path: &str,
+        start_row: Option<usize>,
+        row_offsets: &[usize],
+        num_rows: usize,
+    ) -> Result<()> {
+        let skipped = mem::take(&mut self.skipped_row_indexes);
+        // the skipped rows are unknown without start_row, but so are the line numbers
+        let num_rows = match start_row {
+            Some(_) => num_rows,
+            None => num_rows - skipped.len(),
+        };
+        let format_settings = &self.ctx.format_settings;
+        for i in 0..num_rows {
+            let row_index = start_row.map(|n| n + i);
+            if row_index.is_some() && skipped.contains(&row_index) {
+                continue;
+            }
+            for ((_, column), deserializer) in self
+                .ctx
+                .virtual_columns
+                .iter()
+                .zip(self.virtual_columns.iter_mut())
+            {
+                match (column, row_index) {
+                    (VirtualColumn::FileName, _) => {
+                        deserializer.de_whole_text(path.as_bytes(), format_settings)?
+                    }
+                    (VirtualColumn::LineNumber, Some(r)) => deserializer
+                        .de_whole_text((r + 1).to_string().as_bytes(), format_settings)?,
+                    (VirtualColumn::LineNumber, None) => deserializer.de_default(format_settings),
+                    (VirtualColumn::RowOffset, _) => match row_offsets.get(i) {
+                        Some(offset) => deserializer
+                            .de_whole_text(offset.to_string().as_bytes(), format_settings)?,
+                        None => deserializer.de_default(format_settings),
+                    },
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn create_virtual_deserializers(ctx: &InputContext) -> Vec<TypeDeserializerImpl> {
+        ctx.virtual_columns
+            .iter()
+            .map(|(c, _)| {
+                ctx.block_schema
+                    .field(*c)
+                    .data_type()
+                    .create_deserializer(ctx.rows_per_block)
+            })
+            .collect()
+    }
+
     fn flush(&mut self) -> Result<Vec<DataBlock>> {
-        let mut columns = Vec::with_capacity(self.mutable_columns.len());
-        for deserializer in &mut self.mutable_columns {
-            columns.push(deserializer.finish_to_column());
+        if self.ctx.validate_only {
+            // the rows are checked already, drop the values
+            self.mutable_columns = self
+                .ctx
+                .output_schema
+                .create_deserializers(self.ctx.rows_per_block);
+            self.virtual_columns = Self::create_virtual_deserializers(&self.ctx);
+            self.finished_columns.iter_mut().for_each(Vec::clear);
+            self.row_positions.clear();
+            self.num_rows = 0;
+            return Ok(vec![]);
+        }
+        let mut columns = Vec::with_capacity(self.ctx.block_schema.num_fields());
+        for (deserializer, finished) in self
+            .mutable_columns
+            .iter_mut()
+            .zip(self.finished_columns.iter_mut())
+        {
+            let column = deserializer.finish_to_column();
+            if finished.is_empty() {
+                columns.push(column);
+            } else {
+                finished.push(column);
+                columns.push(Series::concat(&mem::take(finished))?);
+            }
+        }
+        // in the order of block_schema, as the virtual columns are sorted
+        for ((c, _), deserializer) in self
+            .ctx
+            .virtual_columns
+            .iter()
+            .zip(self.virtual_columns.iter_mut())
+        {
+            columns.insert(*c, deserializer.finish_to_column());
         }
         self.mutable_columns = self
             .ctx
-            .schema
+            .output_schema
             .create_deserializers(self.ctx.rows_per_block);
+        self.virtual_columns = Self::create_virtual_deserializers(&self.ctx);
         self.num_rows = 0;
 
-        Ok(vec![DataBlock::create(self.ctx.schema.clone(), columns)])
+        let block = DataBlock::create(self.ctx.block_schema.clone(), columns);
+        match &self.ctx.key_dedup {
+            // the formats without positions of rows are not deduplicated
+            Some(dedup) if !self.row_positions.is_empty() => {
+                dedup.hold_block(block, mem::take(&mut self.row_positions));
+                Ok(vec![])
+            }
+            _ => Ok(vec![block]),
+        }
     }
 
     fn memory_size(&self) -> usize {
-        self.mutable_columns.iter().map(|x| x.memory_size()).sum()
+        let finished: usize = self
+            .finished_columns
+            .iter()
+            .flatten()
+            .map(|c| c.memory_size())
+            .sum();
+        let mutable: usize = self
+            .mutable_columns
+            .iter()
+            .chain(self.virtual_columns.iter())
+            .map(|x| x.memory_size())
+            .sum();
+        finished + mutable
     }
 }
 
@@ -388,19 +1025,47 @@ impl<T: InputFormatTextBase> BlockBuilderTrait for BlockBuilder<T> {
     type Pipe = InputFormatTextPipe<T>;
 
     fn create(ctx: Arc<InputContext>) -> Self {
-        let columns = ctx.schema.create_deserializers(ctx.rows_per_block);
+        let columns = ctx.output_schema.create_deserializers(ctx.rows_per_block);
+        let rejected_rows = match ctx.error_mode {
+            InputErrorMode::AbortOnError | InputErrorMode::CollectErrors(_) => None,
+            InputErrorMode::SkipError(_) => Some(vec![]),
+        };
         BlockBuilder {
+            virtual_columns: Self::create_virtual_deserializers(&ctx),
+            finished_columns: vec![vec![]; ctx.output_schema.num_fields()],
             ctx,
             mutable_columns: columns,
             num_rows: 0,
+            num_skipped_rows: 0,
+            num_filtered_rows: 0,
+            rejected_rows,
+            row_positions: vec![],
+            skipped_row_indexes: vec![],
+            path: String::new(),
+            deserialize_nanos: 0,
             phantom: Default::default(),
         }
     }
 
     fn deserialize(&mut self, batch: Option<RowBatch>) -> Result<Vec<DataBlock>> {
         if let Some(b) = batch {
+            b.check_checksum()?;
+            let start = self.ctx.phase_timing.then(Instant::now);
             self.num_rows += b.row_ends.len();
-            T::deserialize(self, b)?;
+            if matches!(self.ctx.error_mode, InputErrorMode::CollectErrors(_)) {
+                self.path.clone_from(&b.path);
+            }
+            if self.virtual_columns.is_empty() {
+                T::deserialize(self, b)?;
+            } else {
+                let (path, start_row, num_rows) = (b.path.clone(), b.start_row, b.row_ends.len());
+                let row_offsets = b.row_offsets.clone();
+                T::deserialize(self, b)?;
+                self.append_virtual_values(&path, start_row, &row_offsets, num_rows)?;
+            }
+            if let Some(start) = start {
+                self.deserialize_nanos += start.elapsed().as_nanos() as u64;
+            }
             let mem = self.memory_size();
             tracing::debug!(
                 "block builder added new batch: row {} size {}",
@@ -415,43 +1080,121 @@ impl<T: InputFormatTextBase> BlockBuilderTrait for BlockBuilder<T> {
                 Ok(vec![])
             }
         } else {
+            if self.num_skipped_rows > 0 {
+                tracing::info!(
+                    "block builder skipped {} malformed rows",
+                    self.num_skipped_rows
+                );
+            }
+            if self.num_skipped_rows > 0 || self.num_filtered_rows > 0 || self.deserialize_nanos > 0
+            {
+                self.ctx.add_load_stats(&InputLoadStats {
+                    skipped_rows: self.num_skipped_rows,
+                    filtered_rows: self.num_filtered_rows,
+                    deserialize_nanos: self.deserialize_nanos,
+                    ..Default::default()
+                });
+            }
             self.flush()
         }
     }
 }
 
-fn decompress(decoder: &mut DecompressDecoder, compressed: &[u8]) -> Result<Vec<u8>> {
-    let mut decompress_bufs = vec![];
-    let mut amt = 0;
-    loop {
-        match decoder.state() {
-            DecompressState::Reading => {
-                if amt == compressed.len() {
-                    break;
-                }
-                let read = decoder.fill(&compressed[amt..]);
-                amt += read;
-            }
-            DecompressState::Decoding => {
-                let mut decompress_buf = vec![0u8; 4096];
-                let written = decoder.decode(&mut decompress_buf[..]).map_err(|e| {
-                    ErrorCode::InvalidCompressionData(format!("compression data invalid: {e}"))
-                })?;
-                decompress_buf.truncate(written);
-                decompress_bufs.push(decompress_buf);
-            }
-            DecompressState::Flushing => {
-                let mut decompress_buf = vec![0u8; 4096];
-                let written = decoder.finish(&mut decompress_buf).map_err(|e| {
-                    ErrorCode::InvalidCompressionData(format!("compression data invalid: {e}"))
-                })?;
-                decompress_buf.truncate(written);
-                decompress_bufs.push(decompress_buf);
-            }
-            DecompressState::Done => break,
+/// Remove the values appended to the first `num_columns` columns by a malformed row.
+pub fn rollback_row(deserializers: &mut [TypeDeserializerImpl], num_columns: usize) {
+    for deserializer in deserializers[..num_columns].iter_mut() {
+        let _ = deserializer.pop_data_value();
+    }
+}
+
+/// Append an unquoted empty field of the column as `format_settings.empty_field_as` asks,
+/// return the error message if it can not be.
+pub fn de_empty_field(
+    deserializer: &mut TypeDeserializerImpl,
+    schema: &DataSchemaRef,
+    column_index: usize,
+    format_settings: &FormatSettings,
+) -> std::result::Result<(), String> {
+    let field = schema.field(column_index);
+    match format_settings.empty_field_as {
+        EmptyFieldAs::Null if !field.is_nullable_or_null() => {
+            return Err("empty field as NULL, but the column is not nullable".to_string());
+        }
+        EmptyFieldAs::EmptyString
+            if remove_nullable(field.data_type())
+                .data_type_id()
+                .is_string() =>
+        {
+            deserializer
+                .de_whole_text(&[], format_settings)
+                .map_err(|e| e.message())?;
+        }
+        // NULL is the default value of nullable columns
+        EmptyFieldAs::Null => deserializer.de_default(format_settings),
+        _ => match format_settings.column_defaults.get(&column_index) {
+            Some(literal) => deserializer
+                .de_whole_text(literal, format_settings)
+                .map_err(|e| e.message())?,
+            None => deserializer.de_default(format_settings),
+        },
+    }
+    Ok(())
+}
+
+/// Parse the default values of columns like `0:-1,1:UNKNOWN`.
+pub fn parse_column_defaults(s: &str) -> Result<HashMap<usize, Vec<u8>>> {
+    let mut column_defaults = HashMap::new();
+    for item in s.split(',').filter(|i| !i.is_empty()) {
+        let (column_index, literal) = item
+            .split_once(':')
+            .and_then(|(c, v)| c.trim().parse::<usize>().ok().map(|c| (c, v)))
+            .ok_or_else(|| {
+                ErrorCode::InvalidArgument(format!(
+                    "column_defaults expect <column index>:<default value>, got {}",
+                    item
+                ))
+            })?;
+        if column_defaults
+            .insert(column_index, literal.as_bytes().to_vec())
+            .is_some()
+        {
+            return Err(ErrorCode::InvalidArgument(format!(
+                "column_defaults has more than one default value of column {}",
+                column_index
+            )));
         }
     }
-    Ok(decompress_bufs.concat())
+    Ok(column_defaults)
+}
+
+/// Fail before reading any data if a default value of `format_settings.column_defaults`
+/// is not a value of its column.
+pub fn check_column_defaults(
+    schema: &DataSchemaRef,
+    format_settings: &FormatSettings,
+) -> Result<()> {
+    for (column_index, literal) in &format_settings.column_defaults {
+        if *column_index >= schema.num_fields() {
+            return Err(ErrorCode::InvalidArgument(format!(
+                "column_defaults has column {}, but the table has {} columns only",
+                column_index,
+                schema.num_fields()
+            )));
+        }
+        let field = schema.field(*column_index);
+        let mut deserializer = field.data_type().create_deserializer(1);
+        if let Err(e) = deserializer.de_whole_text(literal, format_settings) {
+            return Err(ErrorCode::InvalidArgument(format!(
+                "default value '{}' of column {} '{}' ({}) is invalid: {}",
+                String::from_utf8_lossy(literal),
+                column_index,
+                field.name(),
+                field.data_type().name(),
+                e.message()
+            )));
+        }
+    }
+    Ok(())
 }
 
 pub fn get_time_zone(settings: &Settings) -> Result<Tz> {
@@ -459,3 +1202,28 @@ pub fn get_time_zone(settings: &Settings) -> Result<Tz> {
     tz.parse::<Tz>()
         .map_err(|_| ErrorCode::InvalidTimezone("Timezone has been checked and should be valid"))
 }
+
+pub fn get_empty_field_as(settings: &Settings) -> Result<EmptyFieldAs> {
+    match settings
+        .get_format_empty_field_as()?
+        .to_lowercase()
+        .as_str()
+    {
+        "default" => Ok(EmptyFieldAs::Default),
+        "null" => Ok(EmptyFieldAs::Null),
+        "empty_string" => Ok(EmptyFieldAs::EmptyString),
+        v => Err(ErrorCode::InvalidArgument(format!(
+            "empty_field_as can only be 'default', 'null' or 'empty_string', got '{}'",
+            v
+        ))),
+    }
+}
+
+/// (date_format, timestamp_format), an empty setting means the built-in parser.
+pub fn get_datetime_formats(settings: &Settings) -> Result<(Option<String>, Option<String>)> {
+    let non_empty = |s: String| if s.is_empty() { None } else { Some(s) };
+    Ok((
+        non_empty(settings.get_format_date_format()?),
+        non_empty(settings.get_format_timestamp_format()?),
+    ))
+}
@@ -37,6 +37,7 @@ use crate::processors::sources::input_formats::input_split::SplitInfo;
 use crate::processors::sources::input_formats::source_aligner::Aligner;
 use crate::processors::sources::input_formats::source_deserializer::DeserializeSource;
 use crate::processors::sources::input_formats::transform_deserializer::DeserializeTransformer;
+use crate::processors::sources::input_formats::transform_key_dedup::KeyDedupTransform;
 
 pub struct Split<I: InputFormatPipe> {
     pub(crate) info: Arc<SplitInfo>,
@@ -99,6 +100,7 @@ pub trait InputFormatPipe: Sized + Send + 'static {
 
         GlobalIORuntime::instance().spawn(async move {
             let mut sender: Option<Sender<Result<Self::ReadBatch>>> = None;
+            let mut num_files = 0;
             while let Some(batch_result) = input.recv().await {
                 match batch_result {
                     Ok(batch) => {
@@ -107,8 +109,10 @@ pub trait InputFormatPipe: Sized + Send + 'static {
                             sender = Some(data_tx);
                             let split_info = Arc::new(SplitInfo::from_stream_split(
                                 batch.path.clone(),
+                                num_files,
                                 batch.compression,
                             ));
+                            num_files += 1;
                             split_tx
                                 .send(Ok(Split {
                                     info: split_info,
@@ -217,6 +221,8 @@ pub trait InputFormatPipe: Sized + Send + 'static {
     ) -> Result<()> {
         let n_threads = ctx.settings.get_max_threads()? as usize;
         let max_aligner = match ctx.plan {
+            // take the rows within `max_rows` in the order of the splits
+            _ if ctx.max_rows.is_some() => 1,
             InputPlan::CopyInto(_) => ctx.splits.len(),
             InputPlan::StreamingLoad(StreamPlan { is_multi_part, .. }) => {
                 if is_multi_part {
@@ -242,6 +248,13 @@ pub trait InputFormatPipe: Sized + Send + 'static {
         pipeline.add_transform(|input, output| {
             DeserializeTransformer::<Self>::create(ctx.clone(), input, output, row_batch_rx.clone())
         })?;
+        if ctx.key_dedup.is_some() {
+            // a kept record may be replaced until all the records are read
+            pipeline.resize(1)?;
+            pipeline.add_transform(|input, output| {
+                KeyDedupTransform::create(ctx.clone(), input, output)
+            })?;
+        }
         Ok(())
     }
 
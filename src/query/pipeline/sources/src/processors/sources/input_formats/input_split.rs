@@ -27,6 +27,8 @@ pub trait DynData: Send + Sync + 'static {
 #[derive(Debug)]
 pub struct FileInfo {
     pub path: String,
+    // the position of the file among the files of the load
+    pub file_index: usize,
     pub size: usize,
     pub num_splits: usize,
     pub compress_alg: Option<CompressAlgorithm>,
@@ -83,10 +85,15 @@ pub fn split_by_size(size: usize, split_size: usize) -> Vec<(usize, usize)> {
 }
 
 impl SplitInfo {
-    pub fn from_stream_split(path: String, compress_alg: Option<CompressAlgorithm>) -> Self {
+    pub fn from_stream_split(
+        path: String,
+        file_index: usize,
+        compress_alg: Option<CompressAlgorithm>,
+    ) -> Self {
         SplitInfo {
             file: Arc::new(FileInfo {
                 path,
+                file_index,
                 size: 0,
                 num_splits: 1,
                 compress_alg,
@@ -13,6 +13,8 @@
 //  limitations under the License.
 
 mod beyond_end_reader;
+mod csv_splitter;
+mod decompressor;
 mod delimiter;
 mod impls;
 mod input_context;
@@ -20,11 +22,37 @@ mod input_format;
 mod input_format_text;
 mod input_pipeline;
 mod input_split;
+mod schema_inference;
 mod source_aligner;
 mod source_deserializer;
 mod transform_deserializer;
+mod transform_key_dedup;
 
+pub use csv_splitter::find_csv_record_start;
+pub use decompressor::Decompressor;
+pub use delimiter::detect_field_delimiter;
+pub use delimiter::RecordDelimiter;
+pub use impls::input_format_csv::record_preview;
+pub use impls::input_format_csv::CsvErrorKind;
+pub use impls::input_format_csv::CsvParseError;
+pub use impls::input_format_csv::CsvReaderState;
+pub use impls::input_format_csv::HeaderCheck;
+pub use impls::input_format_csv::InputFormatCSV;
+pub use input_context::AlignProgressCallback;
+pub use input_context::DedupMode;
+pub use input_context::HeaderMapper;
 pub use input_context::InputContext;
+pub use input_context::InputErrorMode;
+pub use input_context::InputLoadStats;
+pub use input_context::KeyDedup;
+pub use input_context::LoadError;
+pub use input_context::RecordFilter;
+pub use input_context::RecordValidator;
+pub use input_context::RowPosition;
+pub use input_context::VirtualColumn;
 pub use input_format::InputFormat;
+pub use input_format_text::RowBatch;
+pub use input_format_text::TailRows;
 pub use input_pipeline::StreamingReadBatch;
 pub use input_split::split_by_size;
+pub use schema_inference::infer_csv_schema;
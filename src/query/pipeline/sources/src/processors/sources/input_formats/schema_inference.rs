@@ -0,0 +1,306 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use bstr::ByteSlice;
+use common_datavalues::DataField;
+use common_datavalues::DataSchemaRef;
+use common_datavalues::DataSchemaRefExt;
+use common_datavalues::DataType;
+use common_datavalues::DataTypeImpl;
+use common_datavalues::DateType;
+use common_datavalues::Float64Type;
+use common_datavalues::Int64Type;
+use common_datavalues::NullableType;
+use common_datavalues::StringType;
+use common_datavalues::TimestampType;
+use common_datavalues::TypeDeserializer;
+use common_datavalues::TypeDeserializerImpl;
+use common_exception::ErrorCode;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+use csv_core::ReadRecordResult;
+
+use crate::processors::sources::input_formats::csv_splitter::CsvSplitter;
+use crate::processors::sources::input_formats::delimiter::detect_field_delimiter;
+use crate::processors::sources::input_formats::delimiter::RecordDelimiter;
+use crate::processors::sources::input_formats::impls::input_format_csv::is_null_value;
+
+/// The inferred type of a column so far, from the narrowest to the widest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InferredType {
+    // only nulls are seen
+    Unknown,
+    Int64,
+    Float64,
+    Date,
+    Timestamp,
+    String,
+}
+
+impl InferredType {
+    /// the narrowest type which can hold values of both types.
+    fn merge(self, other: InferredType) -> InferredType {
+        use InferredType::*;
+        match (self, other) {
+            (Unknown, t) | (t, Unknown) => t,
+            (a, b) if a == b => a,
+            (Int64, Float64) | (Float64, Int64) => Float64,
+            (Date, Timestamp) | (Timestamp, Date) => Timestamp,
+            _ => String,
+        }
+    }
+
+    fn data_type(self) -> DataTypeImpl {
+        match self {
+            InferredType::Int64 => Int64Type::new_impl(),
+            InferredType::Float64 => Float64Type::new_impl(),
+            InferredType::Date => DateType::new_impl(),
+            InferredType::Timestamp => TimestampType::new_impl(),
+            InferredType::Unknown | InferredType::String => StringType::new_impl(),
+        }
+    }
+}
+
+/// Values are checked by the deserializers used to load them, so inferred types always load.
+struct TypeProber {
+    int64: TypeDeserializerImpl,
+    float64: TypeDeserializerImpl,
+    date: TypeDeserializerImpl,
+    timestamp: TypeDeserializerImpl,
+}
+
+impl TypeProber {
+    fn create() -> Self {
+        Self {
+            int64: Int64Type::new_impl().create_deserializer(1),
+            float64: Float64Type::new_impl().create_deserializer(1),
+            date: DateType::new_impl().create_deserializer(1),
+            timestamp: TimestampType::new_impl().create_deserializer(1),
+        }
+    }
+
+    fn probe(&mut self, value: &[u8], format_settings: &FormatSettings) -> InferredType {
+        if format_settings.preserve_leading_zeros && has_leading_zero(value) {
+            // the number would lose its zeros
+            InferredType::String
+        } else if probe(&mut self.int64, value, format_settings) {
+            InferredType::Int64
+        } else if probe(&mut self.float64, value, format_settings) {
+            InferredType::Float64
+        } else if value.len() == 10 && probe(&mut self.date, value, format_settings) {
+            // the date deserializer also accepts a timestamp, and drops the time
+            InferredType::Date
+        } else if probe(&mut self.timestamp, value, format_settings) {
+            InferredType::Timestamp
+        } else {
+            InferredType::String
+        }
+    }
+}
+
+/// e.g. `007` and `-01.5`, but not `0` or `0.5`.
+fn has_leading_zero(value: &[u8]) -> bool {
+    let digits = match value.first() {
+        Some(b'+' | b'-') => &value[1..],
+        _ => value,
+    };
+    digits.len() > 1 && digits[0] == b'0' && digits[1].is_ascii_digit()
+}
+
+fn probe(
+    deserializer: &mut TypeDeserializerImpl,
+    value: &[u8],
+    format_settings: &FormatSettings,
+) -> bool {
+    if deserializer.de_whole_text(value, format_settings).is_ok() {
+        let _ = deserializer.pop_data_value();
+        true
+    } else {
+        false
+    }
+}
+
+/// Infer the schema of CSV `data` from its first `max_rows` data rows.
+///
+/// Each column gets the widest type of its sampled values, among Int64, Float64, Date,
+/// Timestamp and String, and is nullable if any sampled value is null,
+/// i.e. an unquoted empty field, `format_settings.null_bytes` or a null value of the column.
+/// With `format_settings.preserve_leading_zeros`, a number with leading zeros is a String,
+/// so are all the columns named in `format_settings.preserve_string_numerics`.
+/// The columns are named by the header if `has_header`, otherwise `c1`, `c2`, ...
+pub fn infer_csv_schema(
+    data: &[u8],
+    format_settings: &FormatSettings,
+    record_delimiter: &RecordDelimiter,
+    has_header: bool,
+    max_rows: usize,
+) -> Result<DataSchemaRef> {
+    let detected;
+    let record_delimiter = match record_delimiter {
+        RecordDelimiter::Auto => {
+            detected = RecordDelimiter::detect(
+                data,
+                format_settings.quote_char,
+                format_settings.escape_char,
+                true,
+            )
+            .unwrap_or(RecordDelimiter::Any(b'\n'));
+            &detected
+        }
+        d => d,
+    };
+    let mut reader = CsvSplitter::try_create(format_settings, record_delimiter)?;
+    if format_settings
+        .field_delimiter
+        .eq_ignore_ascii_case(b"auto")
+    {
+        // no columns to match, the most frequent one is taken
+        let delimiter = detect_field_delimiter(
+            data,
+            format_settings.quote_char,
+            format_settings.escape_char,
+            record_delimiter.end(),
+            None,
+            format_settings.decimal_separator,
+            true,
+        )
+        .expect("must success");
+        reader = reader.with_field_delimiter(format_settings, delimiter)?;
+    }
+    let mut out = vec![0u8; data.len() + reader.max_pending()];
+    let mut field_ends = vec![0usize; 16];
+    let mut field_quoted = vec![false; 16];
+    let mut prober = TypeProber::create();
+
+    let mut names: Option<Vec<String>> = None;
+    let mut types: Vec<InferredType> = vec![];
+    let mut nullables: Vec<bool> = vec![];
+    let mut num_rows = 0;
+
+    let mut buf = data;
+    let mut out_pos = 0;
+    let mut n_end = 0;
+    while num_rows < max_rows {
+        let (result, n_in, n_out, n) = reader.read_record(
+            buf,
+            &mut out[out_pos..],
+            &mut field_ends[n_end..],
+            &mut field_quoted[n_end..],
+        );
+        buf = &buf[n_in..];
+        out_pos += n_out;
+        n_end += n;
+        match result {
+            // the data is complete, the empty input means EOF
+            ReadRecordResult::InputEmpty => continue,
+            ReadRecordResult::OutputFull => {
+                return Err(ErrorCode::BadBytes("output more than input"));
+            }
+            ReadRecordResult::OutputEndsFull => {
+                let len = field_ends.len() * 2;
+                field_ends.resize(len, 0);
+                field_quoted.resize(len, false);
+            }
+            ReadRecordResult::Record => {
+                let record = &out[..out_pos];
+                let mut start = 0;
+                let mut fields = Vec::with_capacity(n_end);
+                for (end, quoted) in field_ends[..n_end].iter().zip(&field_quoted[..n_end]) {
+                    fields.push((&record[start..*end], *quoted));
+                    start = *end;
+                }
+                out_pos = 0;
+                n_end = 0;
+
+                if has_header && names.is_none() {
+                    names = Some(
+                        fields
+                            .iter()
+                            .map(|(f, _)| String::from_utf8_lossy(f).trim().to_string())
+                            .collect(),
+                    );
+                    continue;
+                }
+                if fields.len() > types.len() {
+                    types.resize(fields.len(), InferredType::Unknown);
+                    nullables.resize(fields.len(), false);
+                }
+                for (c, (field, quoted)) in fields.into_iter().enumerate() {
+                    let value = field.trim();
+                    if is_null(format_settings, c, value, quoted) {
+                        nullables[c] = true;
+                    } else if quoted && value.is_empty() {
+                        types[c] = InferredType::String;
+                    } else {
+                        types[c] = types[c].merge(prober.probe(value, format_settings));
+                    }
+                }
+                num_rows += 1;
+            }
+            ReadRecordResult::End => break,
+        }
+    }
+
+    let names = names.unwrap_or_default();
+    if names.len() > types.len() {
+        // columns without values in the sampled rows
+        types.resize(names.len(), InferredType::Unknown);
+        nullables.resize(names.len(), false);
+    }
+    let fields = types
+        .iter()
+        .zip(nullables.iter())
+        .enumerate()
+        .map(|(c, (t, nullable))| {
+            let name = match names.get(c) {
+                Some(name) if !name.is_empty() => name.clone(),
+                _ => format!("c{}", c + 1),
+            };
+            let data_type = if is_string_numeric(format_settings, &name) {
+                // e.g. zip codes, which would lose their leading zeros as numbers
+                StringType::new_impl()
+            } else {
+                t.data_type()
+            };
+            // a column of nulls only is also nullable
+            if *nullable || *t == InferredType::Unknown {
+                DataField::new(&name, NullableType::new_impl(data_type))
+            } else {
+                DataField::new(&name, data_type)
+            }
+        })
+        .collect();
+    Ok(DataSchemaRefExt::create(fields))
+}
+
+/// Whether the column is listed in `format_settings.preserve_string_numerics`.
+pub(crate) fn is_string_numeric(format_settings: &FormatSettings, name: &str) -> bool {
+    format_settings
+        .preserve_string_numerics
+        .iter()
+        .any(|n| n.eq_ignore_ascii_case(name))
+}
+
+fn is_null(format_settings: &FormatSettings, column: usize, value: &[u8], quoted: bool) -> bool {
+    if !quoted && value.is_empty() {
+        return true;
+    }
+    if value == format_settings.null_bytes || (!quoted && is_null_value(format_settings, value)) {
+        return true;
+    }
+    match format_settings.column_null_bytes.get(&column) {
+        Some(null_values) => null_values.iter().any(|v| v == value),
+        None => false,
+    }
+}
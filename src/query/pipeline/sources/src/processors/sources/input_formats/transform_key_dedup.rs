@@ -0,0 +1,102 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use common_datablocks::DataBlock;
+use common_exception::Result;
+use common_pipeline_core::processors::port::InputPort;
+use common_pipeline_core::processors::port::OutputPort;
+use common_pipeline_core::processors::processor::Event;
+use common_pipeline_core::processors::processor::ProcessorPtr;
+use common_pipeline_core::processors::Processor;
+
+use crate::processors::sources::input_formats::input_context::InputContext;
+
+/// Outputs the blocks held by `InputContext::key_dedup` once all the records are read,
+/// without the rows replaced by a better record of the same key.
+pub struct KeyDedupTransform {
+    ctx: Arc<InputContext>,
+    input: Arc<InputPort>,
+    output: Arc<OutputPort>,
+    output_buffer: VecDeque<DataBlock>,
+    taken: bool,
+}
+
+impl KeyDedupTransform {
+    pub(crate) fn create(
+        ctx: Arc<InputContext>,
+        input: Arc<InputPort>,
+        output: Arc<OutputPort>,
+    ) -> Result<ProcessorPtr> {
+        Ok(ProcessorPtr::create(Box::new(Self {
+            ctx,
+            input,
+            output,
+            output_buffer: Default::default(),
+            taken: false,
+        })))
+    }
+}
+
+impl Processor for KeyDedupTransform {
+    fn name(&self) -> String {
+        "KeyDedupTransform".to_string()
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn event(&mut self) -> Result<Event> {
+        if self.output.is_finished() {
+            self.input.finish();
+            return Ok(Event::Finished);
+        }
+        if !self.output.can_push() {
+            self.input.set_not_need_data();
+            return Ok(Event::NeedConsume);
+        }
+        if let Some(data_block) = self.output_buffer.pop_front() {
+            self.output.push_data(Ok(data_block));
+            return Ok(Event::NeedConsume);
+        }
+        if self.input.has_data() {
+            // the blocks not held, e.g. of the rows without positions, are passed through
+            self.output.push_data(self.input.pull_data().unwrap());
+            return Ok(Event::NeedConsume);
+        }
+        if self.input.is_finished() {
+            if self.taken {
+                self.output.finish();
+                Ok(Event::Finished)
+            } else {
+                Ok(Event::Sync)
+            }
+        } else {
+            self.input.set_need_data();
+            Ok(Event::NeedData)
+        }
+    }
+
+    fn process(&mut self) -> Result<()> {
+        if let Some(dedup) = &self.ctx.key_dedup {
+            self.output_buffer.extend(dedup.take_blocks()?);
+        }
+        self.taken = true;
+        Ok(())
+    }
+}
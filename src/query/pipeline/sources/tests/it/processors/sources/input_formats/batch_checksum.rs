@@ -0,0 +1,45 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_pipeline_sources::processors::sources::input_formats::RowBatch;
+
+fn row_batch(data: &[u8]) -> RowBatch {
+    RowBatch {
+        data: data.to_vec(),
+        row_ends: vec![data.len()],
+        path: "a.csv".to_string(),
+        batch_id: 3,
+        offset: 10,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_batch_checksum() {
+    // not verified without a checksum taken
+    let mut batch = row_batch(b"1,a\n");
+    assert!(batch.check_checksum().is_ok());
+    batch.data[0] = b'2';
+    assert!(batch.check_checksum().is_ok());
+
+    let mut batch = row_batch(b"1,a\n");
+    batch.checksum = Some(batch.data_checksum());
+    assert!(batch.check_checksum().is_ok());
+
+    batch.data[0] = b'2';
+    assert_eq!(
+        batch.check_checksum().unwrap_err().message(),
+        "checksum mismatch of row batch 3 of a.csv at offset 10, the data is changed after aligned"
+    );
+}
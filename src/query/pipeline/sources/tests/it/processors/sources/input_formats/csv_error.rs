@@ -0,0 +1,40 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::ErrorCode;
+use common_pipeline_sources::processors::sources::input_formats::CsvErrorKind;
+use common_pipeline_sources::processors::sources::input_formats::CsvParseError;
+
+#[test]
+fn test_csv_error_detail() {
+    let e = CsvParseError {
+        path: "a.csv".to_string(),
+        row: 1,
+        column: Some(0),
+        raw_field: Some(b"x".to_vec()),
+        record_preview: None,
+        message: "fail to decode column 0 'a' (int32)".to_string(),
+        kind: CsvErrorKind::Parse,
+    };
+    let err = ErrorCode::from(e.clone());
+    assert_eq!(err.code(), ErrorCode::bad_bytes_code());
+    assert_eq!(
+        err.message(),
+        "fail to parse CSV a.csv:2 fail to decode column 0 'a' (int32) "
+    );
+    // the fields are returned with the error, not only in the message
+    assert_eq!(err.detail::<CsvParseError>(), Some(&e));
+    let err = err.add_message_back("(abort after skipping 0 malformed rows)");
+    assert_eq!(err.detail::<CsvParseError>().map(|d| d.row), Some(1));
+}
@@ -0,0 +1,87 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_exception::Result;
+use common_pipeline_sources::processors::sources::input_formats::Decompressor;
+use opendal::io_util::CompressAlgorithm;
+
+// gzip of "a,1\n" and gzip of "b,2\n", concatenated
+const TWO_MEMBERS: &[u8] = &[
+    0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x4b, 0xd4, 0x31, 0xe4, 0x02, 0x00,
+    0x52, 0x1e, 0x01, 0xb5, 0x04, 0x00, 0x00, 0x00, 0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x02, 0x03, 0x4b, 0xd2, 0x31, 0xe2, 0x02, 0x00, 0x7f, 0xe2, 0x99, 0x8c, 0x04, 0x00, 0x00, 0x00,
+];
+
+// lz4 of "a,1\n" and lz4 of "b,2\n", concatenated, as `lz4 -c` writes
+const TWO_FRAMES: &[u8] = &[
+    0x04, 0x22, 0x4d, 0x18, 0x64, 0x40, 0xa7, 0x04, 0x00, 0x00, 0x80, 0x61, 0x2c, 0x31, 0x0a, 0x00,
+    0x00, 0x00, 0x00, 0x52, 0x49, 0x83, 0x1d, 0x04, 0x22, 0x4d, 0x18, 0x64, 0x40, 0xa7, 0x04, 0x00,
+    0x00, 0x80, 0x62, 0x2c, 0x32, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x14, 0xe6, 0xea, 0x5e,
+];
+
+#[test]
+fn test_decompress_multi_member_gzip() -> Result<()> {
+    // in one read, and in reads splitting the members at every byte
+    for read_size in [TWO_MEMBERS.len(), 1, 7] {
+        let mut decoder = Decompressor::new_opendal(CompressAlgorithm::Gzip);
+        let mut output = vec![];
+        for data in TWO_MEMBERS.chunks(read_size) {
+            decoder.decompress(data, 3, |chunk| {
+                assert!(chunk.len() <= 3);
+                output.extend_from_slice(chunk);
+                Ok(())
+            })?;
+        }
+        assert!(decoder.is_done());
+        assert_eq!(output, b"a,1\nb,2\n");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_decompress_multi_frame_lz4() -> Result<()> {
+    for read_size in [TWO_FRAMES.len(), 1, 7] {
+        let mut decoder = Decompressor::new_lz4()?;
+        let mut output = vec![];
+        for data in TWO_FRAMES.chunks(read_size) {
+            decoder.decompress(data, 3, |chunk| {
+                assert!(chunk.len() <= 3);
+                output.extend_from_slice(chunk);
+                Ok(())
+            })?;
+        }
+        assert!(decoder.is_done());
+        assert_eq!(output, b"a,1\nb,2\n");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_decompress_truncated() -> Result<()> {
+    // the first member without the size in the trailer
+    let mut decoder = Decompressor::new_opendal(CompressAlgorithm::Gzip);
+    decoder.decompress(&TWO_MEMBERS[..20], 3, |_| Ok(()))?;
+    assert!(!decoder.is_done());
+
+    // the first frame and the header of the second one
+    let mut decoder = Decompressor::new_lz4()?;
+    let mut output = vec![];
+    decoder.decompress(&TWO_FRAMES[..30], 3, |chunk| {
+        output.extend_from_slice(chunk);
+        Ok(())
+    })?;
+    assert!(!decoder.is_done());
+    assert_eq!(output, b"a,1\n");
+    Ok(())
+}
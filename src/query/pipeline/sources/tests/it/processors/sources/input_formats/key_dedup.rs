@@ -0,0 +1,88 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_datablocks::DataBlock;
+use common_datavalues::prelude::*;
+use common_exception::Result;
+use common_pipeline_sources::processors::sources::input_formats::DedupMode;
+use common_pipeline_sources::processors::sources::input_formats::KeyDedup;
+
+#[test]
+fn test_key_dedup_keep_first() {
+    let dedup = KeyDedup::create(0, DedupMode::KeepFirst, 10);
+    assert!(!dedup.has_better(b"1", (0, 20)));
+    // the key of a malformed record is not taken, so the later records of it are loaded
+    assert!(dedup.insert(b"1", (0, 20)));
+    assert!(dedup.has_better(b"1", (0, 30)));
+    assert!(dedup.has_better(b"1", (1, 0)));
+    assert!(!dedup.has_better(b"1", (0, 10)));
+    // read by another thread in parallel, the earlier record replaces the one kept
+    assert!(dedup.insert(b"1", (0, 10)));
+    assert!(!dedup.insert(b"1", (0, 20)));
+    assert!(dedup.insert(b"2", (0, 30)));
+    assert!(!dedup.is_full());
+}
+
+#[test]
+fn test_key_dedup_keep_last() {
+    let dedup = KeyDedup::create(0, DedupMode::KeepLast, 10);
+    assert!(dedup.insert(b"1", (0, 20)));
+    assert!(!dedup.has_better(b"1", (0, 30)));
+    assert!(dedup.has_better(b"1", (0, 10)));
+    assert!(dedup.insert(b"1", (1, 0)));
+    assert!(!dedup.insert(b"1", (0, 30)));
+}
+
+#[test]
+fn test_key_dedup_max_keys() {
+    let dedup = KeyDedup::create(0, DedupMode::KeepFirst, 2);
+    assert!(dedup.insert(b"1", (0, 0)));
+    assert!(dedup.insert(b"2", (0, 10)));
+    assert!(!dedup.is_full());
+
+    // beyond max_keys, the new keys are loaded without being taken
+    assert!(dedup.insert(b"3", (0, 20)));
+    assert!(dedup.is_full());
+    assert!(!dedup.has_better(b"3", (0, 30)));
+    assert!(dedup.insert(b"3", (0, 30)));
+    // the keys taken are still deduplicated
+    assert!(!dedup.insert(b"1", (0, 40)));
+}
+
+#[test]
+fn test_key_dedup_take_blocks() -> Result<()> {
+    let dedup = KeyDedup::create(0, DedupMode::KeepLast, 10);
+    let schema = DataSchemaRefExt::create(vec![DataField::new("a", i64::to_data_type())]);
+    // the blocks of two threads, the key of the row at (0, 0) is loaded again at (1, 0)
+    for (values, positions) in [
+        (vec![1i64, 2], vec![(0, 0), (0, 10)]),
+        (vec![1i64, 3], vec![(1, 0), (1, 10)]),
+    ] {
+        for (v, p) in values.iter().zip(positions.iter()) {
+            assert!(dedup.insert(v.to_string().as_bytes(), *p));
+        }
+        let block = DataBlock::create(schema.clone(), vec![Series::from_data(values)]);
+        dedup.hold_block(block, positions);
+    }
+
+    let blocks = dedup.take_blocks()?;
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].num_rows(), 1);
+    assert_eq!(blocks[0].column(0).get_i64(0)?, 2);
+    assert_eq!(blocks[1].num_rows(), 2);
+    assert_eq!(blocks[1].column(0).get_i64(0)?, 1);
+    // taken only once
+    assert!(dedup.take_blocks()?.is_empty());
+    Ok(())
+}
@@ -12,4 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod batch_checksum;
+mod csv_error;
+mod decompressor;
+mod key_dedup;
+mod record_preview;
+mod schema_inference;
 mod split;
+mod tail_rows;
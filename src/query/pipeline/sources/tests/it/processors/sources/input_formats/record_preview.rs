@@ -0,0 +1,69 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_pipeline_sources::processors::sources::input_formats::record_preview;
+
+#[test]
+fn test_record_preview() {
+    // the record "a,bb,ccc" read as the fields "a", "bb" and "ccc"
+    let data = b"abbccc";
+    let ends = [1, 3, 6];
+    let cases = [
+        (64, r#"["a", "bb", "ccc"]"#),
+        (6, r#"["a", "bb", "ccc"]"#),
+        (5, r#"["a", "bb", "cc"...]"#),
+        (3, r#"["a", "bb", ...]"#),
+        (2, r#"["a", "b"...]"#),
+        (1, r#"["a", ...]"#),
+    ];
+    for (max_bytes, expect) in cases {
+        let preview = record_preview(&[], data, &ends, max_bytes);
+        assert_eq!(preview.as_deref(), Some(expect), "max_bytes {}", max_bytes);
+    }
+
+    // the record starts in the remain of the last read
+    let preview = record_preview(b"ab", b"bccc", &ends, 4);
+    assert_eq!(preview.as_deref(), Some(r#"["a", "bb", "c"...]"#));
+}
+
+#[test]
+fn test_record_preview_disabled() {
+    assert_eq!(record_preview(&[], b"abbccc", &[1, 3, 6], 0), None);
+    assert_eq!(record_preview(&[], b"", &[], 0), None);
+}
+
+#[test]
+fn test_record_preview_utf8() {
+    // "é" is 2 bytes, 0xC3 0xA9, the cut after 0xC3 ends the field before it
+    let data = "a\u{e9}b".as_bytes();
+    let ends = [1, 4];
+    assert_eq!(
+        record_preview(&[], data, &ends, 2).as_deref(),
+        Some(r#"["a", ...]"#)
+    );
+    assert_eq!(
+        record_preview(&[], data, &ends, 1).as_deref(),
+        Some(r#"["a", ...]"#)
+    );
+
+    // a 3 bytes character, cut after its first and second byte
+    let data = "ab\u{4e2d}".as_bytes();
+    let ends = [2, 5];
+    for max_bytes in [3, 4] {
+        let preview = record_preview(&[], data, &ends, max_bytes);
+        assert_eq!(preview.as_deref(), Some(r#"["ab", ...]"#));
+    }
+    let preview = record_preview(&[], "\u{e9}\u{e9}".as_bytes(), &[4], 3);
+    assert!(preview.unwrap().ends_with("\"...]"));
+}
@@ -0,0 +1,161 @@
+//  Copyright 2022 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use common_datavalues::DataType;
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+use common_pipeline_sources::processors::sources::input_formats::infer_csv_schema;
+use common_pipeline_sources::processors::sources::input_formats::RecordDelimiter;
+
+fn field_types(data: &[u8], has_header: bool, max_rows: usize) -> Result<Vec<(String, String)>> {
+    let format_settings = FormatSettings {
+        field_delimiter: vec![b','],
+        quote_char: Some(b'"'),
+        null_bytes: vec![b'\\', b'N'],
+        ..Default::default()
+    };
+    let schema = infer_csv_schema(
+        data,
+        &format_settings,
+        &RecordDelimiter::Crlf,
+        has_header,
+        max_rows,
+    )?;
+    Ok(schema
+        .fields()
+        .iter()
+        .map(|f| (f.name().to_string(), f.data_type().name()))
+        .collect())
+}
+
+#[test]
+fn test_infer_csv_schema() -> Result<()> {
+    let data = b"id,name,score,day,ts\n\
+        1,a,1.5,2022-01-01,2022-01-01 10:00:00\n\
+        2,,2,2022-01-02,2022-01-02\n\
+        3,\"\",\\N,2022-01-03,2022-01-03 00:00:00\n\
+        x,y,z,w,v\n";
+    assert_eq!(field_types(data, true, 3)?, vec![
+        ("id".to_string(), "Int64".to_string()),
+        ("name".to_string(), "Nullable(String)".to_string()),
+        ("score".to_string(), "Nullable(Float64)".to_string()),
+        ("day".to_string(), "Date".to_string()),
+        ("ts".to_string(), "Timestamp".to_string()),
+    ]);
+
+    // the 4th row widens all columns to string
+    let types = field_types(data, true, 10)?;
+    assert_eq!(types[0].1, "String");
+    assert_eq!(types[3].1, "String");
+
+    let types = field_types(b"1,\n2,\n", false, 10)?;
+    assert_eq!(types, vec![
+        ("c1".to_string(), "Int64".to_string()),
+        ("c2".to_string(), "Nullable(String)".to_string()),
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_infer_csv_schema_leading_zeros() -> Result<()> {
+    let data = b"02134,0,0.5\n10001,-07,1\n";
+    for (preserve_leading_zeros, expected) in [
+        (false, ["Int64", "Int64", "Float64"]),
+        (true, ["String", "String", "Float64"]),
+    ] {
+        let format_settings = FormatSettings {
+            preserve_leading_zeros,
+            ..Default::default()
+        };
+        let schema = infer_csv_schema(data, &format_settings, &RecordDelimiter::Crlf, false, 10)?;
+        let types: Vec<String> = schema
+            .fields()
+            .iter()
+            .map(|f| f.data_type().name())
+            .collect();
+        assert_eq!(types, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_infer_csv_schema_string_numerics() -> Result<()> {
+    let format_settings = FormatSettings {
+        preserve_string_numerics: vec!["zip".to_string(), "C3".to_string()],
+        ..Default::default()
+    };
+    let data = b"id,zip,c3\n1,02134,7\n2,10001,\n";
+    let schema = infer_csv_schema(data, &format_settings, &RecordDelimiter::Crlf, true, 10)?;
+    let types: Vec<String> = schema
+        .fields()
+        .iter()
+        .map(|f| f.data_type().name())
+        .collect();
+    assert_eq!(types, ["Int64", "String", "Nullable(String)"]);
+    Ok(())
+}
+
+#[test]
+fn test_infer_csv_schema_null_values() -> Result<()> {
+    // a quoted null value is a string
+    let data = b"1,NULL\nNull,\"null\"\n";
+    for (null_values, expected) in [
+        (vec![], ["String", "Nullable(String)"]),
+        (vec![b"null".to_vec()], [
+            "Nullable(Int64)",
+            "Nullable(String)",
+        ]),
+    ] {
+        let format_settings = FormatSettings {
+            quote_char: Some(b'"'),
+            null_values,
+            ..Default::default()
+        };
+        let schema = infer_csv_schema(data, &format_settings, &RecordDelimiter::Crlf, false, 10)?;
+        let types: Vec<String> = schema
+            .fields()
+            .iter()
+            .map(|f| f.data_type().name())
+            .collect();
+        assert_eq!(types, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_infer_csv_schema_quote_char() -> Result<()> {
+    for quote in [b'\'', b'`'] {
+        let format_settings = FormatSettings {
+            field_delimiter: vec![b';'],
+            quote_char: Some(quote),
+            ..Default::default()
+        };
+        // the quoted fields contain the field delimiter, the record delimiter and doubled quotes
+        let data = "qa;bq;1\nqc\ndq;2\nqeqqfq;3\n".replace('q', &(quote as char).to_string());
+        let schema = infer_csv_schema(
+            data.as_bytes(),
+            &format_settings,
+            &RecordDelimiter::Crlf,
+            false,
+            10,
+        )?;
+        let types: Vec<String> = schema
+            .fields()
+            .iter()
+            .map(|f| f.data_type().name())
+            .collect();
+        assert_eq!(types, vec!["String".to_string(), "Int64".to_string()]);
+    }
+    Ok(())
+}
@@ -12,7 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_exception::Result;
+use common_io::prelude::FormatSettings;
+use common_pipeline_sources::processors::sources::input_formats::detect_field_delimiter;
+use common_pipeline_sources::processors::sources::input_formats::find_csv_record_start;
 use common_pipeline_sources::processors::sources::input_formats::split_by_size;
+use common_pipeline_sources::processors::sources::input_formats::RecordDelimiter;
 
 #[test]
 fn test_split_by_size() {
@@ -20,3 +25,89 @@ fn test_split_by_size() {
     assert_eq!(split_by_size(9, 3), vec![(0, 3), (3, 3), (6, 3)]);
     assert_eq!(split_by_size(8, 3), vec![(0, 3), (3, 3), (6, 2)]);
 }
+
+#[test]
+fn test_find_csv_record_start() -> Result<()> {
+    let format_settings = FormatSettings {
+        field_delimiter: vec![b','],
+        quote_char: Some(b'"'),
+        ..Default::default()
+    };
+    let find = |data: &str, offset: usize| {
+        find_csv_record_start(
+            data.as_bytes(),
+            offset,
+            &format_settings,
+            &RecordDelimiter::Crlf,
+        )
+    };
+
+    let data = "a,b\nc,d\n";
+    assert_eq!(find(data, 0)?, Some(0));
+    assert_eq!(find(data, 1)?, Some(4));
+    assert_eq!(find(data, 5)?, Some(8));
+    assert_eq!(find(data, 8)?, None);
+
+    // in a quoted field, told by the closing quote
+    assert_eq!(find("1,\"x\ny\nz\",2\n3,4\n", 4)?, Some(12));
+    // before a quoted field, told by the opening quote
+    assert_eq!(find("1,2\n3,\"a\nb\",4\n5\n", 5)?, Some(14));
+    Ok(())
+}
+
+#[test]
+fn test_detect_record_delimiter() {
+    let detect = |data: &str, is_end: bool| {
+        RecordDelimiter::detect(data.as_bytes(), Some(b'"'), None, is_end)
+    };
+    assert!(matches!(
+        detect("a,b\nc,d", false),
+        Some(RecordDelimiter::Any(b'\n'))
+    ));
+    assert!(matches!(
+        detect("a,b\r\nc,d", false),
+        Some(RecordDelimiter::Crlf)
+    ));
+    assert!(matches!(
+        detect("a,b\rc,d", false),
+        Some(RecordDelimiter::Any(b'\r'))
+    ));
+    // the delimiters in quoted fields are data
+    assert!(matches!(
+        detect("\"a\r\nb\",c\rd", false),
+        Some(RecordDelimiter::Any(b'\r'))
+    ));
+    // `\r` at the end may be followed by `\n`
+    assert!(detect("a,b\r", false).is_none());
+    assert!(matches!(
+        detect("a,b\r", true),
+        Some(RecordDelimiter::Any(b'\r'))
+    ));
+    assert!(detect("a,b", true).is_none());
+}
+
+#[test]
+fn test_detect_field_delimiter() {
+    let detect = |data: &str, num_fields: Option<usize>, is_end: bool| {
+        detect_field_delimiter(
+            data.as_bytes(),
+            Some(b'"'),
+            None,
+            b'\n',
+            num_fields,
+            Some(b','),
+            is_end,
+        )
+    };
+    assert_eq!(detect("a;b;c\n1,5;2;3", None, false), Some(b';'));
+    assert_eq!(detect("a\tb|c\n", None, false), Some(b'\t'));
+    // the one matching the columns wins over the most frequent one
+    assert_eq!(detect("a|b;c;d;e\n", Some(2), false), Some(b'|'));
+    // the delimiters in quoted fields are data
+    assert_eq!(detect("\"a;b;c\"|d\n", None, false), Some(b'|'));
+    // the first record is not complete yet
+    assert_eq!(detect("a;b", None, false), None);
+    assert_eq!(detect("a;b", None, true), Some(b';'));
+    // `,` is the decimal separator
+    assert_eq!(detect("1,5\n", None, true), Some(b';'));
+}
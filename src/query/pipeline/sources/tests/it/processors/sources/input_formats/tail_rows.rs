@@ -0,0 +1,135 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_pipeline_sources::processors::sources::input_formats::RowBatch;
+use common_pipeline_sources::processors::sources::input_formats::TailRows;
+
+/// A batch of single field rows at `offset` of the file, JSON array has no row offsets.
+fn row_batch(rows: &[&str], offset: usize, start_row: usize, is_json_array: bool) -> RowBatch {
+    let mut batch = RowBatch {
+        offset,
+        start_row: Some(start_row),
+        ..Default::default()
+    };
+    for row in rows {
+        if !is_json_array {
+            batch.row_offsets.push(offset + batch.data.len());
+            batch.field_ends.push(row.len());
+            batch.field_quoted.push(false);
+        }
+        batch.data.extend_from_slice(row.as_bytes());
+        batch.data.push(b'\n');
+        batch.row_ends.push(batch.data.len());
+    }
+    batch
+}
+
+fn rows(batch: &RowBatch) -> Vec<String> {
+    let mut rows = vec![];
+    let mut start = 0;
+    for end in &batch.row_ends {
+        rows.push(String::from_utf8_lossy(&batch.data[start..*end - 1]).to_string());
+        start = *end;
+    }
+    rows
+}
+
+fn rows_of(batches: &[RowBatch]) -> Vec<String> {
+    batches.iter().flat_map(rows).collect()
+}
+
+#[test]
+fn test_row_batch_take_rows() {
+    let mut batch = row_batch(&["a", "bb", "c"], 10, 5, false);
+    let head = batch.take_rows(2);
+    assert_eq!(rows(&head), vec!["a", "bb"]);
+    assert_eq!(head.row_offsets, vec![10, 12]);
+    assert_eq!(head.field_ends, vec![1, 2]);
+    assert_eq!((head.offset, head.start_row), (10, Some(5)));
+
+    assert_eq!(rows(&batch), vec!["c"]);
+    assert_eq!(batch.row_ends, vec![2]);
+    assert_eq!(batch.row_offsets, vec![15]);
+    assert_eq!(batch.field_ends, vec![1]);
+    assert_eq!(batch.field_quoted, vec![false]);
+    assert_eq!((batch.offset, batch.start_row), (15, Some(7)));
+
+    // taken back by append
+    let mut head = head;
+    head.append(batch);
+    assert_eq!(rows(&head), vec!["a", "bb", "c"]);
+    assert_eq!(head.row_offsets, vec![10, 12, 15]);
+
+    let all = head.take_rows(3);
+    assert_eq!(all.row_ends.len(), 3);
+    assert!(head.row_ends.is_empty());
+}
+
+#[test]
+fn test_tail_rows_across_batches() {
+    let mut tail = TailRows::create(3);
+    assert!(
+        tail.push(vec![row_batch(&["1", "2"], 0, 0, false)])
+            .is_empty()
+    );
+    // the rows of a read may be in several batches
+    let output = tail.push(vec![
+        row_batch(&["3"], 4, 2, false),
+        row_batch(&["4", "5"], 6, 3, false),
+    ]);
+    assert_eq!(rows_of(&output), vec!["1", "2"]);
+    assert_eq!(output[0].start_row, Some(0));
+    assert!(tail.push(vec![]).is_empty());
+
+    let output = tail.push(vec![row_batch(&["6"], 10, 5, false)]);
+    assert_eq!(rows_of(&output), vec!["3"]);
+    assert_eq!(output[0].row_offsets, vec![4]);
+    assert_eq!(output[0].start_row, Some(2));
+
+    let footer = tail.take().unwrap();
+    assert_eq!(rows(&footer), vec!["4", "5", "6"]);
+    assert_eq!(footer.row_offsets, vec![6, 8, 10]);
+    assert_eq!(footer.start_row, Some(3));
+    assert!(tail.take().is_none());
+}
+
+#[test]
+fn test_tail_rows_json_array() {
+    let mut tail = TailRows::create(1);
+    let output = tail.push(vec![row_batch(&["{\"a\":1}", "{\"a\":2}"], 0, 0, true)]);
+    assert_eq!(rows_of(&output), vec!["{\"a\":1}"]);
+    assert!(output[0].row_offsets.is_empty());
+    let footer = tail.take().unwrap();
+    assert_eq!(rows(&footer), vec!["{\"a\":2}"]);
+    assert_eq!(footer.start_row, Some(1));
+}
+
+#[test]
+fn test_tail_rows_short_file() {
+    // nothing is held back
+    let mut tail = TailRows::create(0);
+    assert_eq!(
+        rows_of(&tail.push(vec![row_batch(&["1"], 0, 0, false)])),
+        vec!["1"]
+    );
+    assert!(tail.take().is_none());
+
+    // all rows are footer
+    let mut tail = TailRows::create(5);
+    assert!(
+        tail.push(vec![row_batch(&["1", "2"], 0, 0, false)])
+            .is_empty()
+    );
+    assert_eq!(rows_of(&[tail.take().unwrap()]), vec!["1", "2"]);
+}
@@ -24,6 +24,7 @@ use common_exception::ErrorCode;
 use common_exception::Result;
 use common_io::prelude::parse_escape_string;
 use common_pipeline_sources::processors::sources::input_formats::InputContext;
+use common_pipeline_sources::processors::sources::input_formats::LoadError;
 use common_pipeline_sources::processors::sources::input_formats::StreamingReadBatch;
 use futures::StreamExt;
 use poem::error::BadRequest;
@@ -32,6 +33,7 @@ use poem::error::Result as PoemResult;
 use poem::http::StatusCode;
 use poem::web::Json;
 use poem::web::Multipart;
+use poem::IntoResponse;
 use poem::Request;
 use serde::Deserialize;
 use serde::Serialize;
@@ -53,6 +55,30 @@ pub struct LoadResponse {
     pub stats: ProgressValues,
     pub error: Option<String>,
     pub files: Vec<String>,
+    // the malformed rows skipped with the setting input_collect_errors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<LoadErrorRow>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LoadErrorRow {
+    pub file: String,
+    pub row: Option<usize>,
+    pub column: Option<String>,
+    pub message: String,
+    pub raw: String,
+}
+
+impl From<LoadError> for LoadErrorRow {
+    fn from(e: LoadError) -> Self {
+        LoadErrorRow {
+            file: e.file,
+            row: e.row,
+            column: e.column,
+            message: e.message,
+            raw: String::from_utf8_lossy(&e.raw).into_owned(),
+        }
+    }
 }
 
 #[allow(clippy::manual_async_fn)]
@@ -154,11 +180,34 @@ pub async fn streaming_load(
                         id: uuid::Uuid::new_v4().to_string(),
                         stats: context.get_scan_progress_value(),
                         files,
+                        errors: input_context
+                            .take_load_errors()
+                            .into_iter()
+                            .map(LoadErrorRow::from)
+                            .collect(),
                     })),
-                    Ok(Err(cause)) => Err(poem::Error::from_string(
-                        format!("execute fail: {}", cause.message()),
-                        StatusCode::BAD_REQUEST,
-                    )),
+                    Ok(Err(cause)) => match input_context.load_error_of(&cause) {
+                        // report where the load stopped in the same form as the collected errors
+                        Some(error) => {
+                            let response = Json(LoadResponse {
+                                error: Some(format!("execute fail: {}", cause.message())),
+                                state: "FAILURE".to_string(),
+                                id: uuid::Uuid::new_v4().to_string(),
+                                stats: context.get_scan_progress_value(),
+                                files,
+                                errors: vec![LoadErrorRow::from(error)],
+                            });
+                            Err(poem::Error::from_response(
+                                response
+                                    .with_status(StatusCode::BAD_REQUEST)
+                                    .into_response(),
+                            ))
+                        }
+                        None => Err(poem::Error::from_string(
+                            format!("execute fail: {}", cause.message()),
+                            StatusCode::BAD_REQUEST,
+                        )),
+                    },
                     Err(_) => Err(poem::Error::from_string(
                         "Maybe panic.",
                         StatusCode::INTERNAL_SERVER_ERROR,
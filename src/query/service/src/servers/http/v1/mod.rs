@@ -28,6 +28,7 @@ pub use http_query_handlers::QueryResponse;
 pub use http_query_handlers::QueryStats;
 pub(crate) use json_block::JsonBlock;
 pub use load::streaming_load;
+pub use load::LoadErrorRow;
 pub use load::LoadResponse;
 pub use query::ExecuteStateKind;
 pub use query::HttpQueryContext;
@@ -137,9 +137,9 @@ impl Session {
     pub fn get_format_settings(&self) -> Result<FormatSettings> {
         let settings = &self.session_ctx.get_settings();
         let quote_char = settings.get_format_quote_char()?.into_bytes();
-        if quote_char.len() != 1 {
+        if quote_char.len() > 1 {
             return Err(ErrorCode::InvalidArgument(
-                "quote_char can only contain one char",
+                "quote_char can only be empty or contain one char",
             ));
         }
 
@@ -147,7 +147,7 @@ impl Session {
             record_delimiter: settings.get_format_record_delimiter()?.into_bytes(),
             field_delimiter: settings.get_format_field_delimiter()?.into_bytes(),
             empty_as_default: settings.get_format_empty_as_default()? > 0,
-            quote_char: quote_char[0],
+            quote_char: quote_char.first().cloned(),
             ..Default::default()
         };
 
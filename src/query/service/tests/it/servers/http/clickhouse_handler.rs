@@ -276,6 +276,43 @@ async fn test_insert_format_ndjson() -> PoemResult<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_insert_format_csv_without_trailing_newline() -> PoemResult<()> {
+    let config = ConfigBuilder::create().build();
+    let _guard = TestGlobalServices::setup(config.clone()).await.unwrap();
+
+    let server = Server::new(config).await.unwrap();
+    {
+        let (status, body) = server
+            .post("create table t1(a int, b string null)", "")
+            .await;
+        assert_ok!(status, body);
+    }
+
+    {
+        // the last record is not terminated
+        let (status, body) = server
+            .post("insert into table t1 format CSV", "0,\"a\"\n1,\"b\"")
+            .await;
+        assert_ok!(status, body);
+    }
+
+    {
+        let (status, body) = server.get(r#"select * from t1 order by a"#).await;
+        assert_ok!(status, body);
+        assert_eq!(&body, "0\ta\n1\tb\n");
+    }
+
+    {
+        let (status, body) = server
+            .post("insert into table t1 format CSV", "2,\"c\"\n3")
+            .await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_error!(body, "expect 2 fields");
+    }
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_settings() -> PoemResult<()> {
     let config = ConfigBuilder::create().build();
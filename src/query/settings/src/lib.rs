@@ -180,83 +180,721 @@ impl Settings {
                 desc: "The size of buffer in bytes for input with format. By default, it is 1MB.",
                 possible_values: None,
             },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "input_max_error_rows",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Max malformed rows to skip for input with format, 0 means abort on error. By default, it is 0.",
+                possible_values: None,
+            },
+            // input_collect_errors
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "input_collect_errors",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Skip malformed rows for input with format without aborting, and return the errors of at most input_max_error_rows of them as rows, 1 for enable. By default, it is 0.",
+                possible_values: None,
+            },
+            // input_parallel_deserialize_rows
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "input_parallel_deserialize_rows",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Min rows deserialized by each thread when a batch of CSV rows is deserialized in parallel, 0 means never, as the batches are already deserialized by max_threads processors. By default, it is 0.",
+                possible_values: None,
+            },
+            // input_validate_only
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "input_validate_only",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Only parse the input to find malformed rows, nothing is loaded, 1 for enable. By default, it is 0.",
+                possible_values: None,
+            },
+            // input_max_record_bytes
+            SettingValue {
+                default_value: UserSettingValue::UInt64(128 * 1024 * 1024),
+                user_setting: UserSetting::create(
+                    "input_max_record_bytes",
+                    UserSettingValue::UInt64(128 * 1024 * 1024),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Max bytes of an incomplete input record, exceeded usually by an unterminated quote, 0 means unlimited. By default, it is 128MB.",
+                possible_values: None,
+            },
+            // input_max_bytes_without_record_delimiter
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "input_max_bytes_without_record_delimiter",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Fail if no record delimiter is found in so many bytes at the start of a CSV file, usually because the record_delimiter is wrong, 0 means unlimited. By default, it is 0.",
+                possible_values: None,
+            },
+            // input_error_preview_bytes
+            SettingValue {
+                default_value: UserSettingValue::UInt64(128),
+                user_setting: UserSetting::create(
+                    "input_error_preview_bytes",
+                    UserSettingValue::UInt64(128),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Show the fields of the record as split in the CSV errors of a wrong field count, cut after so many bytes, 0 to hide them. By default, it is 128.",
+                possible_values: None,
+            },
+            // input_max_total_rows
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "input_max_total_rows",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Max rows read from all the files of a load, counted when split into rows, so the rows skipped later as malformed, filtered or duplicate are counted too. The files are read in order then, so the first rows are read. 0 means unlimited. By default, it is 0.",
+                possible_values: None,
+            },
+            // input_batch_target_rows
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "input_batch_target_rows",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The aligned rows are accumulated across the reads of a file into batches of at least so many rows, 0 means a batch per read. By default, it is 0.",
+                possible_values: None,
+            },
+            // input_batch_target_bytes
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "input_batch_target_bytes",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The aligned rows are accumulated across the reads of a file into batches of at least so many bytes, 0 means a batch per read. By default, it is 0.",
+                possible_values: None,
+            },
+            // input_phase_timing
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "input_phase_timing",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Measure the time spent in splitting the input into rows and in reading the values of text formats, logged at the end of the load, if value != 0. By default, it is 0.",
+                possible_values: None,
+            },
+            // input_batch_checksum
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "input_batch_checksum",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Checksum the data of each batch of rows of text formats when split into rows, and verify it before reading the values, to debug data corruption, if value != 0. By default, it is 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create("input_dedup_key", UserSettingValue::UInt64(0)),
+                level: ScopeLevel::Session,
+                desc: "The position (from 1) of the key field in the records of CSV and TSV, only one record of a key is loaded, see input_dedup_mode, 0 to load all records. By default, it is 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("keep_first".to_owned()),
+                user_setting: UserSetting::create(
+                    "input_dedup_mode",
+                    UserSettingValue::String("keep_first".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Which record of a duplicate key is loaded with input_dedup_key, \"keep_first\" or \"keep_last\" in the order of the files and the offsets in them. By default, it is \"keep_first\".",
+                possible_values: Some(vec!["keep_first", "keep_last"]),
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(10000000),
+                user_setting: UserSetting::create(
+                    "input_dedup_max_keys",
+                    UserSettingValue::UInt64(10000000),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The max number of keys kept in memory for input_dedup_key, the records of more keys are loaded without deduplication, with a warning. By default, it is 10000000.",
+                possible_values: None,
+            },
             // enable_new_processor_framework
             SettingValue {
                 default_value: UserSettingValue::UInt64(1),
                 user_setting: UserSetting::create(
-                    "enable_new_processor_framework",
-                    UserSettingValue::UInt64(1),
+                    "enable_new_processor_framework",
+                    UserSettingValue::UInt64(1),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Enable new processor framework if value != 0, default value: 1.",
+                possible_values: None,
+            },
+            // enable_planner_v2
+            SettingValue {
+                default_value: UserSettingValue::UInt64(1),
+                user_setting: UserSetting::create("enable_planner_v2", UserSettingValue::UInt64(1)),
+                level: ScopeLevel::Session,
+                desc: "Enable planner v2 by setting this variable to 1, default value: 1.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("\n".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_record_delimiter",
+                    UserSettingValue::String("\n".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Format record_delimiter, 'auto' to detect one of \"\\n\", \"\\r\\n\" and \"\\r\" from the head of CSV files, default value: \"\\n\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String(",".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_field_delimiter",
+                    UserSettingValue::String(",".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Format field delimiter, \"auto\" to detect one of ',', ';', tab and '|' from the first record of CSV, default value: \",\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(1),
+                user_setting: UserSetting::create(
+                    "format_empty_as_default",
+                    UserSettingValue::UInt64(1),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Format empty_as_default, default value: 1.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("default".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_empty_field_as",
+                    UserSettingValue::String("default".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "What an unquoted empty field of CSV and TSV is read as, \"default\", \"null\" or \"empty_string\". default value: \"default\".",
+                possible_values: Some(vec!["default", "null", "empty_string"]),
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_skip_header",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The number of rows to skip at the beginning of each file, e.g. a title row, a units row and a header row, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_skip_footer",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The number of rows to skip at the end of each file, e.g. a totals row, these rows are held in memory until the end of the file, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("None".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_compression",
+                    UserSettingValue::String("None".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Format compression, default value: \"None\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("\"".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_quote_char",
+                    UserSettingValue::String("\"".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The quote char for CSV, empty to disable quoting. default value: '\"'.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_quote_chars",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Any of these chars quotes a field of CSV instead of format_quote_char, e.g. '\"\\''. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_escape_char",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The escape char for CSV, empty means no escape char. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("double".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_quote_escape",
+                    UserSettingValue::String("double".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "How quotes are escaped in quoted CSV fields, \"double\" for a doubled quote, \"backslash\" for the escape char (\\ if empty). default value: \"double\".",
+                possible_values: Some(vec!["double", "backslash"]),
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_lenient_quotes",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Tolerate the stray quotes of broken CSV, a quote only opens a field at its start and only closes it before a delimiter, otherwise it is data, if value != 0, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_merge_consecutive_delimiters",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "A run of CSV field delimiters separates two fields instead of making empty fields, and the ones at the start or end of a line are ignored, e.g. for columns aligned by spaces, if value != 0, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("both".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_trim_fields",
+                    UserSettingValue::String("both".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Which whitespace around unquoted CSV string fields is trimmed, \"none\", \"both\", \"leading\" or \"trailing\", fields of other types are always trimmed. default value: \"both\".",
+                possible_values: Some(vec!["none", "both", "leading", "trailing"]),
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_comment_char",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Lines start with the comment char (or chars, e.g. \"--\") are skipped in CSV, default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_comment_requires_line_start",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "A CSV comment must start at the first char of the line, not after spaces or tabs, if value != 0, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("utf-8".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_encoding",
+                    UserSettingValue::String("utf-8".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The encoding of CSV input, e.g. utf-16le, gbk, windows-1252 or latin-1. default value: \"utf-8\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_column_null_values",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Extra null values of columns for CSV, e.g. '0:NULL,0:\\N,2:NA'. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_null_values",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Extra null values of the nullable columns for unquoted CSV fields, separated by ',', e.g. 'null,NA'. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_null_values_case_sensitive",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Whether format_null_values are matched case-sensitively, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_column_defaults",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Values of columns read for empty fields, instead of the default values of the types, e.g. '0:-1,1:UNKNOWN'. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_column_padding",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Padding chars stripped from the CSV fields of columns, <column index>:<pad char>[:left|right|both], e.g. '0:0,2:*:right'. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("none".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_case_transform",
+                    UserSettingValue::String("none".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Transform the values of string columns of CSV to \"upper\" or \"lower\" case, NULLs are kept. default value: \"none\".",
+                possible_values: Some(vec!["none", "upper", "lower"]),
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("raw".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_binary_encoding",
+                    UserSettingValue::String("raw".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "How the values of string columns of CSV are encoded, \"raw\", \"hex\" or \"base64\". default value: \"raw\".",
+                possible_values: Some(vec!["raw", "hex", "base64"]),
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("datetime".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_timestamp_unit",
+                    UserSettingValue::String("datetime".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "How the values of timestamp columns of CSV are written, \"datetime\" strings, or integers since the Unix epoch in \"epoch_s\", \"epoch_ms\" or \"epoch_us\". default value: \"datetime\".",
+                possible_values: Some(vec!["datetime", "epoch_s", "epoch_ms", "epoch_us"]),
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_numeric_group_separator",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The thousands separator stripped from numeric fields of CSV, e.g. ',' for '1,234,567.89', empty means none. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_decimal_separator",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The decimal separator of float fields of CSV, e.g. ',' for '1.234,56' with '.' as the numeric_group_separator, empty means '.'. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_date_format",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The strftime-style format of date input for CSV and TSV, e.g. '%m/%d/%Y', empty for the default parser. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_timestamp_format",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "The strftime-style format of timestamp input for CSV and TSV, e.g. '%m/%d/%Y %H:%M:%S', empty for the default parser. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_true_values",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Extra literals of true for boolean columns of CSV, separated by ',', e.g. 'Y,1'. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_false_values",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Extra literals of false for boolean columns of CSV, separated by ',', e.g. 'N,0'. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_bool_case_insensitive",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Whether format_true_values and format_false_values are matched case-insensitively, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_nan_values",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Extra literals of NaN for float columns of CSV, separated by ',', matched case-insensitively, e.g. 'NaN,1.#QNAN'. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_inf_values",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Extra literals of infinity for float columns of CSV, separated by ',', matched case-insensitively, a leading '-' means negative, e.g. 'Inf,1.#INF'. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_header_mapping",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Map CSV fields to columns by the header if value != 0, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("none".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_header_check",
+                    UserSettingValue::String("none".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Read the first CSV row as the header and compare it with the column names when loading by position, \"error\" or \"warn\" if they differ. default value: \"none\".",
+                possible_values: Some(vec!["none", "error", "warn"]),
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_lenient_header",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Tolerate a CSV header with a different number of fields than the data, e.g. of a merged title cell, only the names present are compared by format_header_check, if value != 0, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(1),
+                user_setting: UserSetting::create(
+                    "format_header_case_sensitive",
+                    UserSettingValue::UInt64(1),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Match the CSV header to columns case sensitively if value != 0, default value: 1.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(1),
+                user_setting: UserSetting::create(
+                    "format_strip_trailing_cr",
+                    UserSettingValue::UInt64(1),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Strip a trailing '\\r' of CSV records when the record delimiter is '\\n' if value != 0, default value: 1.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_single_column",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Load each line of CSV as the value of the only column, without splitting fields, if value != 0, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_allow_extra_columns",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Ignore the trailing fields not in the table for CSV if value != 0, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_allow_missing_columns",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Fill the missing trailing fields with default for CSV if value != 0, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_ignore_trailing_field",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Ignore the data after a trailing CSV field delimiter, instead of an error, if value != 0, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_skip_blank_lines",
+                    UserSettingValue::UInt64(0),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Skip the CSV lines of only whitespace, which are not counted as rows, if value != 0, default value: 0.",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("keep".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_empty_records",
+                    UserSettingValue::String("keep".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "What to do with the CSV records of only empty fields, like ',,,', \"keep\" to read them as default values, or \"skip\" them, which are not counted as rows. default value: \"keep\".",
+                possible_values: Some(vec!["keep", "skip"]),
+            },
+            SettingValue {
+                default_value: UserSettingValue::String("close".to_owned()),
+                user_setting: UserSetting::create(
+                    "format_on_unterminated_quote",
+                    UserSettingValue::String("close".to_owned()),
                 ),
                 level: ScopeLevel::Session,
-                desc: "Enable new processor framework if value != 0, default value: 1.",
-                possible_values: None,
+                desc: "What to do with a quoted CSV field not closed at the end of the file, \"error\" to report the row it starts, or \"close\" to take the end of the file as the closing quote. default value: \"close\".",
+                possible_values: Some(vec!["error", "close"]),
             },
-            // enable_planner_v2
             SettingValue {
-                default_value: UserSettingValue::UInt64(1),
-                user_setting: UserSetting::create("enable_planner_v2", UserSettingValue::UInt64(1)),
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_preserve_leading_zeros",
+                    UserSettingValue::UInt64(0),
+                ),
                 level: ScopeLevel::Session,
-                desc: "Enable planner v2 by setting this variable to 1, default value: 1.",
+                desc: "Infer the CSV columns of numbers with leading zeros, like zip codes, as strings, if value != 0, default value: 0.",
                 possible_values: None,
             },
             SettingValue {
-                default_value: UserSettingValue::String("\n".to_owned()),
+                default_value: UserSettingValue::String("".to_owned()),
                 user_setting: UserSetting::create(
-                    "format_record_delimiter",
-                    UserSettingValue::String("\n".to_owned()),
+                    "format_preserve_string_numerics",
+                    UserSettingValue::String("".to_owned()),
                 ),
                 level: ScopeLevel::Session,
-                desc: "Format record_delimiter, default value: \"\\n\".",
+                desc: "Names of the CSV columns of numeric codes, e.g. 'zip,account', never inferred as numbers nor stripped of their padding. default value: \"\".",
                 possible_values: None,
             },
             SettingValue {
-                default_value: UserSettingValue::String(",".to_owned()),
+                default_value: UserSettingValue::String("error".to_owned()),
                 user_setting: UserSetting::create(
-                    "format_field_delimiter",
-                    UserSettingValue::String(",".to_owned()),
+                    "format_integer_overflow",
+                    UserSettingValue::String("error".to_owned()),
                 ),
                 level: ScopeLevel::Session,
-                desc: "Format field delimiter, default value: \",\".",
-                possible_values: None,
+                desc: "What to do with CSV integers out of the range of the column type, \"error\" or \"saturate\" to the min/max of the type. default value: \"error\".",
+                possible_values: Some(vec!["error", "saturate"]),
             },
             SettingValue {
                 default_value: UserSettingValue::UInt64(1),
                 user_setting: UserSetting::create(
-                    "format_empty_as_default",
+                    "format_lenient_sign",
                     UserSettingValue::UInt64(1),
                 ),
                 level: ScopeLevel::Session,
-                desc: "Format empty_as_default, default value: 1.",
+                desc: "Accept CSV integers with a leading '+' and '-0' (also for unsigned columns), reject them if value = 0, default value: 1.",
                 possible_values: None,
             },
             SettingValue {
                 default_value: UserSettingValue::UInt64(0),
                 user_setting: UserSetting::create(
-                    "format_skip_header",
+                    "format_max_field_bytes",
                     UserSettingValue::UInt64(0),
                 ),
                 level: ScopeLevel::Session,
-                desc: "Whether to skip the input header, default value: 0.",
+                desc: "Max bytes of a CSV field, a larger field is an error instead of being deserialized, 0 means unlimited. default value: 0.",
                 possible_values: None,
             },
             SettingValue {
-                default_value: UserSettingValue::String("None".to_owned()),
+                default_value: UserSettingValue::String("".to_owned()),
                 user_setting: UserSetting::create(
-                    "format_compression",
-                    UserSettingValue::String("None".to_owned()),
+                    "format_reject_control_chars",
+                    UserSettingValue::String("".to_owned()),
                 ),
                 level: ScopeLevel::Session,
-                desc: "Format compression, default value: \"None\".",
+                desc: "Control chars which make a CSV field an error, separated by ',', each is a code like '0' for NUL, or 'all' for all but tab, LF and CR. default value: \"\".",
                 possible_values: None,
             },
             SettingValue {
-                default_value: UserSettingValue::String("\"".to_owned()),
+                default_value: UserSettingValue::String("".to_owned()),
                 user_setting: UserSetting::create(
-                    "format_quote_char",
-                    UserSettingValue::String("\"".to_owned()),
+                    "format_fixed_width_columns",
+                    UserSettingValue::String("".to_owned()),
+                ),
+                level: ScopeLevel::Session,
+                desc: "Byte ranges of the columns of fixed-width files, 0-based 'start:len' or 'start:len:zero' for zero padded fields, e.g. '0:10,10:8:zero'. default value: \"\".",
+                possible_values: None,
+            },
+            SettingValue {
+                default_value: UserSettingValue::UInt64(0),
+                user_setting: UserSetting::create(
+                    "format_allow_short_records",
+                    UserSettingValue::UInt64(0),
                 ),
                 level: ScopeLevel::Session,
-                desc: "The quote char for CSV. default value: '\"'.",
+                desc: "Read the fields beyond the end of short fixed-width records as empty if value != 0, default value: 0.",
                 possible_values: None,
             },
             SettingValue {
@@ -439,6 +1077,82 @@ impl Settings {
         self.try_get_u64(key)
     }
 
+    pub fn get_input_max_error_rows(&self) -> Result<u64> {
+        let key = "input_max_error_rows";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_parallel_deserialize_rows(&self) -> Result<u64> {
+        let key = "input_parallel_deserialize_rows";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_validate_only(&self) -> Result<u64> {
+        let key = "input_validate_only";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_collect_errors(&self) -> Result<u64> {
+        let key = "input_collect_errors";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_max_record_bytes(&self) -> Result<u64> {
+        let key = "input_max_record_bytes";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_max_bytes_without_record_delimiter(&self) -> Result<u64> {
+        let key = "input_max_bytes_without_record_delimiter";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_error_preview_bytes(&self) -> Result<u64> {
+        let key = "input_error_preview_bytes";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_max_total_rows(&self) -> Result<u64> {
+        let key = "input_max_total_rows";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_batch_target_rows(&self) -> Result<u64> {
+        let key = "input_batch_target_rows";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_batch_target_bytes(&self) -> Result<u64> {
+        let key = "input_batch_target_bytes";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_phase_timing(&self) -> Result<u64> {
+        let key = "input_phase_timing";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_batch_checksum(&self) -> Result<u64> {
+        let key = "input_batch_checksum";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_dedup_key(&self) -> Result<u64> {
+        let key = "input_dedup_key";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_input_dedup_mode(&self) -> Result<String> {
+        let key = "input_dedup_mode";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_input_dedup_max_keys(&self) -> Result<u64> {
+        let key = "input_dedup_max_keys";
+        self.try_get_u64(key)
+    }
+
     pub fn get_enable_new_processor_framework(&self) -> Result<u64> {
         let key = "enable_new_processor_framework";
         self.try_get_u64(key)
@@ -467,6 +1181,264 @@ impl Settings {
             .and_then(|v| v.user_setting.value.as_string())
     }
 
+    pub fn get_format_quote_chars(&self) -> Result<String> {
+        let key = "format_quote_chars";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_escape_char(&self) -> Result<String> {
+        let key = "format_escape_char";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_quote_escape(&self) -> Result<String> {
+        let key = "format_quote_escape";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_lenient_quotes(&self) -> Result<u64> {
+        let key = "format_lenient_quotes";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_merge_consecutive_delimiters(&self) -> Result<u64> {
+        let key = "format_merge_consecutive_delimiters";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_trim_fields(&self) -> Result<String> {
+        let key = "format_trim_fields";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_comment_char(&self) -> Result<String> {
+        let key = "format_comment_char";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_comment_requires_line_start(&self) -> Result<u64> {
+        let key = "format_comment_requires_line_start";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_encoding(&self) -> Result<String> {
+        let key = "format_encoding";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_numeric_group_separator(&self) -> Result<String> {
+        let key = "format_numeric_group_separator";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_decimal_separator(&self) -> Result<String> {
+        let key = "format_decimal_separator";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_date_format(&self) -> Result<String> {
+        let key = "format_date_format";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_timestamp_format(&self) -> Result<String> {
+        let key = "format_timestamp_format";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_true_values(&self) -> Result<String> {
+        let key = "format_true_values";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_false_values(&self) -> Result<String> {
+        let key = "format_false_values";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_nan_values(&self) -> Result<String> {
+        let key = "format_nan_values";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_inf_values(&self) -> Result<String> {
+        let key = "format_inf_values";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_bool_case_insensitive(&self) -> Result<u64> {
+        let key = "format_bool_case_insensitive";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_column_null_values(&self) -> Result<String> {
+        let key = "format_column_null_values";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_null_values(&self) -> Result<String> {
+        let key = "format_null_values";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_null_values_case_sensitive(&self) -> Result<u64> {
+        let key = "format_null_values_case_sensitive";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_column_padding(&self) -> Result<String> {
+        let key = "format_column_padding";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_column_defaults(&self) -> Result<String> {
+        let key = "format_column_defaults";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_case_transform(&self) -> Result<String> {
+        let key = "format_case_transform";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_timestamp_unit(&self) -> Result<String> {
+        let key = "format_timestamp_unit";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_binary_encoding(&self) -> Result<String> {
+        let key = "format_binary_encoding";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_header_mapping(&self) -> Result<u64> {
+        let key = "format_header_mapping";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_header_check(&self) -> Result<String> {
+        let key = "format_header_check";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_lenient_header(&self) -> Result<u64> {
+        let key = "format_lenient_header";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_header_case_sensitive(&self) -> Result<u64> {
+        let key = "format_header_case_sensitive";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_strip_trailing_cr(&self) -> Result<u64> {
+        let key = "format_strip_trailing_cr";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_single_column(&self) -> Result<u64> {
+        let key = "format_single_column";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_allow_extra_columns(&self) -> Result<u64> {
+        let key = "format_allow_extra_columns";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_allow_missing_columns(&self) -> Result<u64> {
+        let key = "format_allow_missing_columns";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_ignore_trailing_field(&self) -> Result<u64> {
+        let key = "format_ignore_trailing_field";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_skip_blank_lines(&self) -> Result<u64> {
+        let key = "format_skip_blank_lines";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_empty_records(&self) -> Result<String> {
+        let key = "format_empty_records";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_on_unterminated_quote(&self) -> Result<String> {
+        let key = "format_on_unterminated_quote";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_preserve_leading_zeros(&self) -> Result<u64> {
+        let key = "format_preserve_leading_zeros";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_preserve_string_numerics(&self) -> Result<String> {
+        let key = "format_preserve_string_numerics";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_integer_overflow(&self) -> Result<String> {
+        let key = "format_integer_overflow";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_lenient_sign(&self) -> Result<u64> {
+        let key = "format_lenient_sign";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_max_field_bytes(&self) -> Result<u64> {
+        let key = "format_max_field_bytes";
+        self.try_get_u64(key)
+    }
+
+    pub fn get_format_reject_control_chars(&self) -> Result<String> {
+        let key = "format_reject_control_chars";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_fixed_width_columns(&self) -> Result<String> {
+        let key = "format_fixed_width_columns";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
+    pub fn get_format_allow_short_records(&self) -> Result<u64> {
+        let key = "format_allow_short_records";
+        self.try_get_u64(key)
+    }
+
     pub fn get_format_compression(&self) -> Result<String> {
         let key = "format_compression";
         self.check_and_get_setting_value(key)
@@ -478,11 +1450,22 @@ impl Settings {
         self.try_get_u64(key)
     }
 
+    pub fn get_format_empty_field_as(&self) -> Result<String> {
+        let key = "format_empty_field_as";
+        self.check_and_get_setting_value(key)
+            .and_then(|v| v.user_setting.value.as_string())
+    }
+
     pub fn get_format_skip_header(&self) -> Result<u64> {
         let key = "format_skip_header";
         self.try_get_u64(key)
     }
 
+    pub fn get_format_skip_footer(&self) -> Result<u64> {
+        let key = "format_skip_footer";
+        self.try_get_u64(key)
+    }
+
     pub fn get_timezone(&self) -> Result<String> {
         let key = "timezone";
         self.check_and_get_setting_value(key)
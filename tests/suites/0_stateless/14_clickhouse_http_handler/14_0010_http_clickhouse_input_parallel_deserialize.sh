@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+# the fields are mapped to the columns out of order, rows 5 and 13 are malformed
+echo 'insert into a(c,b,a) format CSV' > /tmp/databend_test_csv_parallel.txt
+for i in $(seq 1 20); do
+	case $i in
+	5) echo "x,\"s$i\",$i" ;;
+	13) echo "1y,\"s$i\",$i" ;;
+	*) echo "$((i * 10)),\"s$i\",$i" ;;
+	esac
+done >> /tmp/databend_test_csv_parallel.txt
+
+# 0: read each batch in the deserializer, 2: read each batch by up to 4 threads
+for rows in 0 2; do
+	echo "---input_parallel_deserialize_rows=$rows"
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string, c int)"
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&max_threads=4&input_parallel_deserialize_rows=$rows&input_max_error_rows=10" --data-binary @/tmp/databend_test_csv_parallel.txt
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+done
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
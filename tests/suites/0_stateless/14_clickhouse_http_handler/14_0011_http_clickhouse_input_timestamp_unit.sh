@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+# rows 3 and 4 are out of range and not an integer, they are skipped
+for unit in epoch_s epoch_ms epoch_us; do
+	if [ $unit = epoch_us ]; then
+		overflow=9223372036854775808
+	else
+		overflow=9223372036854775807
+	fi
+	cat << EOF > /tmp/databend_test_csv_$unit.txt
+insert into a(a,b) format CSV
+1,1
+2,-1
+3,$overflow
+4,1.5
+EOF
+
+	echo "---$unit"
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b timestamp)"
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_timestamp_unit=$unit&input_max_error_rows=10" --data-binary @/tmp/databend_test_csv_$unit.txt
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&timezone=UTC" -d "select * from a order by a"
+done
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_timestamp_unit=epoch_s" --data-binary @/tmp/databend_test_csv_epoch_s.txt | grep -c "epoch seconds out of range"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_timestamp_unit=epoch_us" --data-binary @/tmp/databend_test_csv_epoch_us.txt | grep -c "expect an integer of microseconds since the Unix epoch"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
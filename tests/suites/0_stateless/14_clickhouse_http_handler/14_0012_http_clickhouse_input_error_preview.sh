@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b int)"
+
+# a record of 4 fields, with "中" of 3 bytes in the third one
+cat << EOF > /tmp/databend_test_csv_preview.txt
+insert into a(a,b) format CSV
+1,2,3中,6
+EOF
+
+# the whole record by default
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" --data-binary @/tmp/databend_test_csv_preview.txt | grep -c 'record: \["1", "2", "3.*", "6"\]'
+# cut in the third field
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&input_error_preview_bytes=3" --data-binary @/tmp/databend_test_csv_preview.txt | grep -cF 'record: ["1", "2", "3"...]'
+# the cut inside "中" drops the character
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&input_error_preview_bytes=5" --data-binary @/tmp/databend_test_csv_preview.txt | grep -cF 'record: ["1", "2", "3"...]'
+# 0 hides the record
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&input_error_preview_bytes=0" --data-binary @/tmp/databend_test_csv_preview.txt | grep -c 'record:'
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
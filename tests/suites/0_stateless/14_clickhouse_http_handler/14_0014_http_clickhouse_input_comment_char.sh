@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string)"
+
+# the quoted '#' and the one inside a field are data
+cat << EOF > /tmp/databend_test_csv_comment.txt
+insert into a(a,b) format CSV
+# exported rows
+1,x#y
+  # indented
+2,"#z"
+#3,w
+EOF
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_comment_char=%23" --data-binary @/tmp/databend_test_csv_comment.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+# the comment lines are not counted as rows
+echo "---errors"
+printf 'insert into a(a,b) format CSV\n# c\n3,x\nx,4\n' > /tmp/databend_test_csv_comment_error.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_comment_char=%23" --data-binary @/tmp/databend_test_csv_comment_error.txt | grep -c ":2 fail to decode column 0 'a'"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string)"
+
+# "café" in latin1, "中文" in gbk
+printf 'insert into a(a,b) format CSV\n1,caf\xe9\n' > /tmp/databend_test_csv_latin1.txt
+printf 'insert into a(a,b) format CSV\n2,"\xd6\xd0\xce\xc4"\n' > /tmp/databend_test_csv_gbk.txt
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_encoding=latin1" --data-binary @/tmp/databend_test_csv_latin1.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_encoding=gbk" --data-binary @/tmp/databend_test_csv_gbk.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_encoding=klingon" --data-binary @/tmp/databend_test_csv_latin1.txt | grep -c "unknown encoding klingon"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
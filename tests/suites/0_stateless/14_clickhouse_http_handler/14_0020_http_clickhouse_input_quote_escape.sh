@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string)"
+
+cat << EOF > /tmp/databend_test_csv_quote_escape.txt
+insert into a(a,b) format CSV
+1,"x\"y"
+2,"a,\"b\""
+EOF
+
+# the escape char is \ if not set
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_quote_escape=backslash" --data-binary @/tmp/databend_test_csv_quote_escape.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_quote_escape=backslash&format_escape_char=%22" --data-binary @/tmp/databend_test_csv_quote_escape.txt | grep -c "quote_escape 'backslash' requires an escape_char other than the quote_char"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_quote_escape=double&format_escape_char=%22" --data-binary @/tmp/databend_test_csv_quote_escape.txt | grep -c "escape_char should not be the quote_char"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_quote_escape=backslash&format_quote_char=" --data-binary @/tmp/databend_test_csv_quote_escape.txt | grep -c "quote_escape 'backslash' requires a quote_char"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string)"
+
+cat << EOF > /tmp/databend_test_csv_record_bytes.txt
+insert into a(a,b) format CSV
+1,"x"
+2,"y,y"
+EOF
+
+# the quote of row 2 is never closed, the rest of the file is in its record
+cat << EOF > /tmp/databend_test_csv_unterminated.txt
+insert into a(a,b) format CSV
+1,"x"
+2,"y
+3,zzzzzzzzzzzzzzzz
+4,w
+EOF
+
+# records within the limit
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&input_max_record_bytes=16" --data-binary @/tmp/databend_test_csv_record_bytes.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&input_max_record_bytes=16" --data-binary @/tmp/databend_test_csv_unterminated.txt | grep -c ":2 record exceeds max size 16 bytes, possible unterminated quote"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
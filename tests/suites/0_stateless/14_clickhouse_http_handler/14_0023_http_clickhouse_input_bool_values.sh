@@ -0,0 +1,38 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b boolean)"
+
+# the built-in literals are still accepted
+cat << EOF > /tmp/databend_test_csv_bool.txt
+insert into a(a,b) format CSV
+1,Y
+2,N
+3,yes
+4,no
+5,true
+6,false
+EOF
+
+cat << EOF > /tmp/databend_test_csv_bool_case.txt
+insert into a(a,b) format CSV
+7,YES
+8,No
+EOF
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_true_values=Y,yes&format_false_values=N,no" --data-binary @/tmp/databend_test_csv_bool.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_true_values=Y,yes&format_false_values=N,no&format_bool_case_insensitive=1" --data-binary @/tmp/databend_test_csv_bool_case.txt
+echo "---true"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select a from a where b order by a"
+echo "---false"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select a from a where not b order by a"
+
+# the literals are case sensitive by default
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_true_values=Y,yes&format_false_values=N,no" --data-binary @/tmp/databend_test_csv_bool_case.txt | grep -c "fail to decode column 1 'b'"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
@@ -0,0 +1,27 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b date, c timestamp)"
+
+cat << EOF > /tmp/databend_test_csv_datetime.txt
+insert into a(a,b,c) format CSV
+1,08/04/2023,08/04/2023 01:02
+EOF
+
+printf 'insert into a(a,b,c) format TSV\n2\t31/12/1999\t31/12/1999 23:59\n' > /tmp/databend_test_tsv_datetime.txt
+printf 'insert into a(a,b,c) format CSV\n3,2023-04-08,08/04/2023 01:02\n' > /tmp/databend_test_csv_datetime_error.txt
+
+# %d/%m/%Y and %d/%m/%Y %H:%M
+formats="format_date_format=%25d%2F%25m%2F%25Y&format_timestamp_format=%25d%2F%25m%2F%25Y%20%25H%3A%25M"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$formats" --data-binary @/tmp/databend_test_csv_datetime.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$formats" --data-binary @/tmp/databend_test_tsv_datetime.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&timezone=UTC" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$formats" --data-binary @/tmp/databend_test_csv_datetime_error.txt | grep -c "cannot parse date '2023-04-08' with format '%d/%m/%Y'"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
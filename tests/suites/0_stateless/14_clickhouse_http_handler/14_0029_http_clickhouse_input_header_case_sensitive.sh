@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+cat << EOF > /tmp/databend_test_csv_header_case.txt
+insert into a(a,b) format CSV
+ B , A
+x,1
+y,2
+EOF
+
+cat << EOF > /tmp/databend_test_csv_header_ambiguous.txt
+insert into a(a,b) format CSV
+a,A
+1,2
+EOF
+
+# not matched by default, the columns are read as default
+for sensitive in 1 0; do
+	echo "---format_header_case_sensitive=$sensitive"
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string)"
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_header_mapping=1&format_header_case_sensitive=$sensitive" --data-binary @/tmp/databend_test_csv_header_case.txt
+	curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a, b"
+done
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_header_mapping=1&format_header_case_sensitive=0" --data-binary @/tmp/databend_test_csv_header_ambiguous.txt | grep -c "header columns 'a' and 'A' both match column 'a'"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
@@ -0,0 +1,27 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string, c int)"
+
+# the last record ends in column b
+cat << EOF > /tmp/databend_test_fixed_width.txt
+insert into a(a,b,c) format FixedWidth
+  1alice0042
+ 22bob  0100
+333x
+EOF
+
+columns="format_fixed_width_columns=0:3,3:5,8:4:zero"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$columns&format_allow_short_records=1" --data-binary @/tmp/databend_test_fixed_width.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$columns" --data-binary @/tmp/databend_test_fixed_width.txt | grep -c "record of 4 bytes is shorter than the end 8 of column 1"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_fixed_width_columns=0:3,3:5" --data-binary @/tmp/databend_test_fixed_width.txt | grep -c "format_fixed_width_columns has 2 columns, but the table has 3"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_fixed_width_columns=0:3,3:5,8:4:one" --data-binary @/tmp/databend_test_fixed_width.txt | grep -c "bad fixed_width_columns '8:4:one'"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
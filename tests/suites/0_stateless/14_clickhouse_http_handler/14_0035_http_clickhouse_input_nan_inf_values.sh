@@ -0,0 +1,36 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b double, c int)"
+
+# the literals are matched case-insensitively, -1.#INF is the negative one
+cat << EOF > /tmp/databend_test_csv_nan_inf.txt
+insert into a(a,b,c) format CSV
+1,1.#QNAN,1
+2,1.#inf,2
+3,-1.#INF,3
+4,0.5,4
+EOF
+
+printf 'insert into a(a,b,c) format CSV\n5,1.5,1.#INF\n' > /tmp/databend_test_csv_nan_inf_int.txt
+printf 'insert into a(a,b,c) format CSV\n6,1.#IND,6\n' > /tmp/databend_test_csv_nan_inf_other.txt
+
+literals="format_nan_values=1.%23QNAN&format_inf_values=1.%23INF"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$literals" --data-binary @/tmp/databend_test_csv_nan_inf.txt
+echo "---nan"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select a, b from a where a = 1"
+echo "---others"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select a, b from a where a > 1 order by a"
+echo "---negative inf"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select a from a where a > 1 and b < 0 order by a"
+
+# integer columns do not take the literals, other strings are still errors
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$literals" --data-binary @/tmp/databend_test_csv_nan_inf_int.txt | grep -c "fail to decode column 2 'c'"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$literals" --data-binary @/tmp/databend_test_csv_nan_inf_other.txt | grep -c "fail to decode column 1 'b'"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
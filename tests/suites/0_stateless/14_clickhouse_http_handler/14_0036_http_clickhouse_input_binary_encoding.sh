@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string)"
+
+# "hello" and "world", quoted fields are decoded as well
+printf 'insert into a(a,b) format CSV\n1,68656c6c6f\n2,776f726c64\n' > /tmp/databend_test_csv_hex.txt
+printf 'insert into a(a,b) format CSV\n3,aGVsbG8=\n4,"d29ybGQ="\n' > /tmp/databend_test_csv_base64.txt
+printf 'insert into a(a,b) format CSV\n5,6865zz\n' > /tmp/databend_test_csv_hex_error.txt
+printf 'insert into a(a,b) format CSV\n6,aGVs*G8=\n' > /tmp/databend_test_csv_base64_error.txt
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_binary_encoding=hex" --data-binary @/tmp/databend_test_csv_hex.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_binary_encoding=base64" --data-binary @/tmp/databend_test_csv_base64.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_binary_encoding=hex" --data-binary @/tmp/databend_test_csv_hex_error.txt | grep -c "fail to decode column 1 'b' (string): invalid hex value"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_binary_encoding=base64" --data-binary @/tmp/databend_test_csv_base64_error.txt | grep -c "fail to decode column 1 'b' (string): invalid base64 value"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
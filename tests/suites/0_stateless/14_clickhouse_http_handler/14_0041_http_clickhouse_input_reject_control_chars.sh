@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string)"
+
+# 'all' does not reject tabs
+printf 'insert into a(a,b) format CSV\n1,a\tb\n2,plain\n' > /tmp/databend_test_csv_control_chars.txt
+printf 'insert into a(a,b) format CSV\n3,ab\001c\n' > /tmp/databend_test_csv_control_chars_error.txt
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_reject_control_chars=all" --data-binary @/tmp/databend_test_csv_control_chars.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_reject_control_chars=all" --data-binary @/tmp/databend_test_csv_control_chars_error.txt | grep -c "fail to decode column 1 'b' (string): control char 0x01 at byte 2 of the field"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_reject_control_chars=0,65" --data-binary @/tmp/databend_test_csv_control_chars_error.txt | grep -c "bad reject_control_chars '65'"
+
+# only the listed chars are rejected
+echo "---not listed"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_reject_control_chars=0,127" --data-binary @/tmp/databend_test_csv_control_chars_error.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select count(*) from a"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string)"
+
+# the header is skipped, the fields are still loaded by position
+printf 'insert into a(a,b) format CSV\na,b\n1,x\n' > /tmp/databend_test_csv_header_match.txt
+printf 'insert into a(a,b) format CSV\na,c\n2,y\n' > /tmp/databend_test_csv_header_mismatch.txt
+printf 'insert into a(a,b) format CSV\na,b,c\n3,z\n' > /tmp/databend_test_csv_header_count.txt
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_header_check=error" --data-binary @/tmp/databend_test_csv_header_match.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_header_check=warn" --data-binary @/tmp/databend_test_csv_header_mismatch.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_header_check=error" --data-binary @/tmp/databend_test_csv_header_mismatch.txt | grep -c ":1 header column 2 'c' does not match column 'b'"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_header_check=error" --data-binary @/tmp/databend_test_csv_header_count.txt | grep -c ":1 header has 3 columns"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b double, c string)"
+
+# only float columns take the decimal separator
+cat << EOF > /tmp/databend_test_csv_decimal_separator.txt
+insert into a(a,b,c) format CSV
+1,"1.234,56","1,5"
+2,"0,5",x
+3,7,y
+EOF
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_decimal_separator=%2C&format_numeric_group_separator=." --data-binary @/tmp/databend_test_csv_decimal_separator.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_decimal_separator=%2C&format_numeric_group_separator=%2C" --data-binary @/tmp/databend_test_csv_decimal_separator.txt | grep -c "numeric_group_separator should not be the decimal separator ','"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_decimal_separator=%2C%2C" --data-binary @/tmp/databend_test_csv_decimal_separator.txt | grep -c "decimal_separator can only be empty or contain one char"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string)"
+
+# only the names present in the header are compared
+printf 'insert into a(a,b) format CSV\na\n1,x\n' > /tmp/databend_test_csv_header_short.txt
+printf 'insert into a(a,b) format CSV\na,b,total\n2,y\n' > /tmp/databend_test_csv_header_long.txt
+printf 'insert into a(a,b) format CSV\nb\n3,z\n' > /tmp/databend_test_csv_header_wrong.txt
+
+check="format_header_check=error"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$check&format_lenient_header=1" --data-binary @/tmp/databend_test_csv_header_short.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$check&format_lenient_header=1" --data-binary @/tmp/databend_test_csv_header_long.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$check&format_lenient_header=1" --data-binary @/tmp/databend_test_csv_header_wrong.txt | grep -c "header column 1 'b' does not match column 'a'"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&$check" --data-binary @/tmp/databend_test_csv_header_short.txt | grep -c "header has 1 columns"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b int unsigned)"
+
+# -0 is also taken by the unsigned column
+printf 'insert into a(a,b) format CSV\n+5,-0\n' > /tmp/databend_test_csv_sign.txt
+printf 'insert into a(a,b) format CSV\n6,+7\n' > /tmp/databend_test_csv_sign_plus.txt
+printf 'insert into a(a,b) format CSV\n8,-0\n' > /tmp/databend_test_csv_sign_zero.txt
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" --data-binary @/tmp/databend_test_csv_sign.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select * from a order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_lenient_sign=0" --data-binary @/tmp/databend_test_csv_sign_plus.txt | grep -c "fail to decode column 1 'b' (uint32): sign of value +7 is not allowed"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_lenient_sign=0" --data-binary @/tmp/databend_test_csv_sign_zero.txt | grep -c "fail to decode column 1 'b' (uint32): sign of value -0 is not allowed"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
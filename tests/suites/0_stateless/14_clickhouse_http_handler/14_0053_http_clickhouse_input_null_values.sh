@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table if exists a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "create table a ( a int, b string null, c int null)"
+
+# a quoted "null" is a string
+cat << EOF > /tmp/databend_test_csv_null_values.txt
+insert into a(a,b,c) format CSV
+1,null,NA
+2,"null",3
+3,NULL,4
+4,x,na
+EOF
+
+printf 'insert into a(a,b,c) format CSV\n5,NULL,NA\n' > /tmp/databend_test_csv_null_values_case.txt
+printf 'insert into a(a,b,c) format CSV\n6,x,na\n' > /tmp/databend_test_csv_null_values_error.txt
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_null_values=null,NA" --data-binary @/tmp/databend_test_csv_null_values.txt
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_null_values=null,NA&format_null_values_case_sensitive=1" --data-binary @/tmp/databend_test_csv_null_values_case.txt
+echo "---strings"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select a, b from a where b is not null order by a"
+echo "---nulls"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select a from a where b is null order by a"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "select a from a where c is null order by a"
+
+echo "---errors"
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1&format_null_values=null,NA&format_null_values_case_sensitive=1" --data-binary @/tmp/databend_test_csv_null_values_error.txt | grep -c "fail to decode column 2 'c'"
+
+curl -s -u 'root:' -XPOST "http://localhost:${QUERY_CLICKHOUSE_HTTP_HANDLER_PORT}/?enable_planner_v2=1" -d "drop table a"
+rm /tmp/databend_test*.txt
@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+cat << EOF > /tmp/databend_test_collect_errors.txt
+1,x
+x,2
+3,z
+y,4
+EOF
+
+load() {
+	curl -s -H "insert_sql:insert into collect_errors format Csv" "$@" -u root: -F "upload=@/tmp/databend_test_collect_errors.txt" -XPUT "http://localhost:${QUERY_HTTP_HANDLER_PORT}/v1/streaming_load"
+}
+
+echo "drop table if exists collect_errors;" | $MYSQL_CLIENT_CONNECT
+echo "create table collect_errors ( a int, b string);" | $MYSQL_CLIENT_CONNECT
+
+# the malformed rows are skipped and listed in the response
+load -H "input_collect_errors:1" -H "input_max_error_rows:10" | grep -o '"row":[0-9]*,"column":"[a-z]*"'
+echo "select * from collect_errors order by a" | $MYSQL_CLIENT_CONNECT
+
+# only the first errors are kept, all the malformed rows are still skipped
+echo "truncate table collect_errors" | $MYSQL_CLIENT_CONNECT
+load -H "input_collect_errors:1" -H "input_max_error_rows:1" | grep -o '"row":[0-9]*,"column":"[a-z]*"'
+echo "select count(*) from collect_errors" | $MYSQL_CLIENT_CONNECT
+
+echo "---errors"
+load -H "input_collect_errors:1" | grep -c "input_collect_errors needs input_max_error_rows > 0"
+
+echo "drop table collect_errors;" | $MYSQL_CLIENT_CONNECT
+rm /tmp/databend_test_collect_errors.txt
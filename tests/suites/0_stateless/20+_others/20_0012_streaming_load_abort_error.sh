@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+cat << EOF > /tmp/databend_test_abort_error.txt
+1,x
+x,2
+EOF
+
+echo "drop table if exists abort_error;" | $MYSQL_CLIENT_CONNECT
+echo "create table abort_error ( a int, b string);" | $MYSQL_CLIENT_CONNECT
+
+# the location of the malformed value is returned in the fields of the error
+curl -s -H "insert_sql:insert into abort_error format Csv" -u root: -F "upload=@/tmp/databend_test_abort_error.txt" -XPUT "http://localhost:${QUERY_HTTP_HANDLER_PORT}/v1/streaming_load" > /tmp/databend_test_abort_error.out
+grep -o '"state":"[A-Z]*"' /tmp/databend_test_abort_error.out
+grep -o '"row":[0-9]*,"column":"[a-z]*"' /tmp/databend_test_abort_error.out
+grep -o '"raw":"[^"]*"' /tmp/databend_test_abort_error.out
+echo "select count(*) from abort_error" | $MYSQL_CLIENT_CONNECT
+
+echo "drop table abort_error;" | $MYSQL_CLIENT_CONNECT
+rm /tmp/databend_test_abort_error.txt /tmp/databend_test_abort_error.out
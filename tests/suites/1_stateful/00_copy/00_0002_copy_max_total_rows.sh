@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+echo "drop table if exists ontime_max_rows;" | $MYSQL_CLIENT_CONNECT
+echo "drop stage if exists s_max_rows" | $MYSQL_CLIENT_CONNECT
+
+cat $CURDIR/../ddl/ontime.sql | sed 's/ontime/ontime_max_rows/g' | $MYSQL_CLIENT_CONNECT
+
+echo "CREATE STAGE s_max_rows;" | $MYSQL_CLIENT_CONNECT
+aws --endpoint-url ${STORAGE_S3_ENDPOINT_URL} s3 cp s3://testbucket/admin/data/ontime_200.csv.gz s3://testbucket/admin/stage/s_max_rows/ontime_200.csv.gz >/dev/null 2>&1
+aws --endpoint-url ${STORAGE_S3_ENDPOINT_URL} s3 cp s3://testbucket/admin/data/ontime_200.csv.zst s3://testbucket/admin/stage/s_max_rows/ontime_200.csv.zst >/dev/null 2>&1
+
+# 199 rows in each file, the files are read in order and the cap is reached in the second
+copy="copy into ontime_max_rows from @s_max_rows FILES = ('ontime_200.csv.gz', 'ontime_200.csv.zst') FILE_FORMAT = (type = 'CSV' field_delimiter = ',' compression = 'auto' record_delimiter = '\n' skip_header = 1) force = true;"
+echo "set input_max_total_rows = 250; $copy" | $MYSQL_CLIENT_CONNECT
+echo "select count(1) from ontime_max_rows" | $MYSQL_CLIENT_CONNECT
+
+# within the cap, all rows are loaded
+echo "truncate table ontime_max_rows" | $MYSQL_CLIENT_CONNECT
+echo "set input_max_total_rows = 1000; $copy" | $MYSQL_CLIENT_CONNECT
+echo "select count(1) from ontime_max_rows" | $MYSQL_CLIENT_CONNECT
+
+echo "drop table ontime_max_rows" | $MYSQL_CLIENT_CONNECT
+echo "drop stage if exists s_max_rows" | $MYSQL_CLIENT_CONNECT
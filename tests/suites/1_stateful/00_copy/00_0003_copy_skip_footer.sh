@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+echo "drop table if exists skip_footer;" | $MYSQL_CLIENT_CONNECT
+echo "drop stage if exists s_skip_footer" | $MYSQL_CLIENT_CONNECT
+echo "create table skip_footer (a int, b string);" | $MYSQL_CLIENT_CONNECT
+echo "CREATE STAGE s_skip_footer;" | $MYSQL_CLIENT_CONNECT
+
+# 10 rows of 92 bytes, then 2 footer rows which fail to load as rows
+for i in $(seq 1 10); do
+	printf '%d\tname_%d\n' $i $i
+done > /tmp/databend_test_footer.tsv
+printf 'total\t10\nend\t0\n' >> /tmp/databend_test_footer.tsv
+echo '[{"a": 1, "b": "x"}, {"a": 2, "b": "y"}, {"a": "total", "b": "2"}]' > /tmp/databend_test_footer.json
+aws --endpoint-url ${STORAGE_S3_ENDPOINT_URL} s3 cp /tmp/databend_test_footer.tsv s3://testbucket/admin/stage/s_skip_footer/footer.tsv >/dev/null 2>&1
+aws --endpoint-url ${STORAGE_S3_ENDPOINT_URL} s3 cp /tmp/databend_test_footer.json s3://testbucket/admin/stage/s_skip_footer/footer.json >/dev/null 2>&1
+
+tsv="copy into skip_footer from @s_skip_footer FILES = ('footer.tsv') FILE_FORMAT = (type = 'TSV')"
+cases=(
+	# the footer spans several reads and batches
+	"set format_skip_footer = 2; set input_read_buffer_size = 10; $tsv force = true;"
+	# and the batches are merged
+	"set format_skip_footer = 2; set input_read_buffer_size = 10; set input_batch_target_rows = 3; $tsv force = true;"
+	# split at 45 and 90, the last split holds the 2 footer rows only
+	"set format_skip_footer = 2; $tsv split_size = 45 force = true;"
+	# JSON array
+	"set format_skip_footer = 1; copy into skip_footer from @s_skip_footer FILES = ('footer.json') FILE_FORMAT = (type = 'JSONARRAY') force = true;"
+)
+
+for i in "${cases[@]}"; do
+	echo "$i" | $MYSQL_CLIENT_CONNECT
+	echo "select count(1), sum(a) from skip_footer" | $MYSQL_CLIENT_CONNECT
+	echo "truncate table skip_footer" | $MYSQL_CLIENT_CONNECT
+done
+
+echo "drop table skip_footer" | $MYSQL_CLIENT_CONNECT
+echo "drop stage if exists s_skip_footer" | $MYSQL_CLIENT_CONNECT
+rm /tmp/databend_test_footer.*
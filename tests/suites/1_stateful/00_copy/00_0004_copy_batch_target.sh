@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+echo "drop table if exists batch_target;" | $MYSQL_CLIENT_CONNECT
+echo "drop stage if exists s_batch_target" | $MYSQL_CLIENT_CONNECT
+echo "create table batch_target (a int, b string);" | $MYSQL_CLIENT_CONNECT
+echo "CREATE STAGE s_batch_target;" | $MYSQL_CLIENT_CONNECT
+
+# 100 rows of 1084 bytes, read 10 bytes at a time
+for i in $(seq 1 100); do
+	printf '%d,name_%d\n' $i $i
+done > /tmp/databend_test_batch_target.csv
+aws --endpoint-url ${STORAGE_S3_ENDPOINT_URL} s3 cp /tmp/databend_test_batch_target.csv s3://testbucket/admin/stage/s_batch_target/batch_target.csv >/dev/null 2>&1
+
+csv="copy into batch_target from @s_batch_target FILES = ('batch_target.csv') FILE_FORMAT = (type = 'CSV') force = true;"
+cases=(
+	"set input_batch_target_rows = 7;"
+	"set input_batch_target_bytes = 100;"
+	# whichever is reached first
+	"set input_batch_target_rows = 7; set input_batch_target_bytes = 100;"
+	# larger than the file, a single batch at the end
+	"set input_batch_target_bytes = 1000000;"
+)
+
+for i in "${cases[@]}"; do
+	echo "set input_read_buffer_size = 10; $i $csv" | $MYSQL_CLIENT_CONNECT
+	echo "select count(1), sum(a), count(distinct b) from batch_target" | $MYSQL_CLIENT_CONNECT
+	echo "truncate table batch_target" | $MYSQL_CLIENT_CONNECT
+done
+
+echo "drop table batch_target" | $MYSQL_CLIENT_CONNECT
+echo "drop stage if exists s_batch_target" | $MYSQL_CLIENT_CONNECT
+rm /tmp/databend_test_batch_target.csv
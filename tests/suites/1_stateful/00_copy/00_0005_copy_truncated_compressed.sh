@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+echo "drop table if exists truncated_compressed;" | $MYSQL_CLIENT_CONNECT
+echo "drop stage if exists s_truncated_compressed" | $MYSQL_CLIENT_CONNECT
+echo "create table truncated_compressed (a int, b string);" | $MYSQL_CLIENT_CONNECT
+echo "CREATE STAGE s_truncated_compressed;" | $MYSQL_CLIENT_CONNECT
+
+for i in $(seq 1 10); do
+	printf '%d,name_%d\n' $i $i
+done | gzip -c > /tmp/databend_test_truncated.csv.gz
+# `lz4 -c` of the same rows
+printf '\x04\x22\x4d\x18\x64\x40\xa7\x45\x00\x00\x00\xa2\x31\x2c\x6e\x61\x6d\x65\x5f\x31\x0a\x32\x09\x00\x32\x32\x0a\x33\x09\x00\x32\x33\x0a\x34\x09\x00\x32\x34\x0a\x35\x09\x00\x32\x35\x0a\x36\x09\x00\x32\x36\x0a\x37\x09\x00\x32\x37\x0a\x38\x09\x00\x32\x38\x0a\x39\x09\x00\xd0\x39\x0a\x31\x30\x2c\x6e\x61\x6d\x65\x5f\x31\x30\x0a\x00\x00\x00\x00\xa8\xef\x90\x8b' > /tmp/databend_test_truncated.csv.lz4
+# without the end of the compressed data, i.e. the gzip trailer and the lz4 end mark and checksum
+for ext in gz lz4; do
+	head -c -8 /tmp/databend_test_truncated.csv.$ext > /tmp/databend_test_truncated_part.csv.$ext
+	aws --endpoint-url ${STORAGE_S3_ENDPOINT_URL} s3 cp /tmp/databend_test_truncated.csv.$ext s3://testbucket/admin/stage/s_truncated_compressed/full.csv.$ext >/dev/null 2>&1
+	aws --endpoint-url ${STORAGE_S3_ENDPOINT_URL} s3 cp /tmp/databend_test_truncated_part.csv.$ext s3://testbucket/admin/stage/s_truncated_compressed/part.csv.$ext >/dev/null 2>&1
+done
+
+for c in gzip:gz lz4:lz4; do
+	compression=${c%%:*}
+	ext=${c##*:}
+	echo "copy into truncated_compressed from @s_truncated_compressed FILES = ('full.csv.$ext') FILE_FORMAT = (type = 'CSV' compression = '$compression') force = true;" | $MYSQL_CLIENT_CONNECT
+	echo "select count(1), sum(a) from truncated_compressed" | $MYSQL_CLIENT_CONNECT
+	echo "truncate table truncated_compressed" | $MYSQL_CLIENT_CONNECT
+	# an error instead of a panic
+	echo "copy into truncated_compressed from @s_truncated_compressed FILES = ('part.csv.$ext') FILE_FORMAT = (type = 'CSV' compression = '$compression') force = true;" | $MYSQL_CLIENT_CONNECT 2>&1 | grep -c "truncated compressed file"
+done
+
+echo "drop table truncated_compressed" | $MYSQL_CLIENT_CONNECT
+echo "drop stage if exists s_truncated_compressed" | $MYSQL_CLIENT_CONNECT
+rm /tmp/databend_test_truncated*
@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+
+CURDIR=$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)
+. "$CURDIR"/../../../shell_env.sh
+
+echo "drop table if exists load_max_rows;" | $MYSQL_CLIENT_CONNECT
+echo "create table load_max_rows (a int, b string);" | $MYSQL_CLIENT_CONNECT
+
+seq 1 100 | awk '{print $1",name_"$1}' > /tmp/databend_test_max_rows_1.csv
+seq 101 200 | awk '{print $1",name_"$1}' > /tmp/databend_test_max_rows_2.csv
+
+# the rows 1 to 150 of the two files, small reads to align them in many batches
+for i in 1 2; do
+	curl -s -H "insert_sql:insert into load_max_rows format CSV" -H "input_max_total_rows: 150" -H "input_read_buffer_size: 64" \
+		-F "upload1=@/tmp/databend_test_max_rows_1.csv" -F "upload2=@/tmp/databend_test_max_rows_2.csv" \
+		-u root: -XPUT "http://localhost:${QUERY_HTTP_HANDLER_PORT}/v1/streaming_load" | grep -o '"state":"[A-Z]*"'
+done
+
+# both loads take the same rows
+echo "select count(), min(a), max(a), sum(a) from load_max_rows" | $MYSQL_CLIENT_CONNECT
+echo "select count() from (select a from load_max_rows group by a having count() != 2)" | $MYSQL_CLIENT_CONNECT
+
+echo "drop table load_max_rows;" | $MYSQL_CLIENT_CONNECT
+rm /tmp/databend_test_max_rows_*.csv